@@ -1,11 +1,21 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use dotenv::dotenv;
 use perty::{
-    modules::{pert::PertId, storage::PostgresDb},
+    modules::{
+        calendar::parse_weekdays,
+        delphi::{Aggregation, DEFAULT_THRESHOLD},
+        estimation_model::EstimationModel,
+        graph_report::GraphFormat,
+        pert::PertId,
+        simulation::{MonteCarlo, SamplingDistribution},
+        storage::open_storage,
+    },
     perty::Perty,
     perty_cli::{self, Output},
 };
 use std::env::{self, Args};
+use std::io::{self, IsTerminal};
 
 fn assert_no_rest(args: &mut Args) {
     let other = args.next();
@@ -16,11 +26,14 @@ fn assert_no_rest(args: &mut Args) {
 }
 
 fn main() -> Result<(), anyhow::Error> {
-    dotenv().expect("Unable to load environment variables");
-    println!("Hello, welcome to Perty!");
+    // the .env file is optional, the default storage needs no configuration
+    dotenv().ok();
+    // left out when the output is piped or redirected, to keep exports clean
+    if io::stdout().is_terminal() {
+        println!("Hello, welcome to Perty!");
+    }
 
-    let storage = PostgresDb::new()?;
-    let mut perty = Perty::new(Box::new(storage));
+    let mut perty = Perty::new(open_storage()?);
 
     let mut args = env::args();
     args.next();
@@ -48,8 +61,33 @@ fn main() -> Result<(), anyhow::Error> {
             }
         }
         "list" => {
+            let output = match args.next().as_deref() {
+                None => Output::Console,
+                Some("--json") => Output::JSON,
+                Some(format) => panic!("Unknown format {}", format),
+            };
+            perty_cli::list_perts(&mut perty, output)?;
+        }
+        "accuracy" => {
+            let output = match args.next().as_deref() {
+                None => Output::Console,
+                Some("--json") => Output::JSON,
+                Some(format) => panic!("Unknown format {}", format),
+            };
+            perty_cli::get_accuracy_overview(perty, output)?;
+        }
+        "check" => {
             assert_no_rest(&mut args);
-            perty_cli::list_perts(&mut perty)?;
+            perty_cli::check(&mut perty)?;
+        }
+        "db" => {
+            let command = args.next().expect("missing db command (init, migrate)");
+            assert_no_rest(&mut args);
+            match command.as_str() {
+                "init" => perty_cli::init_database(&mut perty)?,
+                "migrate" => perty_cli::migrate_database(&mut perty)?,
+                _ => panic!("Unknown db command {}", command),
+            }
         }
         "get" => {
             let resource = args.next().expect("missing resource to create in command");
@@ -57,26 +95,147 @@ fn main() -> Result<(), anyhow::Error> {
             match args.next().expect("unknown command").as_str() {
                 "pert" => {
                     let mut output = Output::Console;
-                    if let Some(format) = args.next() {
-                        output = match format.as_str() {
-                            "--html" => Output::HTML,
-                            "--csv" => Output::CSV,
-                            _ => panic!("Unknown format {}", format),
+                    let mut tag = None;
+                    let mut flags = args.by_ref().peekable();
+                    while let Some(flag) = flags.next() {
+                        match flag.as_str() {
+                            "--tag" => tag = Some(flags.next().expect("Missing value for --tag")),
+                            "--html" => output = Output::HTML,
+                            "--csv" => output = Output::CSV,
+                            "--svg" => output = Output::SVG,
+                            "--json" => output = Output::JSON,
+                            "--markdown" => {
+                                let file_name = flags.next_if(|arg| !arg.starts_with("--"));
+                                output = Output::Markdown(file_name);
+                            }
+                            _ => panic!("Unknown format {}", flag),
                         }
                     }
-                    perty_cli::get_pert(perty, pert_id, output)?;
+                    perty_cli::get_pert(perty, pert_id, output, tag)?;
+                }
+                "activities" => {
+                    let tag = match args.next().as_deref() {
+                        None => None,
+                        Some("--tag") => Some(args.next().expect("Missing value for --tag")),
+                        Some(flag) => panic!("Unknown flag {}", flag),
+                    };
+                    assert_no_rest(&mut args);
+                    perty_cli::list_activities(perty, pert_id, tag)?;
                 }
                 "roadmap" => {
                     let mut output = Output::Console;
-                    if let Some(format) = args.next() {
-                        output = match format.as_str() {
-                            "--html" => Output::HTML,
-                            "--csv" => Output::CSV,
-                            _ => panic!("Unknown format {}", format),
+                    let mut flags = args.by_ref().peekable();
+                    while let Some(flag) = flags.next() {
+                        match flag.as_str() {
+                            "--html" => output = Output::HTML,
+                            "--csv" => output = Output::CSV,
+                            "--svg" => output = Output::SVG,
+                            "--json" => output = Output::JSON,
+                            "--markdown" => {
+                                let file_name = flags.next_if(|arg| !arg.starts_with("--"));
+                                output = Output::Markdown(file_name);
+                            }
+                            _ => panic!("Unknown format {}", flag),
                         }
                     }
                     perty_cli::get_roadmap(perty, pert_id, output)?;
                 }
+                "probability" => {
+                    let mut deadline: Option<f64> = None;
+                    let mut confidence = 95f64;
+                    while let Some(flag) = args.next() {
+                        let value = args.next().expect("Missing value for flag");
+                        match flag.as_str() {
+                            "--deadline" => deadline = Some(value.parse()?),
+                            "--confidence" => confidence = value.parse()?,
+                            _ => panic!("Unknown flag {}", flag),
+                        }
+                    }
+                    let deadline = deadline.expect("Missing --deadline argument");
+                    perty_cli::get_probability(perty, pert_id, deadline, confidence)?;
+                }
+                "simulate" => {
+                    let mut simulation = MonteCarlo::default();
+                    while let Some(flag) = args.next() {
+                        match flag.as_str() {
+                            "--iterations" => {
+                                let value = args.next().expect("Missing value for --iterations");
+                                simulation.iterations = value.parse()?;
+                            }
+                            "--seed" => {
+                                let value = args.next().expect("Missing value for --seed");
+                                simulation.seed = Some(value.parse()?);
+                            }
+                            "--triangular" => {
+                                simulation.distribution = SamplingDistribution::Triangular;
+                            }
+                            _ => panic!("Unknown flag {}", flag),
+                        }
+                    }
+                    perty_cli::simulate(perty, pert_id, simulation)?;
+                }
+                "graph" => {
+                    let mut format = GraphFormat::Dot;
+                    let mut clustered = false;
+                    for flag in args.by_ref() {
+                        match flag.as_str() {
+                            "--dot" => format = GraphFormat::Dot,
+                            "--mermaid" => format = GraphFormat::Mermaid,
+                            "--phases" => clustered = true,
+                            _ => panic!("Unknown flag {}", flag),
+                        }
+                    }
+                    perty_cli::get_graph(perty, pert_id, format, clustered)?;
+                }
+                "critical-path" => {
+                    assert_no_rest(&mut args);
+                    perty_cli::get_critical_path(perty, pert_id)?;
+                }
+                "accuracy" => {
+                    let output = match args.next().as_deref() {
+                        None => Output::Console,
+                        Some("--json") => Output::JSON,
+                        Some(format) => panic!("Unknown format {}", format),
+                    };
+                    perty_cli::get_accuracy(perty, pert_id, output)?;
+                }
+                "delphi" => {
+                    let mut threshold = DEFAULT_THRESHOLD;
+                    let mut output = Output::Console;
+                    while let Some(flag) = args.next() {
+                        match flag.as_str() {
+                            "--threshold" => {
+                                let value = args.next().expect("Missing value for --threshold");
+                                threshold = value.trim_end_matches('%').parse::<f64>()? / 100f64;
+                            }
+                            "--json" => output = Output::JSON,
+                            _ => panic!("Unknown flag {}", flag),
+                        }
+                    }
+                    perty_cli::get_delphi(perty, pert_id, threshold, output)?;
+                }
+                "baselines" => {
+                    assert_no_rest(&mut args);
+                    perty_cli::list_baselines(perty, pert_id)?;
+                }
+                "diff" => {
+                    let mut names = vec![];
+                    let mut output = Output::Console;
+                    for arg in args.by_ref() {
+                        match arg.as_str() {
+                            "--html" => output = Output::HTML,
+                            "--json" => output = Output::JSON,
+                            flag if flag.starts_with("--") => panic!("Unknown format {}", flag),
+                            _ => names.push(arg),
+                        }
+                    }
+                    let (from, to) = match names.as_slice() {
+                        [from] => (from, None),
+                        [from, to] => (from, Some(to.as_str())),
+                        _ => panic!("Expected one or two baseline names"),
+                    };
+                    perty_cli::get_baseline_diff(perty, pert_id, from, to, output)?;
+                }
                 _ => panic!("Unknown command"),
             }
         }
@@ -98,12 +257,86 @@ fn main() -> Result<(), anyhow::Error> {
                         "dependency" => {
                             perty_cli::add_dependency(perty, pert_id)?;
                         }
+                        "estimate" => {
+                            perty_cli::add_estimate(perty, pert_id)?;
+                        }
+                        "baseline" => {
+                            let name = args.next().expect("Missing baseline name");
+                            assert_no_rest(&mut args);
+                            perty_cli::add_baseline(perty, pert_id, &name)?;
+                        }
                         _ => panic!("Unknown resource to add {}", what_add),
                     }
                 }
+                "update" => {
+                    let what_update = args.next().expect("Missing resource to update in command");
+                    match what_update.as_str() {
+                        "activity" => perty_cli::update_activity(perty, pert_id)?,
+                        "actual" => perty_cli::update_actual(perty, pert_id)?,
+                        "status" => perty_cli::update_status(perty, pert_id)?,
+                        "tags" => perty_cli::update_tags(perty, pert_id)?,
+                        _ => panic!("Unknown resource to update {}", what_update),
+                    }
+                }
+                "remove" => {
+                    let what_remove = args.next().expect("Missing resource to remove in command");
+                    match what_remove.as_str() {
+                        "activity" => perty_cli::remove_activity(perty, pert_id)?,
+                        "dependency" => perty_cli::remove_dependency(perty, pert_id)?,
+                        _ => panic!("Unknown resource to remove {}", what_remove),
+                    }
+                }
+                "rename" => {
+                    assert_no_rest(&mut args);
+                    perty_cli::rename_pert(perty, pert_id)?;
+                }
+                "set" => {
+                    let what_set = args.next().expect("Missing property to set in command");
+                    match what_set.as_str() {
+                        "model" => {
+                            let name = args.next().expect("Missing estimation model");
+                            let lambda = match args.next() {
+                                Some(lambda) => Some(lambda.parse()?),
+                                None => None,
+                            };
+                            let estimation_model = EstimationModel::from_name(&name, lambda)?;
+                            perty_cli::set_estimation_model(perty, pert_id, estimation_model)?;
+                        }
+                        "aggregation" => {
+                            let name = args.next().expect("Missing aggregation");
+                            assert_no_rest(&mut args);
+                            let aggregation = Aggregation::from_name(&name)?;
+                            perty_cli::set_aggregation(perty, pert_id, aggregation)?;
+                        }
+                        "hours-per-day" => {
+                            let hours_per_day =
+                                args.next().expect("Missing hours per day").parse()?;
+                            perty_cli::set_hours_per_day(perty, pert_id, hours_per_day)?;
+                        }
+                        "start-date" => {
+                            let start_date = args.next().expect("Missing start date");
+                            let start_date = match start_date.as_str() {
+                                "none" => None,
+                                date => Some(NaiveDate::parse_from_str(date, "%Y-%m-%d")?),
+                            };
+                            perty_cli::set_start_date(perty, pert_id, start_date)?;
+                        }
+                        "working-days" => {
+                            let working_days =
+                                parse_weekdays(&args.next().expect("Missing working days"))?;
+                            perty_cli::set_working_days(perty, pert_id, working_days)?;
+                        }
+                        _ => panic!("Unknown property to set {}", what_set),
+                    }
+                }
                 _ => panic!("unknown operation {}", operation),
             }
         }
+        "delete" => {
+            let pert_id: PertId = args.next().expect("missing PERT to delete").parse()?;
+            assert_no_rest(&mut args);
+            perty_cli::delete_pert(perty, pert_id)?;
+        }
         _ => panic!("invalid operation {}", operation),
     }
     Ok(())
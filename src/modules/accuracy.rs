@@ -0,0 +1,162 @@
+use serde::Serialize;
+
+use super::activity::{Activity, ActivityId};
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct ActivityAccuracy {
+    pub id: ActivityId,
+    pub name: String,
+    pub optimistic: f64,
+    pub probable: f64,
+    pub pessimistic: f64,
+    /// Mean given by the estimation model.
+    pub estimated: f64,
+    pub actual: f64,
+}
+
+impl ActivityAccuracy {
+    /// Positive when the activity took longer than estimated.
+    pub fn error(&self) -> f64 {
+        self.actual - self.estimated
+    }
+
+    pub fn is_within_range(&self) -> bool {
+        self.optimistic <= self.actual && self.actual <= self.pessimistic
+    }
+}
+
+/// Actual efforts compared with their three-point estimates. Only activities with an
+/// actual effort are measured.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Accuracy {
+    pub activities: Vec<ActivityAccuracy>,
+    pub below_optimistic: usize,
+    pub within_range: usize,
+    pub above_pessimistic: usize,
+    /// Mean of actual - estimated, positive when activities take longer than planned.
+    pub mean_bias: Option<f64>,
+    /// Mean of actual - most probable.
+    pub probable_bias: Option<f64>,
+    /// Mean absolute percentage error of the estimated mean, activities that took no
+    /// effort are left out as their error cannot be expressed as a percentage.
+    pub mape: Option<f64>,
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0f64, 0usize), |(sum, count), value| {
+        (sum + value, count + 1)
+    });
+    match count {
+        0 => None,
+        count => Some(sum / count as f64),
+    }
+}
+
+impl Accuracy {
+    pub fn new(activities: &[Activity]) -> Self {
+        Self::from_measures(
+            activities
+                .iter()
+                .filter_map(|act| {
+                    act.actual.effort.map(|actual| ActivityAccuracy {
+                        id: act.id,
+                        name: act.name.to_owned(),
+                        optimistic: act.estimation.optimistic,
+                        probable: act.estimation.probable,
+                        pessimistic: act.estimation.pessimistic,
+                        estimated: act.estimated(),
+                        actual,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Accuracy of activities already measured, e.g. the ones of several PERTs.
+    pub fn from_measures(activities: Vec<ActivityAccuracy>) -> Self {
+        Self {
+            below_optimistic: activities
+                .iter()
+                .filter(|act| act.actual < act.optimistic)
+                .count(),
+            within_range: activities
+                .iter()
+                .filter(|act| act.is_within_range())
+                .count(),
+            above_pessimistic: activities
+                .iter()
+                .filter(|act| act.actual > act.pessimistic)
+                .count(),
+            mean_bias: mean(activities.iter().map(|act| act.error())),
+            probable_bias: mean(activities.iter().map(|act| act.actual - act.probable)),
+            mape: mean(
+                activities
+                    .iter()
+                    .filter(|act| act.actual > 0f64)
+                    .map(|act| (act.error() / act.actual).abs() * 100f64),
+            ),
+            activities,
+        }
+    }
+
+    pub fn measured(&self) -> usize {
+        self.activities.len()
+    }
+
+    /// Share of the measured activities whose actual effort is within [optimistic, pessimistic].
+    pub fn within_range_ratio(&self) -> Option<f64> {
+        match self.measured() {
+            0 => None,
+            measured => Some(self.within_range as f64 / measured as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Accuracy;
+    use crate::modules::activity::Activity;
+
+    fn activity(id: i32, estimate: (f64, f64, f64), actual: Option<f64>) -> Activity {
+        let (optimistic, probable, pessimistic) = estimate;
+        let mut activity = Activity::new(
+            id,
+            format!("activity {}", id),
+            optimistic,
+            probable,
+            pessimistic,
+        )
+        .unwrap();
+        activity.actual.effort = actual;
+        activity
+    }
+
+    #[test]
+    fn accuracy_of_actuals() {
+        let accuracy = Accuracy::new(&[
+            activity(1, (1.0, 2.0, 3.0), Some(2.0)),
+            activity(2, (2.0, 4.0, 6.0), Some(8.0)),
+            activity(3, (1.0, 1.0, 1.0), Some(0.5)),
+            activity(4, (1.0, 2.0, 3.0), None),
+        ]);
+        assert_eq!(accuracy.measured(), 3);
+        assert_eq!(accuracy.below_optimistic, 1);
+        assert_eq!(accuracy.within_range, 1);
+        assert_eq!(accuracy.above_pessimistic, 1);
+        assert_eq!(accuracy.within_range_ratio(), Some(1f64 / 3f64));
+        // errors are 0, +4 and -0.5
+        assert_eq!(accuracy.mean_bias, Some(3.5 / 3f64));
+        assert_eq!(accuracy.probable_bias, Some(3.5 / 3f64));
+        // 0%, 50% and 100%
+        assert_eq!(accuracy.mape, Some(50.0));
+    }
+
+    #[test]
+    fn nothing_measured() {
+        let accuracy = Accuracy::new(&[activity(1, (1.0, 2.0, 3.0), None)]);
+        assert_eq!(accuracy.measured(), 0);
+        assert_eq!(accuracy.within_range_ratio(), None);
+        assert_eq!(accuracy.mean_bias, None);
+        assert_eq!(accuracy.mape, None);
+    }
+}
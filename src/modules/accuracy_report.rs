@@ -0,0 +1,307 @@
+use anyhow::Result;
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    accuracy::{Accuracy, ActivityAccuracy},
+    activity::ActivityId,
+    duration::DurationUnit,
+    json_report::{to_json, PertJson},
+    pert::{Pert, PertId},
+    pert_report::pert_header,
+    report::round,
+};
+
+fn describe_bias(bias: Option<f64>, unit: Option<DurationUnit>) -> String {
+    match (bias, unit) {
+        (Some(bias), Some(unit)) => format!("{:+} {}", round(bias), unit),
+        _ => "-".to_string(),
+    }
+}
+
+fn describe_percent(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |value| format!("{}%", round(value)))
+}
+
+fn describe_within_range(accuracy: &Accuracy) -> String {
+    match accuracy.within_range_ratio() {
+        Some(ratio) => format!(
+            "{} of {} ({}%)",
+            accuracy.within_range,
+            accuracy.measured(),
+            round(ratio * 100f64)
+        ),
+        None => "-".to_string(),
+    }
+}
+
+/// Summary lines of an accuracy, biases are left out when `unit` is `None`.
+fn summary(accuracy: &Accuracy, unit: Option<DurationUnit>) -> String {
+    format!(
+        "WITHIN [OPTIMISTIC, PESSIMISTIC]: {}\nBELOW OPTIMISTIC: {}\nABOVE PESSIMISTIC: {}\nMEAN BIAS: {}\nBIAS FROM MOST PROBABLE: {}\nMAPE: {}",
+        describe_within_range(accuracy),
+        accuracy.below_optimistic,
+        accuracy.above_pessimistic,
+        describe_bias(accuracy.mean_bias, unit),
+        describe_bias(accuracy.probable_bias, unit),
+        describe_percent(accuracy.mape)
+    )
+}
+
+#[derive(Table)]
+struct ActivityAccuracyRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Optimistic")]
+    optimistic: f64,
+    #[table(title = "Probable")]
+    probable: f64,
+    #[table(title = "Pessimistic")]
+    pessimistic: f64,
+    #[table(title = "Pert")]
+    estimated: f64,
+    #[table(title = "Actual")]
+    actual: f64,
+    #[table(title = "Error", justify = "Justify::Right")]
+    error: String,
+    #[table(title = "Within range")]
+    within_range: bool,
+}
+
+impl From<&ActivityAccuracy> for ActivityAccuracyRow {
+    fn from(act: &ActivityAccuracy) -> Self {
+        Self {
+            id: act.id,
+            name: act.name.to_owned(),
+            optimistic: act.optimistic,
+            probable: act.probable,
+            pessimistic: act.pessimistic,
+            estimated: round(act.estimated),
+            actual: act.actual,
+            error: format!("{:+}", round(act.error())),
+            within_range: act.is_within_range(),
+        }
+    }
+}
+
+pub struct AccuracyReport {
+    pert: Pert,
+    accuracy: Accuracy,
+}
+
+impl AccuracyReport {
+    pub fn new(pert: Pert, accuracy: Accuracy) -> Self {
+        Self { pert, accuracy }
+    }
+
+    pub fn ascii(&mut self) -> String {
+        if self.accuracy.measured() == 0 {
+            return format!(
+                "{}\n\nNo actual effort recorded yet",
+                pert_header(&self.pert)
+            );
+        }
+        let rows: Vec<ActivityAccuracyRow> = self
+            .accuracy
+            .activities
+            .iter()
+            .map(|act| act.into())
+            .collect();
+
+        format!(
+            "{}\n\n{}\n{}",
+            pert_header(&self.pert),
+            rows.with_title().display().unwrap(),
+            summary(&self.accuracy, Some(self.pert.unit))
+        )
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct AccuracyJsonReport<'a> {
+            pert: PertJson,
+            #[serde(flatten)]
+            accuracy: &'a Accuracy,
+            within_range_ratio: Option<f64>,
+        }
+
+        to_json(
+            "accuracy",
+            AccuracyJsonReport {
+                pert: (&self.pert).into(),
+                accuracy: &self.accuracy,
+                within_range_ratio: self.accuracy.within_range_ratio(),
+            },
+        )
+    }
+}
+
+#[derive(Table)]
+struct PertAccuracyRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: PertId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Measured", justify = "Justify::Right")]
+    measured: usize,
+    #[table(title = "Within range")]
+    within_range: String,
+    #[table(title = "Mean bias", justify = "Justify::Right")]
+    mean_bias: String,
+    #[table(title = "MAPE", justify = "Justify::Right")]
+    mape: String,
+}
+
+/// Accuracy of every PERT and of all their activities together.
+pub struct AccuracyOverviewReport {
+    perts: Vec<(Pert, Accuracy)>,
+    overall: Accuracy,
+}
+
+impl AccuracyOverviewReport {
+    pub fn new(perts: Vec<(Pert, Accuracy)>) -> Self {
+        let overall = Accuracy::from_measures(
+            perts
+                .iter()
+                .flat_map(|(_, accuracy)| accuracy.activities.iter().cloned())
+                .collect(),
+        );
+        Self { perts, overall }
+    }
+
+    /// Biases add durations, they are only summed up when the PERTs share a unit.
+    fn common_unit(&self) -> Option<DurationUnit> {
+        let mut units = self
+            .perts
+            .iter()
+            .filter(|(_, accuracy)| accuracy.measured() > 0)
+            .map(|(pert, _)| pert.unit);
+        let first = units.next()?;
+        units.all(|unit| unit == first).then_some(first)
+    }
+
+    pub fn ascii(&mut self) -> String {
+        let rows: Vec<PertAccuracyRow> = self
+            .perts
+            .iter()
+            .map(|(pert, accuracy)| PertAccuracyRow {
+                id: pert.id,
+                name: pert.name.to_owned(),
+                measured: accuracy.measured(),
+                within_range: describe_within_range(accuracy),
+                mean_bias: describe_bias(accuracy.mean_bias, Some(pert.unit)),
+                mape: describe_percent(accuracy.mape),
+            })
+            .collect();
+
+        format!(
+            "{}\nALL PERTS\nMEASURED: {}\n{}",
+            rows.with_title().display().unwrap(),
+            self.overall.measured(),
+            summary(&self.overall, self.common_unit())
+        )
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct PertAccuracyJson<'a> {
+            pert: PertJson,
+            #[serde(flatten)]
+            accuracy: &'a Accuracy,
+            within_range_ratio: Option<f64>,
+        }
+
+        #[derive(Serialize)]
+        struct OverallJson {
+            measured: usize,
+            below_optimistic: usize,
+            within_range: usize,
+            above_pessimistic: usize,
+            within_range_ratio: Option<f64>,
+            mean_bias: Option<f64>,
+            probable_bias: Option<f64>,
+            mape: Option<f64>,
+        }
+
+        #[derive(Serialize)]
+        struct AccuracyOverviewJson<'a> {
+            perts: Vec<PertAccuracyJson<'a>>,
+            overall: OverallJson,
+        }
+
+        let unit = self.common_unit();
+        let overall = &self.overall;
+        to_json(
+            "accuracy_overview",
+            AccuracyOverviewJson {
+                perts: self
+                    .perts
+                    .iter()
+                    .map(|(pert, accuracy)| PertAccuracyJson {
+                        pert: pert.into(),
+                        accuracy,
+                        within_range_ratio: accuracy.within_range_ratio(),
+                    })
+                    .collect(),
+                overall: OverallJson {
+                    measured: overall.measured(),
+                    below_optimistic: overall.below_optimistic,
+                    within_range: overall.within_range,
+                    above_pessimistic: overall.above_pessimistic,
+                    within_range_ratio: overall.within_range_ratio(),
+                    mean_bias: overall.mean_bias.filter(|_| unit.is_some()),
+                    probable_bias: overall.probable_bias.filter(|_| unit.is_some()),
+                    mape: overall.mape,
+                },
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccuracyOverviewReport, AccuracyReport};
+    use crate::modules::{
+        accuracy::Accuracy, activity::Activity, duration::DurationUnit, pert::Pert,
+    };
+
+    fn activities() -> Vec<Activity> {
+        [
+            (1, "design", 1.0, 2.0, 3.0, Some(2.0)),
+            (2, "build", 2.0, 4.0, 6.0, Some(8.0)),
+            (3, "test", 1.0, 2.0, 3.0, None),
+        ]
+        .into_iter()
+        .map(|(id, name, optimistic, probable, pessimistic, effort)| {
+            let mut activity =
+                Activity::new(id, name.to_string(), optimistic, probable, pessimistic).unwrap();
+            activity.actual.effort = effort;
+            activity
+        })
+        .collect()
+    }
+
+    #[test]
+    fn pert_accuracy() {
+        let pert = Pert::new(1, "example".to_string());
+        let mut report = AccuracyReport::new(pert, Accuracy::new(&activities()));
+        insta::assert_display_snapshot!(report.ascii());
+    }
+
+    #[test]
+    fn overview() {
+        let first = Pert::new(1, "first".to_string());
+        let mut second = Pert::new(2, "second".to_string());
+        second.unit = DurationUnit::Points;
+        let mut report = AccuracyOverviewReport::new(vec![
+            (first, Accuracy::new(&activities())),
+            (second, Accuracy::new(&activities())),
+        ]);
+        // days and points cannot be added
+        assert!(report.common_unit().is_none());
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.json().unwrap());
+    }
+}
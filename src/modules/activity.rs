@@ -1,19 +1,232 @@
+use chrono::NaiveDate;
+use std::fmt;
+
+use super::estimation_model::EstimationModel;
+
 pub type ActivityId = i32;
-pub type EstimationValue = i32;
+pub type EstimationValue = f64;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Estimation {
     pub optimistic: EstimationValue,
     pub probable: EstimationValue,
     pub pessimistic: EstimationValue,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum EstimationError {
+    NotFinite,
+    Negative,
+    /// The values are not ordered as optimistic <= probable <= pessimistic.
+    Unordered,
+}
+
+impl fmt::Display for EstimationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstimationError::NotFinite => write!(f, "Estimation values must be finite numbers"),
+            EstimationError::Negative => write!(f, "Estimation values cannot be negative"),
+            EstimationError::Unordered => write!(
+                f,
+                "Estimation values must be optimistic <= most probable <= pessimistic"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EstimationError {}
+
 impl Estimation {
+    pub fn new(
+        optimistic: EstimationValue,
+        probable: EstimationValue,
+        pessimistic: EstimationValue,
+    ) -> Result<Self, EstimationError> {
+        let estimation = Self {
+            optimistic,
+            probable,
+            pessimistic,
+        };
+        estimation.validate()?;
+        Ok(estimation)
+    }
+
+    pub fn validate(&self) -> Result<(), EstimationError> {
+        let values = [self.optimistic, self.probable, self.pessimistic];
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(EstimationError::NotFinite);
+        }
+        if values.iter().any(|value| *value < 0f64) {
+            return Err(EstimationError::Negative);
+        }
+        if self.optimistic > self.probable || self.probable > self.pessimistic {
+            return Err(EstimationError::Unordered);
+        }
+        Ok(())
+    }
+
     pub fn estimated(&self) -> f64 {
-        let tm = f64::from(self.probable);
-        let to = f64::from(self.optimistic);
-        let tp = f64::from(self.pessimistic);
-        1f64 / 6f64 * (4f64 * tm + to + tp)
+        1f64 / 6f64 * (4f64 * self.probable + self.optimistic + self.pessimistic)
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.standard_deviation().powi(2)
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        (self.pessimistic - self.optimistic) / 6f64
+    }
+}
+
+/// What really happened, recorded while the activity is done or afterwards.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Actual {
+    pub start: Option<NaiveDate>,
+    pub finish: Option<NaiveDate>,
+    /// Effort spent, in the unit of the PERT.
+    pub effort: Option<EstimationValue>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ActualError {
+    InvalidEffort,
+    FinishBeforeStart,
+}
+
+impl fmt::Display for ActualError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActualError::InvalidEffort => {
+                write!(f, "The actual effort must be a finite number, not negative")
+            }
+            ActualError::FinishBeforeStart => {
+                write!(f, "The actual finish cannot be before the actual start")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActualError {}
+
+impl Actual {
+    pub fn validate(&self) -> Result<(), ActualError> {
+        if let Some(effort) = self.effort {
+            if !effort.is_finite() || effort < 0f64 {
+                return Err(ActualError::InvalidEffort);
+            }
+        }
+        if let (Some(start), Some(finish)) = (self.start, self.finish) {
+            if finish < start {
+                return Err(ActualError::FinishBeforeStart);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Status {
+    #[default]
+    NotStarted,
+    InProgress,
+    Done,
+    Blocked,
+}
+
+impl Status {
+    pub const ALL: [Status; 4] = [
+        Status::NotStarted,
+        Status::InProgress,
+        Status::Done,
+        Status::Blocked,
+    ];
+
+    pub fn from_name(name: &str) -> anyhow::Result<Self> {
+        match Self::ALL.into_iter().find(|status| status.name() == name) {
+            Some(status) => Ok(status),
+            None => anyhow::bail!("Unknown status {}", name),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::NotStarted => "not-started",
+            Status::InProgress => "in-progress",
+            Status::Done => "done",
+            Status::Blocked => "blocked",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::NotStarted => write!(f, "not started"),
+            Status::InProgress => write!(f, "in progress"),
+            Status::Done => write!(f, "done"),
+            Status::Blocked => write!(f, "blocked"),
+        }
+    }
+}
+
+/// Where an activity stands, the percent complete is the share of its estimate already done.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Progress {
+    pub status: Status,
+    pub percent_complete: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProgressError {
+    InvalidPercent,
+    /// A done activity is 100% complete, one not started is 0% complete.
+    Inconsistent,
+}
+
+impl fmt::Display for ProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressError::InvalidPercent => {
+                write!(f, "The percent complete must be between 0 and 100")
+            }
+            ProgressError::Inconsistent => write!(
+                f,
+                "A done activity is 100% complete and one not started is 0% complete"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProgressError {}
+
+impl Progress {
+    pub fn new(status: Status, percent_complete: f64) -> Result<Self, ProgressError> {
+        let progress = Self {
+            status,
+            percent_complete,
+        };
+        progress.validate()?;
+        Ok(progress)
+    }
+
+    pub fn validate(&self) -> Result<(), ProgressError> {
+        if !(0f64..=100f64).contains(&self.percent_complete) {
+            return Err(ProgressError::InvalidPercent);
+        }
+        match self.status {
+            Status::Done if self.percent_complete != 100f64 => Err(ProgressError::Inconsistent),
+            Status::NotStarted if self.percent_complete != 0f64 => Err(ProgressError::Inconsistent),
+            _ => Ok(()),
+        }
+    }
+
+    /// Share of the estimate still to be done, between 0 and 1.
+    pub fn remaining_ratio(&self) -> f64 {
+        1f64 - self.percent_complete / 100f64
     }
 }
 
@@ -22,39 +235,213 @@ pub struct Activity {
     pub id: ActivityId,
     pub name: String,
     pub estimation: Estimation,
+    /// Estimation model of the PERT the activity belongs to.
+    pub model: EstimationModel,
+    pub actual: Actual,
+    pub progress: Progress,
+    /// Sorted, without duplicates.
+    pub tags: Vec<String>,
 }
 
 impl Activity {
+    /// Refused when the estimation is not valid, see `Estimation::new`.
     pub fn new(
         id: ActivityId,
         name: String,
         optimistic: EstimationValue,
         probable: EstimationValue,
         pessimistic: EstimationValue,
-    ) -> Self {
+    ) -> Result<Self, EstimationError> {
+        Ok(Self::from_storage(
+            id,
+            name,
+            Estimation::new(optimistic, probable, pessimistic)?,
+        ))
+    }
+
+    /// As stored, the estimation is not validated: activities saved before the
+    /// validation can still be read and fixed, `check` reports them.
+    pub fn from_storage(id: ActivityId, name: String, estimation: Estimation) -> Self {
         Self {
             id,
             name,
+            estimation,
+            model: EstimationModel::default(),
+            actual: Actual::default(),
+            progress: Progress::default(),
+            tags: vec![],
+        }
+    }
+
+    pub fn estimated(&self) -> f64 {
+        self.model.mean(&self.estimation)
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.model.variance(&self.estimation)
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// The unfinished portion of the activity: its three values scaled by the share left.
+    pub fn remaining(&self) -> Activity {
+        let ratio = self.progress.remaining_ratio();
+        Self {
+            id: self.id,
+            name: self.name.to_owned(),
             estimation: Estimation {
-                optimistic,
-                probable,
-                pessimistic,
+                optimistic: self.estimation.optimistic * ratio,
+                probable: self.estimation.probable * ratio,
+                pessimistic: self.estimation.pessimistic * ratio,
             },
+            model: self.model,
+            actual: self.actual.clone(),
+            progress: self.progress,
+            tags: self.tags.clone(),
         }
     }
 
-    pub fn estimated(&self) -> f64 {
-        self.estimation.estimated()
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own == tag)
     }
 }
 
+/// Tags separated by commas, sorted and without duplicates. A tag cannot contain a
+/// comma, they are stored joined by commas.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = input
+        .split(',')
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_string())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+pub fn validate_tags(tags: &[String]) -> anyhow::Result<()> {
+    for tag in tags {
+        if tag.trim().is_empty() || tag.trim() != tag {
+            anyhow::bail!("Invalid tag \"{}\"", tag);
+        }
+        if tag.contains(',') {
+            anyhow::bail!("The tag \"{}\" cannot contain a comma", tag);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
-    use super::Activity;
+    use super::{
+        Activity, Actual, ActualError, Estimation, EstimationError, Progress, ProgressError, Status,
+    };
+    use crate::modules::estimation_model::EstimationModel;
+    use chrono::NaiveDate;
 
     #[test]
     fn get_estimated() {
-        let estimation = Activity::new(1, "activity 1".to_string(), 6, 10, 15).estimated();
+        let estimation = Activity::new(1, "activity 1".to_string(), 6.0, 10.0, 15.0)
+            .unwrap()
+            .estimated();
         assert_eq!(estimation, 10.166666666666666);
     }
+
+    #[test]
+    fn get_variance() {
+        let activity = Activity::new(1, "activity 1".to_string(), 6.0, 10.0, 18.0).unwrap();
+        assert_eq!(activity.standard_deviation(), 2.0);
+        assert_eq!(activity.variance(), 4.0);
+    }
+
+    #[test]
+    fn get_estimated_with_model() {
+        let mut activity = Activity::new(1, "activity 1".to_string(), 6.0, 12.0, 24.0).unwrap();
+        activity.model = EstimationModel::Triangular;
+        assert_eq!(activity.estimated(), 14.0);
+        assert_eq!(activity.variance(), 14.0);
+    }
+
+    #[test]
+    fn validate_estimation() {
+        assert!(Estimation::new(1.0, 2.0, 3.0).is_ok());
+        assert!(Estimation::new(2.0, 2.0, 2.0).is_ok());
+        assert_eq!(
+            Estimation::new(3.0, 2.0, 4.0),
+            Err(EstimationError::Unordered)
+        );
+        assert_eq!(
+            Estimation::new(1.0, 5.0, 4.0),
+            Err(EstimationError::Unordered)
+        );
+        assert_eq!(
+            Estimation::new(-1.0, 2.0, 4.0),
+            Err(EstimationError::Negative)
+        );
+        assert_eq!(
+            Estimation::new(1.0, f64::NAN, 4.0),
+            Err(EstimationError::NotFinite)
+        );
+        assert_eq!(
+            Activity::new(1, "activity 1".to_string(), 3.0, 2.0, 1.0),
+            Err(EstimationError::Unordered)
+        );
+    }
+
+    #[test]
+    fn validate_actual() {
+        let mut actual = Actual {
+            start: NaiveDate::from_ymd_opt(2022, 6, 2),
+            finish: NaiveDate::from_ymd_opt(2022, 6, 2),
+            effort: Some(0.0),
+        };
+        assert!(actual.validate().is_ok());
+        actual.finish = NaiveDate::from_ymd_opt(2022, 6, 1);
+        assert_eq!(actual.validate(), Err(ActualError::FinishBeforeStart));
+        actual.finish = None;
+        actual.effort = Some(-1.0);
+        assert_eq!(actual.validate(), Err(ActualError::InvalidEffort));
+        assert!(Actual::default().is_empty());
+    }
+
+    #[test]
+    fn validate_progress() {
+        assert!(Progress::new(Status::InProgress, 40.0).is_ok());
+        assert!(Progress::new(Status::Blocked, 0.0).is_ok());
+        assert_eq!(
+            Progress::new(Status::InProgress, 120.0),
+            Err(ProgressError::InvalidPercent)
+        );
+        assert_eq!(
+            Progress::new(Status::Done, 90.0),
+            Err(ProgressError::Inconsistent)
+        );
+        assert_eq!(
+            Progress::new(Status::NotStarted, 10.0),
+            Err(ProgressError::Inconsistent)
+        );
+        assert_eq!(
+            Status::from_name("in-progress").unwrap(),
+            Status::InProgress
+        );
+        assert!(Status::from_name("started").is_err());
+    }
+
+    #[test]
+    fn remaining_activity() {
+        let mut activity = Activity::new(1, "activity 1".to_string(), 6.0, 12.0, 24.0).unwrap();
+        activity.progress = Progress::new(Status::InProgress, 25.0).unwrap();
+        let remaining = activity.remaining();
+        assert_eq!(
+            remaining.estimation,
+            Estimation::new(4.5, 9.0, 18.0).unwrap()
+        );
+        assert_eq!(remaining.estimated(), activity.estimated() * 0.75);
+
+        activity.progress = Progress::new(Status::Done, 100.0).unwrap();
+        assert_eq!(activity.remaining().estimated(), 0.0);
+    }
 }
@@ -1,8 +1,15 @@
 use super::{
     activity::{Activity, ActivityId, EstimationValue},
+    critical_path::CriticalPath,
+    gantt_report::GanttReport,
     pert::{Pert, PertId},
     pert_report::PertReport,
+    progress::Forecast,
+    roadmap::ActivityWithRelatedDependencies,
+    schedule::Schedule,
+    tags_report::display_tags,
 };
+use anyhow::Result;
 use cli_table::{format::Justify, Table, WithTitle};
 use serde::Serialize;
 
@@ -20,45 +27,138 @@ struct ActivityPertRow {
     pessimistic: EstimationValue,
     #[table(title = "PERT estimation")]
     pert: f64,
+    #[table(title = "Tags", display_fn = "display_tags")]
+    tags: Vec<String>,
+}
+
+fn activities_table<'a>(activities: impl Iterator<Item = &'a Activity>) -> String {
+    let rows: Vec<ActivityPertRow> = activities
+        .map(|activity| ActivityPertRow {
+            id: activity.id,
+            name: activity.name.to_owned(),
+            pessimistic: activity.estimation.pessimistic,
+            probable: activity.estimation.probable,
+            optimistic: activity.estimation.optimistic,
+            pert: activity.estimated(),
+            tags: activity.tags.clone(),
+        })
+        .collect();
+    rows.with_title().display().unwrap().to_string()
+}
+
+/// Activities of a PERT with their dependencies, listed without scheduling them: the
+/// dependencies of a PERT that contain a cycle can still be removed.
+pub struct ActivityList {
+    pub activities: Vec<Activity>,
+    /// Dependencies as (head, tail), "tail depends on head".
+    dependencies: Vec<(ActivityId, ActivityId)>,
+}
+
+impl ActivityList {
+    pub fn new(
+        activities: Vec<Activity>,
+        acts_with_deps: &[ActivityWithRelatedDependencies],
+    ) -> Self {
+        let dependencies = acts_with_deps
+            .iter()
+            .filter_map(|dep| dep.activity_id_head.map(|head| (head, dep.activity_id)))
+            .collect();
+        Self {
+            activities,
+            dependencies,
+        }
+    }
+
+    /// Keeps the activities with the tag only, with the dependencies between them.
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        if let Some(tag) = &tag {
+            self.activities.retain(|activity| activity.has_tag(tag));
+            let ids: Vec<ActivityId> = self.activities.iter().map(|act| act.id).collect();
+            self.dependencies
+                .retain(|(head, tail)| ids.contains(head) && ids.contains(tail));
+        }
+        self
+    }
+
+    /// The activities only, without their dependencies.
+    pub fn table(&self) -> String {
+        activities_table(self.activities.iter())
+    }
+
+    fn name(&self, id: ActivityId) -> String {
+        self.activities
+            .iter()
+            .find(|act| act.id == id)
+            .map_or_else(|| id.to_string(), |act| act.name.to_owned())
+    }
+
+    pub fn ascii(&self) -> String {
+        let dependencies: String = self
+            .dependencies
+            .iter()
+            .map(|(head, tail)| {
+                format!(
+                    "\n\"{}\" depends on \"{}\"",
+                    self.name(*tail),
+                    self.name(*head)
+                )
+            })
+            .collect();
+        format!("{}{}", self.table(), dependencies)
+    }
 }
 
 struct PertWithActivities {
     pert: Pert,
     activities: Vec<Activity>,
+    critical_path: CriticalPath,
+    schedule: Option<Schedule>,
+    forecast: Forecast,
+    gantt: GanttReport,
 }
 pub struct ActivityReport {
     data: PertWithActivities,
+    /// Only the activities with this tag are listed.
+    tag: Option<String>,
 }
 
 impl ActivityReport {
-    pub fn new(pert: Pert, activities: Vec<Activity>) -> Self {
+    pub fn new(
+        pert: Pert,
+        activities: Vec<Activity>,
+        critical_path: CriticalPath,
+        schedule: Option<Schedule>,
+        forecast: Forecast,
+        gantt: GanttReport,
+    ) -> Self {
         Self {
-            data: PertWithActivities { pert, activities },
+            data: PertWithActivities {
+                pert,
+                activities,
+                critical_path,
+                schedule,
+                forecast,
+                gantt,
+            },
+            tag: None,
         }
     }
 
-    fn activities_rows(&self) -> Vec<ActivityPertRow> {
-        self.data
-            .activities
-            .iter()
-            .map(|activity| ActivityPertRow {
-                id: activity.id,
-                name: activity.name.to_owned(),
-                pessimistic: activity.estimation.pessimistic,
-                probable: activity.estimation.probable,
-                optimistic: activity.estimation.optimistic,
-                pert: activity.estimated(),
-            })
-            .collect()
-    }
-
-    pub fn list_activities(&mut self) -> String {
-        let rows: Vec<ActivityPertRow> = self.activities_rows();
-        rows.with_title().display().unwrap().to_string()
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
     }
 
-    pub fn pert_detail(self) -> PertReport {
-        PertReport::new(self.data.pert, self.data.activities)
+    pub fn pert_detail(self) -> Result<PertReport> {
+        Ok(PertReport::new(
+            self.data.pert,
+            self.data.activities,
+            self.data.critical_path,
+            self.data.schedule,
+            self.data.forecast,
+            self.data.gantt,
+        )?
+        .with_tag(self.tag))
     }
 }
 
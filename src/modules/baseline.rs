@@ -0,0 +1,386 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    activity::{Activity, ActivityId},
+    critical_path::CriticalPath,
+    graph::DependencyGraph,
+    pert::Pert,
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+/// Name given to the state of the PERT when it is compared with a baseline.
+pub const CURRENT: &str = "current";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineActivity {
+    pub id: ActivityId,
+    pub name: String,
+    pub optimistic: f64,
+    pub probable: f64,
+    pub pessimistic: f64,
+    /// Estimate given by the model of the PERT.
+    pub estimated: f64,
+    /// Activities this one depends on, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<ActivityId>,
+}
+
+/// Estimates of a PERT frozen at a point in time. Totals are kept as they were computed,
+/// changing the estimation model later does not rewrite the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub estimation_model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimation_lambda: Option<f64>,
+    pub unit: String,
+    /// Sum of the estimates of the activities.
+    pub total: f64,
+    /// Project duration along the critical path.
+    pub duration: f64,
+    /// Critical activities, ordered by their earliest start.
+    pub critical_path: Vec<ActivityId>,
+    /// Critical activities linked by critical dependencies, see `CriticalPath::chains`.
+    pub critical_chains: Vec<Vec<ActivityId>>,
+    pub activities: Vec<BaselineActivity>,
+}
+
+impl Snapshot {
+    pub fn new(
+        pert: &Pert,
+        activities: &[Activity],
+        acts_with_deps: &[ActivityWithRelatedDependencies],
+    ) -> Result<Self> {
+        let graph = DependencyGraph::new(acts_with_deps);
+        let critical_path = CriticalPath::new(activities, &graph)?;
+        let mut baseline_activities: Vec<BaselineActivity> = activities
+            .iter()
+            .map(|act| {
+                let mut depends_on = graph.predecessors(act.id).to_vec();
+                depends_on.sort_unstable();
+                BaselineActivity {
+                    id: act.id,
+                    name: act.name.to_owned(),
+                    optimistic: act.estimation.optimistic,
+                    probable: act.estimation.probable,
+                    pessimistic: act.estimation.pessimistic,
+                    estimated: act.estimated(),
+                    depends_on,
+                }
+            })
+            .collect();
+        baseline_activities.sort_by_key(|act| act.id);
+        Ok(Self {
+            estimation_model: pert.estimation_model.name().to_string(),
+            estimation_lambda: pert.estimation_model.lambda(),
+            unit: pert.unit.name().to_string(),
+            total: activities.iter().map(|act| act.estimated()).sum(),
+            duration: critical_path.duration,
+            critical_path: critical_path
+                .critical_activities()
+                .iter()
+                .map(|act| act.id)
+                .collect(),
+            critical_chains: critical_path.chains,
+            activities: baseline_activities,
+        })
+    }
+
+    pub fn get(&self, id: ActivityId) -> Option<&BaselineActivity> {
+        self.activities.iter().find(|act| act.id == id)
+    }
+
+    fn name(&self, id: ActivityId) -> String {
+        self.get(id)
+            .map_or_else(|| id.to_string(), |act| act.name.to_owned())
+    }
+
+    fn critical_chain_names(&self) -> Vec<Vec<String>> {
+        self.critical_chains
+            .iter()
+            .map(|chain| chain.iter().map(|id| self.name(*id)).collect())
+            .collect()
+    }
+}
+
+/// Snapshot of a PERT kept under a name, unique within the PERT.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub snapshot: Snapshot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Estimate {
+    pub optimistic: f64,
+    pub probable: f64,
+    pub pessimistic: f64,
+    pub estimated: f64,
+}
+
+impl From<&BaselineActivity> for Estimate {
+    fn from(act: &BaselineActivity) -> Self {
+        Self {
+            optimistic: act.optimistic,
+            probable: act.probable,
+            pessimistic: act.pessimistic,
+            estimated: act.estimated,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ActivityDiff {
+    pub id: ActivityId,
+    pub name: String,
+    /// Former name of a renamed activity.
+    pub previous_name: Option<String>,
+    pub change: Change,
+    pub before: Option<Estimate>,
+    pub after: Option<Estimate>,
+}
+
+impl ActivityDiff {
+    /// Change of the estimate, an added or removed activity counts for its whole estimate.
+    pub fn delta(&self) -> f64 {
+        let estimated =
+            |estimate: &Option<Estimate>| estimate.as_ref().map_or(0f64, |e| e.estimated);
+        estimated(&self.after) - estimated(&self.before)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DependencyDiff {
+    pub head: String,
+    pub tail: String,
+    pub change: Change,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Totals {
+    pub total: f64,
+    pub duration: f64,
+    /// Names of the critical activities, ordered by their earliest start.
+    pub critical_path: Vec<String>,
+    /// Names of the activities of every critical chain.
+    pub critical_chains: Vec<Vec<String>>,
+}
+
+impl From<&Snapshot> for Totals {
+    fn from(snapshot: &Snapshot) -> Self {
+        Self {
+            total: snapshot.total,
+            duration: snapshot.duration,
+            critical_path: snapshot
+                .critical_path
+                .iter()
+                .map(|id| snapshot.name(*id))
+                .collect(),
+            critical_chains: snapshot.critical_chain_names(),
+        }
+    }
+}
+
+/// Differences between two versions of a PERT, activities are matched by id.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BaselineDiff {
+    pub from: String,
+    pub to: String,
+    pub unit: String,
+    /// Activities added, removed or changed, by id.
+    pub activities: Vec<ActivityDiff>,
+    pub dependencies: Vec<DependencyDiff>,
+    pub before: Totals,
+    pub after: Totals,
+}
+
+impl BaselineDiff {
+    pub fn new(from: &str, before: &Snapshot, to: &str, after: &Snapshot) -> Self {
+        let mut ids: Vec<ActivityId> = before
+            .activities
+            .iter()
+            .chain(after.activities.iter())
+            .map(|act| act.id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut activities = vec![];
+        let mut dependencies = vec![];
+        for id in ids {
+            let (old, new) = (before.get(id), after.get(id));
+            let change = match (old, new) {
+                (None, Some(_)) => Some(Change::Added),
+                (Some(_), None) => Some(Change::Removed),
+                (Some(old), Some(new)) if !same_estimate(old, new) => Some(Change::Changed),
+                _ => None,
+            };
+            if let Some(change) = change {
+                let current = new.or(old).unwrap();
+                activities.push(ActivityDiff {
+                    id,
+                    name: current.name.to_owned(),
+                    previous_name: match (old, new) {
+                        (Some(old), Some(new)) if old.name != new.name => Some(old.name.to_owned()),
+                        _ => None,
+                    },
+                    change,
+                    before: old.map(Estimate::from),
+                    after: new.map(Estimate::from),
+                });
+            }
+
+            let no_heads = vec![];
+            let old_heads = old.map_or(&no_heads, |act| &act.depends_on);
+            let new_heads = new.map_or(&no_heads, |act| &act.depends_on);
+            for head in old_heads.iter().filter(|head| !new_heads.contains(head)) {
+                dependencies.push(DependencyDiff {
+                    head: before.name(*head),
+                    tail: before.name(id),
+                    change: Change::Removed,
+                });
+            }
+            for head in new_heads.iter().filter(|head| !old_heads.contains(head)) {
+                dependencies.push(DependencyDiff {
+                    head: after.name(*head),
+                    tail: after.name(id),
+                    change: Change::Added,
+                });
+            }
+        }
+
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            unit: after.unit.to_owned(),
+            activities,
+            dependencies,
+            before: before.into(),
+            after: after.into(),
+        }
+    }
+
+    pub fn total_delta(&self) -> f64 {
+        self.after.total - self.before.total
+    }
+
+    pub fn duration_delta(&self) -> f64 {
+        self.after.duration - self.before.duration
+    }
+
+    pub fn critical_path_changed(&self) -> bool {
+        self.before.critical_path != self.after.critical_path
+            || self.before.critical_chains != self.after.critical_chains
+    }
+}
+
+fn same_estimate(old: &BaselineActivity, new: &BaselineActivity) -> bool {
+    old.name == new.name
+        && old.optimistic == new.optimistic
+        && old.probable == new.probable
+        && old.pessimistic == new.pessimistic
+        && old.estimated == new.estimated
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BaselineDiff, Change, Snapshot};
+    use crate::modules::{
+        activity::Activity, pert::Pert, roadmap::ActivityWithRelatedDependencies, test_util::dep,
+    };
+
+    fn snapshot(activities: Vec<Activity>, deps: Vec<ActivityWithRelatedDependencies>) -> Snapshot {
+        Snapshot::new(&Pert::new(1, "example".to_string()), &activities, &deps).unwrap()
+    }
+
+    #[test]
+    fn snapshot_keeps_totals_and_critical_path() {
+        let snapshot = snapshot(
+            vec![
+                Activity::new(1, "design".to_string(), 1.0, 2.0, 3.0).unwrap(),
+                Activity::new(2, "build".to_string(), 4.0, 4.0, 4.0).unwrap(),
+                Activity::new(3, "doc".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            ],
+            vec![dep(1, None), dep(2, Some(1)), dep(3, Some(1))],
+        );
+        assert_eq!(snapshot.total, 7.0);
+        assert_eq!(snapshot.duration, 6.0);
+        assert_eq!(snapshot.critical_path, vec![1, 2]);
+        assert_eq!(snapshot.critical_chains, vec![vec![1, 2]]);
+        assert_eq!(snapshot.activities[2].depends_on, vec![1]);
+    }
+
+    #[test]
+    fn diff_between_versions() {
+        let before = snapshot(
+            vec![
+                Activity::new(1, "design".to_string(), 1.0, 2.0, 3.0).unwrap(),
+                Activity::new(2, "build".to_string(), 4.0, 4.0, 4.0).unwrap(),
+                Activity::new(3, "doc".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            ],
+            vec![dep(1, None), dep(2, Some(1)), dep(3, Some(1))],
+        );
+        let after = snapshot(
+            vec![
+                Activity::new(1, "design".to_string(), 1.0, 2.0, 3.0).unwrap(),
+                Activity::new(2, "build all".to_string(), 4.0, 5.0, 6.0).unwrap(),
+                Activity::new(4, "test".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            ],
+            vec![dep(1, None), dep(2, Some(1)), dep(4, Some(2))],
+        );
+
+        let diff = BaselineDiff::new("v1", &before, "current", &after);
+        let changes: Vec<(i32, Change)> = diff
+            .activities
+            .iter()
+            .map(|act| (act.id, act.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (2, Change::Changed),
+                (3, Change::Removed),
+                (4, Change::Added)
+            ]
+        );
+        assert_eq!(diff.activities[0].previous_name.as_deref(), Some("build"));
+        assert_eq!(diff.activities[0].delta(), 1.0);
+        assert_eq!(diff.activities[1].delta(), -1.0);
+        assert_eq!(diff.dependencies.len(), 2);
+        assert_eq!(diff.total_delta(), 2.0);
+        assert_eq!(diff.duration_delta(), 3.0);
+        assert!(diff.critical_path_changed());
+        assert_eq!(
+            diff.after.critical_path,
+            vec!["design", "build all", "test"]
+        );
+        assert_eq!(
+            diff.after.critical_chains,
+            vec![vec!["design", "build all", "test"]]
+        );
+
+        assert!(BaselineDiff::new("v1", &before, "v1", &before)
+            .activities
+            .is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+use anyhow::Result;
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    activity::ActivityId,
+    baseline::{ActivityDiff, Baseline, BaselineDiff, Change, Estimate},
+    json_report::{to_json, PertJson},
+    pert::Pert,
+    pert_report::pert_header,
+    report::round,
+    templates::render,
+};
+
+fn signed(value: f64) -> String {
+    format!("{:+}", round(value))
+}
+
+fn describe_estimate(estimate: &Option<Estimate>) -> String {
+    match estimate {
+        Some(estimate) => format!(
+            "{} ({} / {} / {})",
+            round(estimate.estimated),
+            estimate.optimistic,
+            estimate.probable,
+            estimate.pessimistic
+        ),
+        None => "-".to_string(),
+    }
+}
+
+#[derive(Table, Serialize)]
+struct ActivityDiffRow {
+    #[table(title = "Change")]
+    change: &'static str,
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Before")]
+    before: String,
+    #[table(title = "After")]
+    after: String,
+    #[table(title = "Delta", justify = "Justify::Right")]
+    delta: String,
+}
+
+impl From<&ActivityDiff> for ActivityDiffRow {
+    fn from(act: &ActivityDiff) -> Self {
+        Self {
+            change: act.change.name(),
+            id: act.id,
+            name: match &act.previous_name {
+                Some(previous_name) => format!("{} (was {})", act.name, previous_name),
+                None => act.name.to_owned(),
+            },
+            before: describe_estimate(&act.before),
+            after: describe_estimate(&act.after),
+            delta: signed(act.delta()),
+        }
+    }
+}
+
+/// Critical chains separated by `;`, parallel chains are not one sequence.
+fn describe_chains(chains: &[Vec<String>]) -> String {
+    chains
+        .iter()
+        .map(|chain| chain.join(" -> "))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+pub struct BaselineDiffReport {
+    pert: Pert,
+    diff: BaselineDiff,
+    rows: Vec<ActivityDiffRow>,
+}
+
+impl BaselineDiffReport {
+    pub fn new(pert: Pert, diff: BaselineDiff) -> Self {
+        let rows = diff.activities.iter().map(|act| act.into()).collect();
+        Self { pert, diff, rows }
+    }
+
+    fn dependency_lines(&self) -> Vec<String> {
+        self.diff
+            .dependencies
+            .iter()
+            .map(|dep| {
+                let sign = match dep.change {
+                    Change::Removed => "-",
+                    _ => "+",
+                };
+                format!("{} {} -> {}", sign, dep.head, dep.tail)
+            })
+            .collect()
+    }
+
+    fn total_line(&self, before: f64, after: f64) -> String {
+        format!(
+            "{} -> {} ({}) {}",
+            round(before),
+            round(after),
+            signed(after - before),
+            self.diff.unit
+        )
+    }
+
+    fn critical_path_line(&self) -> String {
+        let before = describe_chains(&self.diff.before.critical_chains);
+        if self.diff.critical_path_changed() {
+            format!(
+                "{} => {}",
+                before,
+                describe_chains(&self.diff.after.critical_chains)
+            )
+        } else {
+            format!("unchanged ({})", before)
+        }
+    }
+
+    pub fn ascii(&mut self) -> String {
+        let activities = if self.rows.is_empty() {
+            "No activity changed".to_string()
+        } else {
+            self.rows.with_title().display().unwrap().to_string()
+        };
+        let dependencies = self.dependency_lines();
+        let dependencies = if dependencies.is_empty() {
+            "No dependency changed".to_string()
+        } else {
+            dependencies.join("\n")
+        };
+
+        format!(
+            "{}\n\nCHANGES: {} -> {}\n{}\nDEPENDENCIES:\n{}\nTOTAL: {}\nDURATION: {}\nCRITICAL PATH: {}",
+            pert_header(&self.pert),
+            self.diff.from,
+            self.diff.to,
+            activities,
+            dependencies,
+            self.total_line(self.diff.before.total, self.diff.after.total),
+            self.total_line(self.diff.before.duration, self.diff.after.duration),
+            self.critical_path_line()
+        )
+    }
+
+    pub fn html(&mut self) -> Result<String> {
+        let globals = liquid::object!({
+            "pert_name": self.pert.name,
+            "estimation_model": self.pert.estimation_model.to_string(),
+            "unit": self.pert.unit.name(),
+            "from": self.diff.from,
+            "to": self.diff.to,
+            "activities": self.rows,
+            "dependencies": self.dependency_lines(),
+            "total": self.total_line(self.diff.before.total, self.diff.after.total),
+            "duration": self.total_line(self.diff.before.duration, self.diff.after.duration),
+            "critical_path": self.critical_path_line(),
+        });
+
+        render("report_baseline_diff.liquid", &globals)
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct BaselineDiffJsonReport<'a> {
+            pert: PertJson,
+            #[serde(flatten)]
+            diff: &'a BaselineDiff,
+            total_delta: f64,
+            duration_delta: f64,
+            critical_path_changed: bool,
+        }
+
+        to_json(
+            "baseline_diff",
+            BaselineDiffJsonReport {
+                pert: (&self.pert).into(),
+                diff: &self.diff,
+                total_delta: self.diff.total_delta(),
+                duration_delta: self.diff.duration_delta(),
+                critical_path_changed: self.diff.critical_path_changed(),
+            },
+        )
+    }
+}
+
+#[derive(Table)]
+struct BaselineRow {
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Created at")]
+    created_at: String,
+    #[table(title = "Activities", justify = "Justify::Right")]
+    activities: usize,
+    #[table(title = "Total", justify = "Justify::Right")]
+    total: f64,
+    #[table(title = "Duration", justify = "Justify::Right")]
+    duration: f64,
+}
+
+pub fn baselines_view(baselines: &[Baseline]) -> String {
+    let rows: Vec<BaselineRow> = baselines
+        .iter()
+        .map(|baseline| BaselineRow {
+            name: baseline.name.to_owned(),
+            created_at: baseline.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            activities: baseline.snapshot.activities.len(),
+            total: round(baseline.snapshot.total),
+            duration: round(baseline.snapshot.duration),
+        })
+        .collect();
+    rows.with_title().display().unwrap().to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{baselines_view, BaselineDiffReport};
+    use crate::modules::{
+        activity::Activity,
+        baseline::{Baseline, BaselineDiff, Snapshot},
+        pert::Pert,
+        roadmap::ActivityWithRelatedDependencies,
+        test_util::dep,
+    };
+    use chrono::NaiveDate;
+
+    fn snapshot(pert: &Pert, activities: &[(i32, &str, f64, Option<i32>)]) -> Snapshot {
+        let acts: Vec<Activity> = activities
+            .iter()
+            .map(|(id, name, estimate, _)| {
+                Activity::new(*id, name.to_string(), *estimate, *estimate, *estimate).unwrap()
+            })
+            .collect();
+        let deps: Vec<ActivityWithRelatedDependencies> = activities
+            .iter()
+            .map(|(id, _, _, head)| dep(*id, *head))
+            .collect();
+        Snapshot::new(pert, &acts, &deps).unwrap()
+    }
+
+    fn example_report() -> BaselineDiffReport {
+        let pert = Pert::new(1, "example".to_string());
+        let before = snapshot(
+            &pert,
+            &[
+                (1, "design", 2.0, None),
+                (2, "build", 4.0, Some(1)),
+                (3, "doc", 1.0, Some(1)),
+            ],
+        );
+        let after = snapshot(
+            &pert,
+            &[
+                (1, "design", 2.0, None),
+                (2, "build all", 5.0, Some(1)),
+                (4, "test", 2.0, Some(2)),
+            ],
+        );
+        let diff = BaselineDiff::new("v1", &before, "current", &after);
+        BaselineDiffReport::new(pert, diff)
+    }
+
+    #[test]
+    fn diff_console() {
+        insta::assert_display_snapshot!(example_report().ascii());
+    }
+
+    #[test]
+    fn diff_json() {
+        insta::assert_display_snapshot!(example_report().json().unwrap());
+    }
+
+    #[test]
+    fn diff_html() {
+        let html = example_report().html().unwrap();
+        assert!(html.contains("<td>build all (was build)</td>"));
+        assert!(html.contains("design -> build => design -> build all -> test"));
+    }
+
+    #[test]
+    fn list() {
+        let pert = Pert::new(1, "example".to_string());
+        let baseline = Baseline {
+            name: "v1".to_string(),
+            created_at: NaiveDate::from_ymd_opt(2022, 6, 1)
+                .unwrap()
+                .and_hms_opt(10, 30, 0)
+                .unwrap(),
+            snapshot: snapshot(
+                &pert,
+                &[(1, "design", 2.0, None), (2, "build", 4.0, Some(1))],
+            ),
+        };
+        insta::assert_display_snapshot!(baselines_view(&[baseline]));
+    }
+}
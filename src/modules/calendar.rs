@@ -0,0 +1,176 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use super::{
+    duration::{Duration, DurationUnit},
+    pert::Pert,
+};
+
+/// File with the holidays, one `YYYY-MM-DD` date per line.
+pub const HOLIDAYS_FILE_ENV: &str = "PERTY_HOLIDAYS_FILE";
+
+pub fn default_working_days() -> Vec<Weekday> {
+    vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+    ]
+}
+
+/// Parses a comma separated list of weekdays, e.g. `mon,tue,wed`.
+pub fn parse_weekdays(input: &str) -> Result<Vec<Weekday>> {
+    let mut weekdays = vec![];
+    for name in input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let weekday: Weekday = name
+            .parse()
+            .map_err(|_| anyhow!("Unknown weekday {}", name))?;
+        if !weekdays.contains(&weekday) {
+            weekdays.push(weekday);
+        }
+    }
+    if weekdays.is_empty() {
+        bail!("At least one working day is needed");
+    }
+    weekdays.sort_by_key(|weekday| weekday.num_days_from_monday());
+    Ok(weekdays)
+}
+
+pub fn weekdays_name(weekdays: &[Weekday]) -> String {
+    weekdays
+        .iter()
+        .map(|weekday| weekday.to_string().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Ignores empty lines and lines starting with `#`.
+pub fn parse_holidays(content: &str) -> Result<Vec<NaiveDate>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            NaiveDate::parse_from_str(line, "%Y-%m-%d")
+                .with_context(|| format!("Invalid holiday {}", line))
+        })
+        .collect()
+}
+
+/// Holidays of the file set in `PERTY_HOLIDAYS_FILE`, none when it is not set.
+pub fn load_holidays() -> Result<Vec<NaiveDate>> {
+    match std::env::var(HOLIDAYS_FILE_ENV) {
+        Ok(path) => {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read holidays file {}", path))?;
+            parse_holidays(&content)
+        }
+        Err(_) => Ok(vec![]),
+    }
+}
+
+#[derive(Debug)]
+pub struct Calendar {
+    working_days: Vec<Weekday>,
+    hours_per_day: f64,
+    unit: DurationUnit,
+    holidays: Vec<NaiveDate>,
+}
+
+impl Calendar {
+    pub fn new(pert: &Pert, holidays: Vec<NaiveDate>) -> Result<Self> {
+        if pert.working_days.is_empty() {
+            bail!("At least one working day is needed");
+        }
+        if pert.unit == DurationUnit::Points {
+            bail!("Activities estimated in points cannot be scheduled on a calendar");
+        }
+        Ok(Self {
+            working_days: pert.working_days.clone(),
+            hours_per_day: pert.hours_per_day,
+            unit: pert.unit,
+            holidays,
+        })
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// The working day `index` working days after the first one on or after `start`.
+    pub fn working_day(&self, start: NaiveDate, index: usize) -> NaiveDate {
+        let mut date = start;
+        let mut left = index;
+        loop {
+            if self.is_working_day(date) {
+                if left == 0 {
+                    return date;
+                }
+                left -= 1;
+            }
+            date = date.succ_opt().expect("Date out of range");
+        }
+    }
+
+    /// Working days needed for `value`, expressed in the unit of the PERT.
+    pub fn working_days_for(&self, value: f64) -> Result<f64> {
+        Duration::new(value, self.unit).to_unit(DurationUnit::Days, self.hours_per_day)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_holidays, parse_weekdays, Calendar};
+    use crate::modules::{duration::DurationUnit, pert::Pert};
+    use chrono::{NaiveDate, Weekday};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn working_days_skip_weekends_and_holidays() {
+        let pert = Pert::new(1, "example".to_string());
+        let calendar = Calendar::new(&pert, vec![date(2022, 6, 2)]).unwrap();
+        // Saturday 2022-05-28
+        assert_eq!(
+            calendar.working_day(date(2022, 5, 28), 0),
+            date(2022, 5, 30)
+        );
+        assert_eq!(calendar.working_day(date(2022, 5, 30), 2), date(2022, 6, 1));
+        assert_eq!(calendar.working_day(date(2022, 5, 30), 3), date(2022, 6, 3));
+        assert_eq!(calendar.working_day(date(2022, 5, 30), 4), date(2022, 6, 6));
+    }
+
+    #[test]
+    fn working_days_for_unit() {
+        let mut pert = Pert::new(1, "example".to_string());
+        pert.unit = DurationUnit::Hours;
+        pert.hours_per_day = 6.0;
+        let calendar = Calendar::new(&pert, vec![]).unwrap();
+        assert_eq!(calendar.working_days_for(9.0).unwrap(), 1.5);
+
+        pert.unit = DurationUnit::Points;
+        assert!(Calendar::new(&pert, vec![]).is_err());
+    }
+
+    #[test]
+    fn parse_calendar_settings() {
+        assert_eq!(
+            parse_weekdays("wed, mon,tue").unwrap(),
+            vec![Weekday::Mon, Weekday::Tue, Weekday::Wed]
+        );
+        assert!(parse_weekdays("mon,foo").is_err());
+        assert!(parse_weekdays("").is_err());
+        assert_eq!(
+            parse_holidays("# holidays\n2022-12-25\n\n2022-12-26\n").unwrap(),
+            vec![date(2022, 12, 25), date(2022, 12, 26)]
+        );
+        assert!(parse_holidays("25/12/2022").is_err());
+    }
+}
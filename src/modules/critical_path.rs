@@ -0,0 +1,363 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use super::{
+    activity::{Activity, ActivityId},
+    graph::DependencyGraph,
+};
+
+const FLOAT_TOLERANCE: f64 = 1e-9;
+
+/// Parallel critical paths multiply with every fork, only the first ones are listed.
+pub const MAX_CHAINS: usize = 10;
+
+/// Removes the rounding noise left by summing fractional durations.
+fn settle(value: f64) -> f64 {
+    if value.abs() < FLOAT_TOLERANCE {
+        0f64
+    } else {
+        value
+    }
+}
+
+fn longest_path(current: (f64, f64), candidate: (f64, f64)) -> (f64, f64) {
+    let (duration, variance) = candidate;
+    if duration > current.0 + FLOAT_TOLERANCE
+        || ((duration - current.0).abs() < FLOAT_TOLERANCE && variance > current.1)
+    {
+        (duration, variance)
+    } else {
+        current
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScheduledActivity {
+    pub id: ActivityId,
+    pub name: String,
+    pub duration: f64,
+    pub earliest_start: f64,
+    pub earliest_finish: f64,
+    pub latest_start: f64,
+    pub latest_finish: f64,
+    pub total_float: f64,
+    pub free_float: f64,
+}
+
+impl ScheduledActivity {
+    pub fn is_critical(&self) -> bool {
+        self.total_float == 0f64
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct CriticalPath {
+    /// Scheduled activities, in the same order the activities were given.
+    pub activities: Vec<ScheduledActivity>,
+    /// Project duration along the longest path of the network.
+    pub duration: f64,
+    /// Sum of the variances of the activities along the critical path.
+    pub variance: f64,
+    /// Critical activities linked by critical dependencies, from the start of the project
+    /// to its end. Parallel critical paths are separate chains, at most `MAX_CHAINS`.
+    pub chains: Vec<Vec<ActivityId>>,
+    /// Whether critical chains were left out of `chains`.
+    pub more_chains: bool,
+}
+
+impl CriticalPath {
+    pub fn new(activities: &[Activity], graph: &DependencyGraph) -> Result<Self> {
+        let durations: HashMap<ActivityId, f64> = activities
+            .iter()
+            .map(|act| (act.id, act.estimated()))
+            .collect();
+        let variances: HashMap<ActivityId, f64> = activities
+            .iter()
+            .map(|act| (act.id, act.variance()))
+            .collect();
+        let order = graph.topological_order()?;
+        for id in &order {
+            durations
+                .get(id)
+                .with_context(|| format!("Activity {} not found", id))?;
+        }
+
+        // along with the earliest start, keep the variance of the longest path leading to it:
+        // when several paths are equally long, the most uncertain one is taken
+        let mut earliest_start: HashMap<ActivityId, f64> = HashMap::new();
+        let mut path_variance: HashMap<ActivityId, f64> = HashMap::new();
+        for id in &order {
+            let (start, variance) = graph
+                .predecessors(*id)
+                .iter()
+                .map(|pred| {
+                    (
+                        settle(earliest_start[pred] + durations[pred]),
+                        path_variance[pred],
+                    )
+                })
+                .fold((0f64, 0f64), longest_path);
+            earliest_start.insert(*id, start);
+            path_variance.insert(*id, variance + variances[id]);
+        }
+        let (duration, variance) = order
+            .iter()
+            .map(|id| (earliest_start[id] + durations[id], path_variance[id]))
+            .fold((0f64, 0f64), longest_path);
+
+        let mut latest_start: HashMap<ActivityId, f64> = HashMap::new();
+        for id in order.iter().rev() {
+            let finish = graph
+                .successors(*id)
+                .iter()
+                .map(|succ| latest_start[succ])
+                .fold(duration, f64::min);
+            latest_start.insert(*id, finish - durations[id]);
+        }
+
+        let activities = activities
+            .iter()
+            .filter(|act| earliest_start.contains_key(&act.id))
+            .map(|act| {
+                let activity_duration = durations[&act.id];
+                let earliest_start_act = earliest_start[&act.id];
+                let earliest_finish = earliest_start_act + activity_duration;
+                let total_float = settle(latest_start[&act.id] - earliest_start_act);
+                let latest_start_act = earliest_start_act + total_float;
+                let successors_start = graph
+                    .successors(act.id)
+                    .iter()
+                    .map(|succ| earliest_start[succ])
+                    .fold(duration, f64::min);
+                ScheduledActivity {
+                    id: act.id,
+                    name: act.name.to_owned(),
+                    duration: activity_duration,
+                    earliest_start: earliest_start_act,
+                    earliest_finish,
+                    latest_start: latest_start_act,
+                    latest_finish: latest_start_act + activity_duration,
+                    total_float,
+                    free_float: settle(successors_start - earliest_finish),
+                }
+            })
+            .collect();
+
+        let mut critical_path = Self {
+            activities,
+            duration,
+            variance,
+            chains: vec![],
+            more_chains: false,
+        };
+        let mut chains = critical_path.find_chains(graph);
+        critical_path.more_chains = chains.len() > MAX_CHAINS;
+        chains.truncate(MAX_CHAINS);
+        critical_path.chains = chains;
+        Ok(critical_path)
+    }
+
+    /// Lists one chain more than `MAX_CHAINS` at most, to tell whether some were left out.
+    fn find_chains(&self, graph: &DependencyGraph) -> Vec<Vec<ActivityId>> {
+        let mut chains = vec![];
+        for act in self.critical_activities() {
+            let driven = graph
+                .predecessors(act.id)
+                .iter()
+                .any(|head| self.is_critical_dependency(*head, act.id));
+            if !driven {
+                self.extend_chain(graph, &mut vec![act.id], &mut chains);
+            }
+        }
+        chains
+    }
+
+    fn extend_chain(
+        &self,
+        graph: &DependencyGraph,
+        chain: &mut Vec<ActivityId>,
+        chains: &mut Vec<Vec<ActivityId>>,
+    ) {
+        if chains.len() > MAX_CHAINS {
+            return;
+        }
+        let last = *chain.last().unwrap();
+        let next: Vec<ActivityId> = graph
+            .successors(last)
+            .iter()
+            .filter(|tail| self.is_critical_dependency(last, **tail))
+            .copied()
+            .collect();
+        if next.is_empty() {
+            chains.push(chain.clone());
+        }
+        for tail in next {
+            chain.push(tail);
+            self.extend_chain(graph, chain, chains);
+            chain.pop();
+        }
+    }
+
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    pub fn get(&self, id: ActivityId) -> Option<&ScheduledActivity> {
+        self.activities.iter().find(|act| act.id == id)
+    }
+
+    /// Activities with no slack, ordered by their earliest start.
+    pub fn critical_activities(&self) -> Vec<&ScheduledActivity> {
+        let mut critical: Vec<&ScheduledActivity> = self
+            .activities
+            .iter()
+            .filter(|act| act.is_critical())
+            .collect();
+        critical.sort_by(|a, b| a.earliest_start.total_cmp(&b.earliest_start));
+        critical
+    }
+
+    /// A dependency is critical when both activities are and the tail starts as soon as the head is completed.
+    pub fn is_critical_dependency(&self, head: ActivityId, tail: ActivityId) -> bool {
+        match (self.get(head), self.get(tail)) {
+            (Some(head), Some(tail)) => {
+                head.is_critical()
+                    && tail.is_critical()
+                    && (head.earliest_finish - tail.earliest_start).abs() < FLOAT_TOLERANCE
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CriticalPath;
+    use crate::modules::{activity::Activity, graph::DependencyGraph, test_util::dep};
+
+    #[test]
+    fn critical_path_parallel_branches() {
+        // 1 -> 2 -> 4 and 1 -> 3 -> 4, with the 3 branch being shorter
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 6.0, 6.0, 6.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 3.0, 3.0, 3.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&[
+            dep(1, None),
+            dep(2, Some(1)),
+            dep(3, Some(1)),
+            dep(4, Some(2)),
+            dep(4, Some(3)),
+        ]);
+
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        assert_eq!(critical_path.duration, 9.0);
+        let critical_ids: Vec<i32> = critical_path
+            .critical_activities()
+            .iter()
+            .map(|act| act.id)
+            .collect();
+        assert_eq!(critical_ids, vec![1, 2, 4]);
+        assert_eq!(critical_path.chains, vec![vec![1, 2, 4]]);
+        assert_eq!(critical_path.variance, 0.0);
+
+        let third = critical_path.get(3).unwrap();
+        assert_eq!(third.earliest_start, 2.0);
+        assert_eq!(third.earliest_finish, 5.0);
+        assert_eq!(third.latest_start, 5.0);
+        assert_eq!(third.latest_finish, 8.0);
+        assert_eq!(third.total_float, 3.0);
+        assert_eq!(third.free_float, 3.0);
+    }
+
+    #[test]
+    fn parallel_critical_paths_are_separate_chains() {
+        // 1 -> 2 and 3 -> 4 both last 5, their activities are all critical
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 3.0, 3.0, 3.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 4.0, 4.0, 4.0).unwrap(),
+        ];
+        let graph =
+            DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, None), dep(4, Some(3))]);
+
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        assert_eq!(critical_path.critical_activities().len(), 4);
+        assert_eq!(critical_path.chains, vec![vec![1, 2], vec![3, 4]]);
+        assert!(!critical_path.more_chains);
+    }
+
+    #[test]
+    fn critical_chains_are_capped() {
+        // 30 diamonds in a row: 2^30 critical paths
+        let mut activities =
+            vec![Activity::new(1, "activity 1".to_string(), 1.0, 1.0, 1.0).unwrap()];
+        let mut dependencies = vec![dep(1, None)];
+        for diamond in 0..30 {
+            let join = 3 * diamond + 1;
+            for id in join + 1..=join + 3 {
+                activities
+                    .push(Activity::new(id, format!("activity {}", id), 1.0, 1.0, 1.0).unwrap());
+            }
+            dependencies.extend([
+                dep(join + 1, Some(join)),
+                dep(join + 2, Some(join)),
+                dep(join + 3, Some(join + 1)),
+                dep(join + 3, Some(join + 2)),
+            ]);
+        }
+        let graph = DependencyGraph::new(&dependencies);
+
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        assert_eq!(critical_path.chains.len(), super::MAX_CHAINS);
+        assert!(critical_path.more_chains);
+        assert_eq!(critical_path.chains[0].len(), 61);
+    }
+
+    #[test]
+    fn critical_path_free_float_differs_from_total_float() {
+        // 1 -> 2 -> 3 is short, 4 is long and independent
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 10.0, 10.0, 10.0).unwrap(),
+        ];
+        let graph =
+            DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, Some(2)), dep(4, None)]);
+
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        assert_eq!(critical_path.duration, 10.0);
+        let first = critical_path.get(1).unwrap();
+        assert_eq!(first.total_float, 7.0);
+        assert_eq!(first.free_float, 0.0);
+        let last = critical_path.get(3).unwrap();
+        assert_eq!(last.total_float, 7.0);
+        assert_eq!(last.free_float, 7.0);
+    }
+
+    #[test]
+    fn critical_path_variance_takes_the_most_uncertain_path() {
+        // 1 -> 3 and 2 -> 3 are equally long, but 2 is more uncertain
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 4.0, 4.0, 4.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 1.0, 3.0, 11.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 2.0, 3.0).unwrap(),
+        ];
+        let graph =
+            DependencyGraph::new(&[dep(1, None), dep(2, None), dep(3, Some(1)), dep(3, Some(2))]);
+
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        assert_eq!(critical_path.duration, 6.0);
+        let expected_variance = (10f64 / 6f64).powi(2) + (2f64 / 6f64).powi(2);
+        assert!((critical_path.variance - expected_variance).abs() < 1e-9);
+    }
+}
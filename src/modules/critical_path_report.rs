@@ -0,0 +1,146 @@
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    activity::ActivityId,
+    critical_path::{CriticalPath, ScheduledActivity, MAX_CHAINS},
+    pert::Pert,
+    pert_report::pert_header,
+    report::round,
+};
+
+#[derive(Table, Serialize)]
+struct ScheduledActivityRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Duration")]
+    duration: f64,
+    #[table(title = "ES")]
+    earliest_start: f64,
+    #[table(title = "EF")]
+    earliest_finish: f64,
+    #[table(title = "LS")]
+    latest_start: f64,
+    #[table(title = "LF")]
+    latest_finish: f64,
+    #[table(title = "Total float")]
+    total_float: f64,
+    #[table(title = "Free float")]
+    free_float: f64,
+    #[table(title = "Critical")]
+    critical: bool,
+}
+
+impl From<&ScheduledActivity> for ScheduledActivityRow {
+    fn from(act: &ScheduledActivity) -> Self {
+        Self {
+            id: act.id,
+            name: act.name.to_owned(),
+            duration: round(act.duration),
+            earliest_start: round(act.earliest_start),
+            earliest_finish: round(act.earliest_finish),
+            latest_start: round(act.latest_start),
+            latest_finish: round(act.latest_finish),
+            total_float: round(act.total_float),
+            free_float: round(act.free_float),
+            critical: act.is_critical(),
+        }
+    }
+}
+
+pub struct CriticalPathReport {
+    pert: Pert,
+    critical_path: CriticalPath,
+}
+
+impl CriticalPathReport {
+    pub fn new(pert: Pert, critical_path: CriticalPath) -> Self {
+        Self {
+            pert,
+            critical_path,
+        }
+    }
+
+    pub fn ascii(&mut self) -> String {
+        let rows: Vec<ScheduledActivityRow> = self
+            .critical_path
+            .activities
+            .iter()
+            .map(|act| act.into())
+            .collect();
+        // one line per chain, parallel critical paths are not one sequence
+        let mut paths: String = self
+            .critical_path
+            .chains
+            .iter()
+            .map(|chain| {
+                let names: Vec<&str> = chain
+                    .iter()
+                    .filter_map(|id| self.critical_path.get(*id))
+                    .map(|act| act.name.as_str())
+                    .collect();
+                format!("CRITICAL PATH: {}\n", names.join(" -> "))
+            })
+            .collect();
+        if self.critical_path.more_chains {
+            paths.push_str(&format!(
+                "CRITICAL PATH: ... and more, only the first {} critical paths are listed\n",
+                MAX_CHAINS
+            ));
+        }
+
+        format!(
+            "{}\n\n{}\n{}DURATION: {} {}",
+            pert_header(&self.pert),
+            rows.with_title().display().unwrap(),
+            paths,
+            round(self.critical_path.duration),
+            self.pert.unit
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CriticalPathReport;
+    use crate::modules::{
+        activity::Activity, critical_path::CriticalPath, graph::DependencyGraph, pert::Pert,
+        test_util::dep,
+    };
+
+    #[test]
+    fn table() {
+        let pert = Pert::new(1, "example".to_string());
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 1.0, 2.0, 4.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 2.0, 3.0, 7.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.5, 3.0).unwrap(),
+        ];
+        let dependencies = [(1, None), (2, Some(1)), (3, Some(1))]
+            .into_iter()
+            .map(|(id, head)| dep(id, head))
+            .collect::<Vec<_>>();
+        let critical_path =
+            CriticalPath::new(&activities, &DependencyGraph::new(&dependencies)).unwrap();
+
+        let mut report = CriticalPathReport::new(pert, critical_path);
+        insta::assert_display_snapshot!(report.ascii());
+    }
+
+    #[test]
+    fn parallel_chains() {
+        let pert = Pert::new(1, "example".to_string());
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 6.0, 6.0, 6.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 6.0, 6.0, 6.0).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, Some(1))]);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+
+        let mut report = CriticalPathReport::new(pert, critical_path);
+        insta::assert_display_snapshot!(report.ascii());
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::fmt;
+
+use super::activity::{Activity, ActivityId, Estimation, EstimationValue};
+
+/// Relative spread above which the estimators of an activity are asked for another round.
+pub const DEFAULT_THRESHOLD: f64 = 0.25;
+
+/// How the estimates of several estimators are combined into the estimation of an activity.
+/// Every value is combined on its own: optimistic with optimistic, and so on.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Median,
+    /// Mean weighted by the confidence of each estimator.
+    ConfidenceWeighted,
+}
+
+impl Aggregation {
+    pub fn from_name(name: &str) -> Result<Self> {
+        let aggregation = match name {
+            "mean" => Aggregation::Mean,
+            "median" => Aggregation::Median,
+            "confidence-weighted" => Aggregation::ConfidenceWeighted,
+            _ => bail!("Unknown aggregation {}", name),
+        };
+        Ok(aggregation)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aggregation::Mean => "mean",
+            Aggregation::Median => "median",
+            Aggregation::ConfidenceWeighted => "confidence-weighted",
+        }
+    }
+
+    /// Estimation of the activity, `None` without estimates.
+    pub fn aggregate(&self, estimates: &[&EstimatorEstimate]) -> Option<Estimation> {
+        if estimates.is_empty() {
+            return None;
+        }
+        let combine = |value: fn(&Estimation) -> EstimationValue| {
+            let values = estimates.iter().map(|estimate| value(&estimate.estimation));
+            match self {
+                Aggregation::Mean => values.sum::<f64>() / estimates.len() as f64,
+                Aggregation::Median => median(values.collect()),
+                Aggregation::ConfidenceWeighted => {
+                    let weights: f64 = estimates.iter().map(|estimate| estimate.confidence).sum();
+                    values
+                        .zip(estimates.iter())
+                        .map(|(value, estimate)| value * estimate.confidence)
+                        .sum::<f64>()
+                        / weights
+                }
+            }
+        };
+        Some(Estimation {
+            optimistic: combine(|estimation| estimation.optimistic),
+            probable: combine(|estimation| estimation.probable),
+            pessimistic: combine(|estimation| estimation.pessimistic),
+        })
+    }
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aggregation::Mean => write!(f, "mean"),
+            Aggregation::Median => write!(f, "median"),
+            Aggregation::ConfidenceWeighted => write!(f, "confidence-weighted mean"),
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2f64
+    } else {
+        values[middle]
+    }
+}
+
+/// Three-point estimate of an activity given by one estimator.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EstimatorEstimate {
+    pub estimator: String,
+    /// Rounds of an estimator are numbered from 1, only the last one counts.
+    pub round: u32,
+    pub estimation: Estimation,
+    /// In (0, 1], weight of the estimate in the confidence-weighted mean.
+    pub confidence: f64,
+}
+
+impl EstimatorEstimate {
+    pub fn validate(&self) -> Result<()> {
+        if self.estimator.trim().is_empty() {
+            bail!("The estimator needs a name");
+        }
+        if !(self.confidence > 0f64 && self.confidence <= 1f64) {
+            bail!("The confidence must be greater than 0 and at most 1");
+        }
+        self.estimation.validate()?;
+        Ok(())
+    }
+}
+
+/// Last estimate of every estimator, sorted by estimator.
+pub fn latest(estimates: &[EstimatorEstimate]) -> Vec<&EstimatorEstimate> {
+    let mut latest: Vec<&EstimatorEstimate> = vec![];
+    for estimate in estimates {
+        match latest
+            .iter_mut()
+            .find(|other| other.estimator == estimate.estimator)
+        {
+            Some(other) if other.round < estimate.round => *other = estimate,
+            Some(_) => {}
+            None => latest.push(estimate),
+        }
+    }
+    latest.sort_by(|a, b| a.estimator.cmp(&b.estimator));
+    latest
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EstimatorRow {
+    pub estimator: String,
+    pub round: u32,
+    pub optimistic: f64,
+    pub probable: f64,
+    pub pessimistic: f64,
+    /// Mean given by the estimation model of the PERT.
+    pub expected: f64,
+    pub confidence: f64,
+}
+
+/// Where the estimators of an activity stand.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Consensus {
+    pub id: ActivityId,
+    pub name: String,
+    /// Last estimate of every estimator.
+    pub estimates: Vec<EstimatorRow>,
+    /// Lowest and highest expected duration among the estimators.
+    pub lowest: Option<f64>,
+    pub highest: Option<f64>,
+    /// (highest - lowest) / aggregated expected duration.
+    pub spread: Option<f64>,
+    pub needs_another_round: bool,
+}
+
+/// Wideband Delphi view of a PERT: estimates of every estimator, and the activities
+/// they disagree on by more than the threshold.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Delphi {
+    #[serde(serialize_with = "serialize_aggregation")]
+    pub aggregation: Aggregation,
+    pub threshold: f64,
+    pub activities: Vec<Consensus>,
+}
+
+fn serialize_aggregation<S: serde::Serializer>(
+    aggregation: &Aggregation,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(aggregation.name())
+}
+
+impl Delphi {
+    /// `activities` carry their aggregated estimation, `estimates` are all the rounds
+    /// of every activity.
+    pub fn new(
+        activities: &[Activity],
+        estimates: &[(ActivityId, EstimatorEstimate)],
+        aggregation: Aggregation,
+        threshold: f64,
+    ) -> Self {
+        let activities = activities
+            .iter()
+            .map(|act| {
+                let own: Vec<EstimatorEstimate> = estimates
+                    .iter()
+                    .filter(|(activity_id, _)| *activity_id == act.id)
+                    .map(|(_, estimate)| estimate.clone())
+                    .collect();
+                let rows: Vec<EstimatorRow> = latest(&own)
+                    .into_iter()
+                    .map(|estimate| EstimatorRow {
+                        estimator: estimate.estimator.to_owned(),
+                        round: estimate.round,
+                        optimistic: estimate.estimation.optimistic,
+                        probable: estimate.estimation.probable,
+                        pessimistic: estimate.estimation.pessimistic,
+                        expected: act.model.mean(&estimate.estimation),
+                        confidence: estimate.confidence,
+                    })
+                    .collect();
+                let expected = rows.iter().map(|row| row.expected);
+                let lowest = expected.clone().reduce(f64::min);
+                let highest = expected.reduce(f64::max);
+                let spread = match (lowest, highest) {
+                    (Some(lowest), Some(highest)) if act.estimated() > 0f64 => {
+                        Some((highest - lowest) / act.estimated())
+                    }
+                    (Some(lowest), Some(highest)) if highest == lowest => Some(0f64),
+                    _ => None,
+                };
+                Consensus {
+                    id: act.id,
+                    name: act.name.to_owned(),
+                    needs_another_round: rows.len() > 1
+                        && spread.map_or(true, |spread| spread > threshold),
+                    estimates: rows,
+                    lowest,
+                    highest,
+                    spread,
+                }
+            })
+            .collect();
+        Self {
+            aggregation,
+            threshold,
+            activities,
+        }
+    }
+
+    pub fn needing_another_round(&self) -> Vec<&Consensus> {
+        self.activities
+            .iter()
+            .filter(|act| act.needs_another_round)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{latest, Aggregation, Delphi, EstimatorEstimate};
+    use crate::modules::activity::{Activity, Estimation};
+
+    fn estimate(
+        estimator: &str,
+        round: u32,
+        values: (f64, f64, f64),
+        confidence: f64,
+    ) -> EstimatorEstimate {
+        EstimatorEstimate {
+            estimator: estimator.to_string(),
+            round,
+            estimation: Estimation::new(values.0, values.1, values.2).unwrap(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn aggregations() {
+        let estimates = [
+            estimate("ann", 1, (1.0, 2.0, 3.0), 1.0),
+            estimate("bob", 1, (2.0, 4.0, 6.0), 0.5),
+            estimate("eve", 1, (3.0, 9.0, 12.0), 0.5),
+        ];
+        let estimates: Vec<&EstimatorEstimate> = estimates.iter().collect();
+        assert_eq!(
+            Aggregation::Mean.aggregate(&estimates),
+            Some(Estimation::new(2.0, 5.0, 7.0).unwrap())
+        );
+        assert_eq!(
+            Aggregation::Median.aggregate(&estimates),
+            Some(Estimation::new(2.0, 4.0, 6.0).unwrap())
+        );
+        assert_eq!(
+            Aggregation::ConfidenceWeighted.aggregate(&estimates),
+            Some(Estimation::new(1.75, 4.25, 6.0).unwrap())
+        );
+        assert_eq!(
+            Aggregation::Median
+                .aggregate(&estimates[..2])
+                .unwrap()
+                .probable,
+            3.0
+        );
+        assert_eq!(Aggregation::Mean.aggregate(&[]), None);
+        assert!(Aggregation::from_name("mode").is_err());
+    }
+
+    #[test]
+    fn validate_estimate() {
+        assert!(estimate("ann", 1, (1.0, 2.0, 3.0), 1.0).validate().is_ok());
+        assert!(estimate("ann", 1, (1.0, 2.0, 3.0), 0.0).validate().is_err());
+        assert!(estimate(" ", 1, (1.0, 2.0, 3.0), 1.0).validate().is_err());
+    }
+
+    #[test]
+    fn last_round_of_every_estimator() {
+        let estimates = [
+            estimate("bob", 1, (1.0, 2.0, 3.0), 1.0),
+            estimate("ann", 1, (2.0, 4.0, 6.0), 1.0),
+            estimate("bob", 2, (2.0, 3.0, 4.0), 1.0),
+        ];
+        let rounds: Vec<(&str, u32)> = latest(&estimates)
+            .iter()
+            .map(|estimate| (estimate.estimator.as_str(), estimate.round))
+            .collect();
+        assert_eq!(rounds, vec![("ann", 1), ("bob", 2)]);
+    }
+
+    #[test]
+    fn divergent_activities_need_another_round() {
+        // estimations are the mean of the estimates
+        let activities = vec![
+            Activity::new(1, "agreed".to_string(), 2.0, 2.1, 2.2).unwrap(),
+            Activity::new(2, "diverging".to_string(), 2.0, 4.0, 6.0).unwrap(),
+            Activity::new(3, "alone".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        let estimates = vec![
+            (1, estimate("ann", 1, (2.0, 2.0, 2.0), 1.0)),
+            (1, estimate("bob", 1, (2.2, 2.2, 2.4), 1.0)),
+            (2, estimate("ann", 1, (1.0, 2.0, 3.0), 1.0)),
+            (2, estimate("bob", 1, (3.0, 6.0, 9.0), 1.0)),
+            (3, estimate("ann", 1, (1.0, 1.0, 1.0), 1.0)),
+        ];
+        let delphi = Delphi::new(&activities, &estimates, Aggregation::Mean, 0.25);
+        let flagged: Vec<&str> = delphi
+            .needing_another_round()
+            .iter()
+            .map(|act| act.name.as_str())
+            .collect();
+        assert_eq!(flagged, vec!["diverging"]);
+        assert_eq!(delphi.activities[1].spread, Some(1.0));
+        assert_eq!(delphi.activities[1].lowest, Some(2.0));
+        assert_eq!(delphi.activities[1].highest, Some(6.0));
+        assert_eq!(delphi.activities[2].spread, Some(0.0));
+    }
+}
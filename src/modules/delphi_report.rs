@@ -0,0 +1,197 @@
+use anyhow::Result;
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    activity::ActivityId,
+    delphi::Delphi,
+    json_report::{to_json, PertJson},
+    pert::Pert,
+    pert_report::pert_header,
+    report::round,
+};
+
+fn describe(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |value| round(value).to_string())
+}
+
+#[derive(Table)]
+struct EstimateRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Estimator")]
+    estimator: String,
+    #[table(title = "Round", justify = "Justify::Right")]
+    round: u32,
+    #[table(title = "Optimistic")]
+    optimistic: f64,
+    #[table(title = "Probable")]
+    probable: f64,
+    #[table(title = "Pessimistic")]
+    pessimistic: f64,
+    #[table(title = "Pert")]
+    expected: f64,
+    #[table(title = "Confidence")]
+    confidence: f64,
+}
+
+#[derive(Table)]
+struct ConsensusRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Estimators", justify = "Justify::Right")]
+    estimators: usize,
+    #[table(title = "Lowest", justify = "Justify::Right")]
+    lowest: String,
+    #[table(title = "Highest", justify = "Justify::Right")]
+    highest: String,
+    #[table(title = "Spread", justify = "Justify::Right")]
+    spread: String,
+    #[table(title = "Another round")]
+    needs_another_round: bool,
+}
+
+pub struct DelphiReport {
+    pert: Pert,
+    delphi: Delphi,
+}
+
+impl DelphiReport {
+    pub fn new(pert: Pert, delphi: Delphi) -> Self {
+        Self { pert, delphi }
+    }
+
+    pub fn ascii(&mut self) -> String {
+        let header = format!(
+            "{}\nAGGREGATION: {}\nTHRESHOLD: {}%",
+            pert_header(&self.pert),
+            self.delphi.aggregation,
+            round(self.delphi.threshold * 100f64)
+        );
+        let estimates: Vec<EstimateRow> = self
+            .delphi
+            .activities
+            .iter()
+            .flat_map(|act| {
+                act.estimates.iter().map(|estimate| EstimateRow {
+                    id: act.id,
+                    name: act.name.to_owned(),
+                    estimator: estimate.estimator.to_owned(),
+                    round: estimate.round,
+                    optimistic: estimate.optimistic,
+                    probable: estimate.probable,
+                    pessimistic: estimate.pessimistic,
+                    expected: round(estimate.expected),
+                    confidence: estimate.confidence,
+                })
+            })
+            .collect();
+        if estimates.is_empty() {
+            return format!("{}\n\nNo estimates recorded yet", header);
+        }
+        let consensus: Vec<ConsensusRow> = self
+            .delphi
+            .activities
+            .iter()
+            .filter(|act| !act.estimates.is_empty())
+            .map(|act| ConsensusRow {
+                id: act.id,
+                name: act.name.to_owned(),
+                estimators: act.estimates.len(),
+                lowest: describe(act.lowest),
+                highest: describe(act.highest),
+                spread: act.spread.map_or("-".to_string(), |spread| {
+                    format!("{}%", round(spread * 100f64))
+                }),
+                needs_another_round: act.needs_another_round,
+            })
+            .collect();
+        let another_round: Vec<String> = self
+            .delphi
+            .needing_another_round()
+            .iter()
+            .map(|act| format!("{} {}", act.id, act.name))
+            .collect();
+        let another_round = if another_round.is_empty() {
+            "NO ACTIVITY NEEDS ANOTHER ROUND".to_string()
+        } else {
+            format!("ANOTHER ROUND FOR: {}", another_round.join(", "))
+        };
+
+        format!(
+            "{}\n\n{}\n{}\n{}",
+            header,
+            estimates.with_title().display().unwrap(),
+            consensus.with_title().display().unwrap(),
+            another_round
+        )
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct DelphiJsonReport<'a> {
+            pert: PertJson,
+            #[serde(flatten)]
+            delphi: &'a Delphi,
+        }
+
+        to_json(
+            "delphi",
+            DelphiJsonReport {
+                pert: (&self.pert).into(),
+                delphi: &self.delphi,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DelphiReport;
+    use crate::modules::{
+        activity::{Activity, Estimation},
+        delphi::{Aggregation, Delphi, EstimatorEstimate},
+        pert::Pert,
+    };
+
+    fn estimate(estimator: &str, round: u32, probable: f64) -> EstimatorEstimate {
+        EstimatorEstimate {
+            estimator: estimator.to_string(),
+            round,
+            estimation: Estimation::new(1.0, probable, 8.0).unwrap(),
+            confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn estimates_and_consensus() {
+        let activities = vec![
+            Activity::new(1, "design".to_string(), 1.0, 2.5, 8.0).unwrap(),
+            Activity::new(2, "build".to_string(), 1.0, 4.0, 8.0).unwrap(),
+            Activity::new(3, "test".to_string(), 1.0, 2.0, 3.0).unwrap(),
+        ];
+        let estimates = vec![
+            (1, estimate("ann", 1, 2.0)),
+            (1, estimate("bob", 1, 3.0)),
+            (2, estimate("ann", 1, 2.0)),
+            (2, estimate("bob", 1, 7.0)),
+            (2, estimate("ann", 2, 1.0)),
+        ];
+        let delphi = Delphi::new(&activities, &estimates, Aggregation::Mean, 0.25);
+        let mut report = DelphiReport::new(Pert::new(1, "example".to_string()), delphi);
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.json().unwrap());
+    }
+
+    #[test]
+    fn without_estimates() {
+        let activities = vec![Activity::new(1, "design".to_string(), 1.0, 2.0, 3.0).unwrap()];
+        let delphi = Delphi::new(&activities, &[], Aggregation::Median, 0.1);
+        let mut report = DelphiReport::new(Pert::new(1, "example".to_string()), delphi);
+        insta::assert_display_snapshot!(report.ascii());
+    }
+}
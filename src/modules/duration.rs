@@ -0,0 +1,189 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::fmt;
+
+pub const DEFAULT_HOURS_PER_DAY: f64 = 8f64;
+pub const DAYS_PER_WEEK: f64 = 5f64;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum DurationUnit {
+    Hours,
+    #[default]
+    Days,
+    Weeks,
+    Points,
+}
+
+impl DurationUnit {
+    pub fn from_name(name: &str) -> Result<Self> {
+        let unit = match name {
+            "h" | "hour" | "hours" => DurationUnit::Hours,
+            "d" | "day" | "days" => DurationUnit::Days,
+            "w" | "week" | "weeks" => DurationUnit::Weeks,
+            "p" | "pt" | "pts" | "point" | "points" => DurationUnit::Points,
+            _ => bail!("Unknown duration unit {}", name),
+        };
+        Ok(unit)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DurationUnit::Hours => "hours",
+            DurationUnit::Days => "days",
+            DurationUnit::Weeks => "weeks",
+            DurationUnit::Points => "points",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            DurationUnit::Hours => "h",
+            DurationUnit::Days => "d",
+            DurationUnit::Weeks => "w",
+            DurationUnit::Points => "pt",
+        }
+    }
+
+    fn hours(&self, hours_per_day: f64) -> Option<f64> {
+        match self {
+            DurationUnit::Hours => Some(1f64),
+            DurationUnit::Days => Some(hours_per_day),
+            DurationUnit::Weeks => Some(hours_per_day * DAYS_PER_WEEK),
+            DurationUnit::Points => None,
+        }
+    }
+}
+
+impl fmt::Display for DurationUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Duration {
+    pub value: f64,
+    pub unit: DurationUnit,
+}
+
+impl Duration {
+    pub fn new(value: f64, unit: DurationUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Parses inputs like `2.5`, `4h` or `3d 4h`, a number without a unit is in `default_unit`
+    /// and must be the whole input. Every unit is given once. The result is expressed in
+    /// `default_unit`.
+    pub fn parse(input: &str, default_unit: DurationUnit, hours_per_day: f64) -> Result<Self> {
+        let regex = Regex::new(r"^(?P<value>[0-9]+(\.[0-9]+)?)\s*(?P<unit>[a-z]*)$").unwrap();
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        if parts.is_empty() {
+            bail!("Missing duration");
+        }
+        let mut total = 0f64;
+        let mut units = vec![];
+        let mut index = 0;
+        while index < parts.len() {
+            // both "3d" and "3 d" are accepted
+            let mut part = parts[index].to_lowercase();
+            if let Some(next) = parts.get(index + 1) {
+                if next.chars().all(|c| c.is_alphabetic()) {
+                    part.push_str(next);
+                    index += 1;
+                }
+            }
+            index += 1;
+
+            let group = regex
+                .captures(&part)
+                .with_context(|| format!("Invalid duration {}", input))?;
+            let value: f64 = group.name("value").unwrap().as_str().parse()?;
+            let unit = match group.name("unit").unwrap().as_str() {
+                // "3 4" is a typo rather than 7
+                "" if parts.len() > 1 => bail!("Invalid duration {}", input),
+                "" => default_unit,
+                unit => DurationUnit::from_name(unit)?,
+            };
+            if units.contains(&unit) {
+                bail!("Invalid duration {}, {} given twice", input, unit);
+            }
+            units.push(unit);
+            total += Duration::new(value, unit).to_unit(default_unit, hours_per_day)?;
+        }
+        Ok(Duration::new(total, default_unit))
+    }
+
+    pub fn to_unit(&self, unit: DurationUnit, hours_per_day: f64) -> Result<f64> {
+        if self.unit == unit {
+            return Ok(self.value);
+        }
+        match (self.unit.hours(hours_per_day), unit.hours(hours_per_day)) {
+            (Some(from), Some(to)) => Ok(self.value * from / to),
+            _ => bail!("Unable to convert {} to {}", self.unit, unit),
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit.symbol())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Duration, DurationUnit, DEFAULT_HOURS_PER_DAY};
+
+    fn parse(input: &str, unit: DurationUnit) -> f64 {
+        Duration::parse(input, unit, DEFAULT_HOURS_PER_DAY)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse("2.5", DurationUnit::Days), 2.5);
+        assert_eq!(parse("4h", DurationUnit::Hours), 4.0);
+        assert_eq!(parse("3d 4h", DurationUnit::Days), 3.5);
+        assert_eq!(parse("3d 4h", DurationUnit::Hours), 28.0);
+        assert_eq!(parse("1w 2 days", DurationUnit::Days), 7.0);
+        assert_eq!(parse("5 points", DurationUnit::Points), 5.0);
+    }
+
+    #[test]
+    fn parse_invalid_durations() {
+        let error = Duration::parse("3x", DurationUnit::Days, DEFAULT_HOURS_PER_DAY)
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(error, "Unknown duration unit x".to_string());
+        let error = Duration::parse("3pt", DurationUnit::Days, DEFAULT_HOURS_PER_DAY)
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(error, "Unable to convert points to days".to_string());
+        assert!(Duration::parse("-3d", DurationUnit::Days, DEFAULT_HOURS_PER_DAY).is_err());
+        assert!(Duration::parse("", DurationUnit::Days, DEFAULT_HOURS_PER_DAY).is_err());
+        let error = Duration::parse("3 4", DurationUnit::Days, DEFAULT_HOURS_PER_DAY)
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(error, "Invalid duration 3 4".to_string());
+        assert!(Duration::parse("3d 4", DurationUnit::Days, DEFAULT_HOURS_PER_DAY).is_err());
+        let error = Duration::parse("3d 3d", DurationUnit::Days, DEFAULT_HOURS_PER_DAY)
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            error,
+            "Invalid duration 3d 3d, days given twice".to_string()
+        );
+    }
+
+    #[test]
+    fn convert_with_hours_per_day() {
+        let duration = Duration::new(15.0, DurationUnit::Hours);
+        assert_eq!(duration.to_unit(DurationUnit::Days, 7.5).unwrap(), 2.0);
+        assert_eq!(duration.to_unit(DurationUnit::Weeks, 7.5).unwrap(), 0.4);
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::{bail, Result};
+use std::fmt;
+
+use super::activity::Estimation;
+
+pub const DEFAULT_LAMBDA: f64 = 4f64;
+
+/// How the three-point estimation of an activity is turned into its expected duration.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum EstimationModel {
+    /// `(o + 4m + p) / 6`
+    #[default]
+    Classic,
+    /// `(o + m + p) / 3`
+    Triangular,
+    /// `(o + λm + p) / (λ + 2)`, the classic PERT is the case λ = 4
+    ModifiedPert { lambda: f64 },
+    /// `(o + 3m + 2p) / 6`, moves weight from the most probable to the pessimistic value
+    PessimismWeighted,
+}
+
+impl EstimationModel {
+    /// `lambda` is only accepted for the modified PERT, and must be positive and finite.
+    pub fn from_name(name: &str, lambda: Option<f64>) -> Result<Self> {
+        if lambda.is_some() && name != "modified-pert" {
+            bail!("Lambda is only used by the modified PERT, not by {}", name);
+        }
+        let model = match name {
+            "classic" => EstimationModel::Classic,
+            "triangular" => EstimationModel::Triangular,
+            "modified-pert" => {
+                let lambda = lambda.unwrap_or(DEFAULT_LAMBDA);
+                if !(lambda.is_finite() && lambda > 0f64) {
+                    bail!("Lambda of the modified PERT must be positive");
+                }
+                EstimationModel::ModifiedPert { lambda }
+            }
+            "pessimism-weighted" => EstimationModel::PessimismWeighted,
+            _ => bail!("Unknown estimation model {}", name),
+        };
+        Ok(model)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EstimationModel::Classic => "classic",
+            EstimationModel::Triangular => "triangular",
+            EstimationModel::ModifiedPert { .. } => "modified-pert",
+            EstimationModel::PessimismWeighted => "pessimism-weighted",
+        }
+    }
+
+    pub fn lambda(&self) -> Option<f64> {
+        match self {
+            EstimationModel::ModifiedPert { lambda } => Some(*lambda),
+            _ => None,
+        }
+    }
+
+    pub fn mean(&self, estimation: &Estimation) -> f64 {
+        let (to, tm, tp) = values(estimation);
+        match self {
+            EstimationModel::Classic => estimation.estimated(),
+            EstimationModel::Triangular => (to + tm + tp) / 3f64,
+            EstimationModel::ModifiedPert { lambda } => (to + lambda * tm + tp) / (lambda + 2f64),
+            EstimationModel::PessimismWeighted => (to + 3f64 * tm + 2f64 * tp) / 6f64,
+        }
+    }
+
+    /// The classic and pessimism-weighted models keep the textbook approximation
+    /// `((p - o) / 6)²`. The modified PERT uses the exact variance of its beta
+    /// distribution, the one sampled by the simulation, so with λ = 4 it has the mean
+    /// of the classic model but not its variance.
+    pub fn variance(&self, estimation: &Estimation) -> f64 {
+        let (to, tm, tp) = values(estimation);
+        match self {
+            EstimationModel::Classic | EstimationModel::PessimismWeighted => estimation.variance(),
+            EstimationModel::Triangular => {
+                (to * to + tm * tm + tp * tp - to * tm - to * tp - tm * tp) / 18f64
+            }
+            EstimationModel::ModifiedPert { lambda } => {
+                let mean = self.mean(estimation);
+                (mean - to) * (tp - mean) / (lambda + 3f64)
+            }
+        }
+    }
+}
+
+fn values(estimation: &Estimation) -> (f64, f64, f64) {
+    (
+        estimation.optimistic,
+        estimation.probable,
+        estimation.pessimistic,
+    )
+}
+
+impl fmt::Display for EstimationModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstimationModel::Classic => write!(f, "Classic PERT (o + 4m + p) / 6"),
+            EstimationModel::Triangular => write!(f, "Triangular (o + m + p) / 3"),
+            EstimationModel::ModifiedPert { lambda } => {
+                write!(f, "Modified PERT (o + {}m + p) / {}", lambda, lambda + 2f64)
+            }
+            EstimationModel::PessimismWeighted => {
+                write!(f, "Pessimism-weighted PERT (o + 3m + 2p) / 6")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EstimationModel;
+    use crate::modules::activity::Estimation;
+
+    const ESTIMATION: Estimation = Estimation {
+        optimistic: 6.0,
+        probable: 12.0,
+        pessimistic: 24.0,
+    };
+
+    #[test]
+    fn mean_by_model() {
+        assert_eq!(EstimationModel::Classic.mean(&ESTIMATION), 13.0);
+        assert_eq!(EstimationModel::Triangular.mean(&ESTIMATION), 14.0);
+        assert_eq!(
+            EstimationModel::ModifiedPert { lambda: 4.0 }.mean(&ESTIMATION),
+            EstimationModel::Classic.mean(&ESTIMATION)
+        );
+        assert_eq!(
+            EstimationModel::ModifiedPert { lambda: 1.0 }.mean(&ESTIMATION),
+            14.0
+        );
+        assert_eq!(EstimationModel::PessimismWeighted.mean(&ESTIMATION), 15.0);
+    }
+
+    #[test]
+    fn variance_by_model() {
+        assert_eq!(EstimationModel::Classic.variance(&ESTIMATION), 9.0);
+        assert_eq!(EstimationModel::Triangular.variance(&ESTIMATION), 14.0);
+        // the exact variance of the beta distribution, not the approximation of the classic
+        assert_eq!(
+            EstimationModel::ModifiedPert { lambda: 4.0 }.variance(&ESTIMATION),
+            11.0
+        );
+    }
+
+    #[test]
+    fn from_name() {
+        assert_eq!(
+            EstimationModel::from_name("modified-pert", Some(3.0)).unwrap(),
+            EstimationModel::ModifiedPert { lambda: 3.0 }
+        );
+        assert_eq!(
+            EstimationModel::from_name("modified-pert", None).unwrap(),
+            EstimationModel::ModifiedPert { lambda: 4.0 }
+        );
+        let error = EstimationModel::from_name("foobar", None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(error, "Unknown estimation model foobar".to_string());
+        for lambda in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(EstimationModel::from_name("modified-pert", Some(lambda)).is_err());
+        }
+        assert!(EstimationModel::from_name("classic", Some(3.0)).is_err());
+    }
+}
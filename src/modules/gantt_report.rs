@@ -0,0 +1,251 @@
+use super::{
+    activity::ActivityId, critical_path::CriticalPath, duration::DurationUnit,
+    graph::DependencyGraph,
+};
+
+const ASCII_WIDTH: usize = 60;
+const SVG_LABEL_WIDTH: f64 = 200f64;
+const SVG_CHART_WIDTH: f64 = 600f64;
+const SVG_ROW_HEIGHT: f64 = 30f64;
+const SVG_BAR_HEIGHT: f64 = 18f64;
+const SVG_AXIS_HEIGHT: f64 = 30f64;
+const SVG_TICKS: usize = 10;
+const CRITICAL_COLOR: &str = "#e74c3c";
+const COLOR: &str = "#4793e3";
+
+struct GanttBar {
+    id: ActivityId,
+    name: String,
+    start: f64,
+    duration: f64,
+    critical: bool,
+}
+
+impl GanttBar {
+    fn finish(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// Timeline of the activities, each one starting at its earliest start.
+pub struct GanttReport {
+    bars: Vec<GanttBar>,
+    /// (head, tail, critical), the tail starts after the head is completed.
+    dependencies: Vec<(ActivityId, ActivityId, bool)>,
+    duration: f64,
+    unit: DurationUnit,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl GanttReport {
+    pub fn new(critical_path: &CriticalPath, graph: &DependencyGraph, unit: DurationUnit) -> Self {
+        let mut bars: Vec<GanttBar> = critical_path
+            .activities
+            .iter()
+            .map(|act| GanttBar {
+                id: act.id,
+                name: act.name.to_owned(),
+                start: act.earliest_start,
+                duration: act.duration,
+                critical: act.is_critical(),
+            })
+            .collect();
+        bars.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let dependencies = bars
+            .iter()
+            .flat_map(|bar| {
+                graph.predecessors(bar.id).iter().map(move |head| {
+                    let critical = critical_path.is_critical_dependency(*head, bar.id);
+                    (*head, bar.id, critical)
+                })
+            })
+            .collect();
+        Self {
+            bars,
+            dependencies,
+            duration: critical_path.duration,
+            unit,
+        }
+    }
+
+    fn position(&self, id: ActivityId) -> Option<usize> {
+        self.bars.iter().position(|bar| bar.id == id)
+    }
+
+    fn scale(&self, width: f64) -> f64 {
+        if self.duration > 0f64 {
+            width / self.duration
+        } else {
+            0f64
+        }
+    }
+
+    pub fn svg(&self) -> String {
+        let scale = self.scale(SVG_CHART_WIDTH);
+        let height = SVG_AXIS_HEIGHT + SVG_ROW_HEIGHT * self.bars.len() as f64;
+        let mut elements = vec![];
+
+        for tick in 0..=SVG_TICKS {
+            let value = self.duration * tick as f64 / SVG_TICKS as f64;
+            let x = SVG_LABEL_WIDTH + value * scale;
+            elements.push(format!(
+                r##"<line x1="{x:.1}" y1="{top}" x2="{x:.1}" y2="{height}" stroke="#dddddd"/><text x="{x:.1}" y="{label}" font-size="10" text-anchor="middle">{value:.1}</text>"##,
+                top = SVG_AXIS_HEIGHT - 5f64,
+                label = SVG_AXIS_HEIGHT - 10f64,
+            ));
+        }
+
+        for (row, bar) in self.bars.iter().enumerate() {
+            let y = SVG_AXIS_HEIGHT + SVG_ROW_HEIGHT * row as f64;
+            let bar_y = y + (SVG_ROW_HEIGHT - SVG_BAR_HEIGHT) / 2f64;
+            elements.push(format!(
+                r#"<text x="5" y="{text_y:.1}" font-size="12">{}</text><rect class="{class}" x="{x:.1}" y="{bar_y:.1}" width="{width:.1}" height="{SVG_BAR_HEIGHT}" fill="{color}"><title>{} ({} - {} {})</title></rect>"#,
+                escape(&bar.name),
+                escape(&bar.name),
+                bar.start,
+                bar.finish(),
+                self.unit,
+                text_y = bar_y + SVG_BAR_HEIGHT - 4f64,
+                class = if bar.critical { "bar critical" } else { "bar" },
+                x = SVG_LABEL_WIDTH + bar.start * scale,
+                width = (bar.duration * scale).max(1f64),
+                color = if bar.critical { CRITICAL_COLOR } else { COLOR },
+            ));
+        }
+
+        for &(head, tail, critical) in &self.dependencies {
+            let (head_row, tail_row) = match (self.position(head), self.position(tail)) {
+                (Some(head_row), Some(tail_row)) => (head_row, tail_row),
+                _ => continue,
+            };
+            let (head, tail) = (&self.bars[head_row], &self.bars[tail_row]);
+            let from_y = SVG_AXIS_HEIGHT + SVG_ROW_HEIGHT * (head_row as f64 + 0.5);
+            // the arrow goes right from the end of the head and enters the tail bar vertically
+            let to_x = SVG_LABEL_WIDTH + tail.start * scale + 4f64;
+            let tail_y = SVG_AXIS_HEIGHT + SVG_ROW_HEIGHT * tail_row as f64;
+            let to_y = if tail_row > head_row {
+                tail_y + (SVG_ROW_HEIGHT - SVG_BAR_HEIGHT) / 2f64
+            } else {
+                tail_y + (SVG_ROW_HEIGHT + SVG_BAR_HEIGHT) / 2f64
+            };
+            elements.push(format!(
+                r#"<path class="{class}" d="M {from_x:.1} {from_y:.1} H {to_x:.1} V {to_y:.1}" fill="none" stroke="{color}" marker-end="url(#{marker})"/>"#,
+                class = if critical { "dependency critical" } else { "dependency" },
+                from_x = SVG_LABEL_WIDTH + head.finish() * scale,
+                color = if critical { CRITICAL_COLOR } else { "#555555" },
+                marker = if critical { "arrow-critical" } else { "arrow" },
+            ));
+        }
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="Helvetica" class="gantt">
+<defs>
+<marker id="arrow" markerWidth="6" markerHeight="6" refX="6" refY="3" orient="auto"><path d="M 0 0 L 6 3 L 0 6 z" fill="#555555"/></marker>
+<marker id="arrow-critical" markerWidth="6" markerHeight="6" refX="6" refY="3" orient="auto"><path d="M 0 0 L 6 3 L 0 6 z" fill="{CRITICAL_COLOR}"/></marker>
+</defs>
+<rect width="100%" height="100%" fill="white"/>
+{}
+</svg>"##,
+            elements.join("\n"),
+            width = SVG_LABEL_WIDTH + SVG_CHART_WIDTH + 20f64,
+        )
+    }
+
+    pub fn ascii(&self) -> String {
+        let scale = self.scale(ASCII_WIDTH as f64);
+        let label_width = self
+            .bars
+            .iter()
+            .map(|bar| format!("{} {}", bar.id, bar.name).chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines: Vec<String> = self
+            .bars
+            .iter()
+            .map(|bar| {
+                let from = (bar.start * scale).round() as usize;
+                let to = ((bar.finish() * scale).round() as usize).max(from + 1);
+                let symbol = if bar.critical { '#' } else { '=' };
+                let timeline: String = (0..ASCII_WIDTH)
+                    .map(|column| {
+                        if column >= from && column < to {
+                            symbol
+                        } else {
+                            ' '
+                        }
+                    })
+                    .collect();
+                let predecessors = self
+                    .dependencies
+                    .iter()
+                    .filter(|(_, tail, _)| *tail == bar.id)
+                    .map(|(head, _, _)| head.to_string())
+                    .collect::<Vec<_>>();
+                let label = format!("{} {}", bar.id, bar.name);
+                let line = format!("{:<label_width$} |{}|", label, timeline);
+                if predecessors.is_empty() {
+                    line
+                } else {
+                    format!("{} <- {}", line, predecessors.join(", "))
+                }
+            })
+            .collect();
+        lines.push(format!(
+            "{:<label_width$} 0{:>width$} {}",
+            "",
+            self.duration,
+            self.unit,
+            width = ASCII_WIDTH + 1
+        ));
+        lines.push("# critical path, = other activities, <- dependencies".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GanttReport;
+    use crate::modules::{
+        activity::Activity, critical_path::CriticalPath, duration::DurationUnit,
+        graph::DependencyGraph, test_util::dep,
+    };
+
+    fn example_gantt() -> GanttReport {
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 6.0, 6.0, 6.0).unwrap(),
+            Activity::new(3, "activity <3>".to_string(), 3.0, 3.0, 3.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        let dependencies = [
+            (1, None),
+            (2, Some(1)),
+            (3, Some(1)),
+            (4, Some(2)),
+            (4, Some(3)),
+        ]
+        .into_iter()
+        .map(|(id, head)| dep(id, head))
+        .collect::<Vec<_>>();
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        GanttReport::new(&critical_path, &graph, DurationUnit::Days)
+    }
+
+    #[test]
+    fn gantt_ascii() {
+        insta::assert_display_snapshot!(example_gantt().ascii());
+    }
+
+    #[test]
+    fn gantt_svg() {
+        insta::assert_display_snapshot!(example_gantt().svg());
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::{bail, Result};
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    activity::{Activity, ActivityId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    nodes: Vec<ActivityId>,
+    predecessors: HashMap<ActivityId, Vec<ActivityId>>,
+    successors: HashMap<ActivityId, Vec<ActivityId>>,
+}
+
+impl DependencyGraph {
+    pub fn new(acts_with_deps: &[ActivityWithRelatedDependencies]) -> Self {
+        let mut graph = Self::default();
+        for act in acts_with_deps {
+            if !graph.nodes.contains(&act.activity_id) {
+                graph.nodes.push(act.activity_id);
+            }
+        }
+        for act in acts_with_deps {
+            if let Some(head_id) = act.activity_id_head {
+                // a dependency pointing outside of the PERT cannot be scheduled
+                if graph.nodes.contains(&head_id) {
+                    graph.add_edge(head_id, act.activity_id);
+                }
+            }
+        }
+        graph
+    }
+
+    fn add_edge(&mut self, head: ActivityId, tail: ActivityId) {
+        let predecessors = self.predecessors.entry(tail).or_default();
+        if predecessors.contains(&head) {
+            return;
+        }
+        predecessors.push(head);
+        self.successors.entry(head).or_default().push(tail);
+    }
+
+    pub fn nodes(&self) -> &[ActivityId] {
+        &self.nodes
+    }
+
+    pub fn predecessors(&self, id: ActivityId) -> &[ActivityId] {
+        self.predecessors.get(&id).map_or(&[], |ids| ids.as_slice())
+    }
+
+    pub fn successors(&self, id: ActivityId) -> &[ActivityId] {
+        self.successors.get(&id).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Chain of activities going from `from` to `to` following the dependencies, if any.
+    pub fn path(&self, from: ActivityId, to: ActivityId) -> Option<Vec<ActivityId>> {
+        let mut previous: HashMap<ActivityId, ActivityId> = HashMap::new();
+        let mut queue = vec![from];
+        let mut next = 0;
+        while next < queue.len() {
+            let id = queue[next];
+            next += 1;
+            if id == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = previous[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for successor in self.successors(id) {
+                if *successor != from && !previous.contains_key(successor) {
+                    previous.insert(*successor, id);
+                    queue.push(*successor);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every elementary cycle, each one starting and ending with the activity of the
+    /// cycle that comes first in `nodes`. Found with Johnson's algorithm, so cycles
+    /// sharing dependencies are all listed.
+    pub fn cycles(&self) -> Vec<Vec<ActivityId>> {
+        let mut cycles = vec![];
+        for start in 0..self.nodes.len() {
+            let mut search = CycleSearch {
+                graph: self,
+                allowed: &self.nodes[start..],
+                stack: vec![],
+                blocked: HashSet::new(),
+                blocked_by: HashMap::new(),
+                cycles: &mut cycles,
+            };
+            search.circuit(self.nodes[start]);
+        }
+        cycles
+    }
+
+    /// Activities ordered so that every activity comes after all of its predecessors.
+    pub fn topological_order(&self) -> Result<Vec<ActivityId>> {
+        let mut in_degree: HashMap<ActivityId, usize> = self
+            .nodes
+            .iter()
+            .map(|id| (*id, self.predecessors(*id).len()))
+            .collect();
+        let mut ready: Vec<ActivityId> = self
+            .nodes
+            .iter()
+            .filter(|id| in_degree[id] == 0)
+            .copied()
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut next = 0;
+        while next < ready.len() {
+            let id = ready[next];
+            next += 1;
+            order.push(id);
+            for successor in self.successors(id) {
+                let degree = in_degree.get_mut(successor).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(*successor);
+                }
+            }
+        }
+        if order.len() != self.nodes.len() {
+            bail!("The dependencies between activities contain a cycle");
+        }
+        Ok(order)
+    }
+}
+
+/// Cycles through the first of the `allowed` activities, those through an earlier
+/// activity having been found already.
+struct CycleSearch<'a> {
+    graph: &'a DependencyGraph,
+    allowed: &'a [ActivityId],
+    stack: Vec<ActivityId>,
+    /// Activities that cannot lead back to the start without going through the stack.
+    blocked: HashSet<ActivityId>,
+    /// Activities to unblock when the key is unblocked.
+    blocked_by: HashMap<ActivityId, HashSet<ActivityId>>,
+    cycles: &'a mut Vec<Vec<ActivityId>>,
+}
+
+impl CycleSearch<'_> {
+    fn circuit(&mut self, id: ActivityId) -> bool {
+        let graph = self.graph;
+        let start = self.allowed[0];
+        let successors = graph
+            .successors(id)
+            .iter()
+            .filter(|successor| self.allowed.contains(successor));
+        let mut found = false;
+        self.stack.push(id);
+        self.blocked.insert(id);
+        for successor in successors.clone() {
+            if *successor == start {
+                let mut cycle = self.stack.clone();
+                cycle.push(start);
+                self.cycles.push(cycle);
+                found = true;
+            } else if !self.blocked.contains(successor) && self.circuit(*successor) {
+                found = true;
+            }
+        }
+        if found {
+            self.unblock(id);
+        } else {
+            for successor in successors {
+                self.blocked_by.entry(*successor).or_default().insert(id);
+            }
+        }
+        self.stack.pop();
+        found
+    }
+
+    fn unblock(&mut self, id: ActivityId) {
+        self.blocked.remove(&id);
+        for blocked in self.blocked_by.remove(&id).unwrap_or_default() {
+            if self.blocked.contains(&blocked) {
+                self.unblock(blocked);
+            }
+        }
+    }
+}
+
+/// Human readable chain of activities, e.g. `"A" -> "B" -> "C"`.
+pub fn describe_path(path: &[ActivityId], activities: &[Activity]) -> String {
+    path.iter()
+        .map(|id| match activities.iter().find(|act| act.id == *id) {
+            Some(act) => format!("\"{}\"", act.name),
+            None => id.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{describe_path, DependencyGraph};
+    use crate::modules::{activity::Activity, test_util::dep};
+
+    #[test]
+    fn topological_order() {
+        let graph = DependencyGraph::new(&[
+            dep(3, Some(2)),
+            dep(3, Some(1)),
+            dep(1, None),
+            dep(2, Some(1)),
+        ]);
+        assert_eq!(graph.topological_order().unwrap(), vec![1, 2, 3]);
+        assert_eq!(graph.predecessors(3), &[2, 1]);
+        assert_eq!(graph.successors(1), &[3, 2]);
+    }
+
+    #[test]
+    fn topological_order_with_cycle() {
+        let graph = DependencyGraph::new(&[dep(1, Some(2)), dep(2, Some(1))]);
+        let error = graph.topological_order().err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "The dependencies between activities contain a cycle".to_string()
+        );
+    }
+
+    #[test]
+    fn path_between_activities() {
+        let graph = DependencyGraph::new(&[
+            dep(1, None),
+            dep(2, Some(1)),
+            dep(3, Some(2)),
+            dep(4, Some(1)),
+        ]);
+        assert_eq!(graph.path(1, 3), Some(vec![1, 2, 3]));
+        assert_eq!(graph.path(3, 1), None);
+        assert_eq!(graph.path(4, 3), None);
+    }
+
+    #[test]
+    fn find_overlapping_cycles() {
+        // A -> B -> C -> A and A -> C -> A share the dependency C -> A
+        let graph = DependencyGraph::new(&[
+            dep(1, Some(3)),
+            dep(2, Some(1)),
+            dep(3, Some(2)),
+            dep(3, Some(1)),
+        ]);
+        assert_eq!(graph.cycles(), vec![vec![1, 2, 3, 1], vec![1, 3, 1]]);
+    }
+
+    #[test]
+    fn find_every_cycle() {
+        let graph = DependencyGraph::new(&[
+            dep(1, Some(3)),
+            dep(2, Some(1)),
+            dep(3, Some(2)),
+            dep(4, Some(4)),
+            dep(5, None),
+        ]);
+        assert_eq!(graph.cycles(), vec![vec![1, 2, 3, 1], vec![4, 4]]);
+        let activities = vec![
+            Activity::new(1, "A".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(2, "B".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        assert_eq!(
+            describe_path(&[1, 2, 3], &activities),
+            "\"A\" -> \"B\" -> 3".to_string()
+        );
+    }
+}
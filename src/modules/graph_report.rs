@@ -0,0 +1,252 @@
+use super::{
+    activity::{Activity, ActivityId},
+    critical_path::CriticalPath,
+    graph::DependencyGraph,
+    pert::Pert,
+    report::round,
+    roadmap::Roadmap,
+};
+
+const CRITICAL_COLOR: &str = "#e74c3c";
+
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+struct GraphNode {
+    id: ActivityId,
+    name: String,
+    mean: f64,
+    standard_deviation: f64,
+}
+
+struct GraphEdge {
+    head: ActivityId,
+    tail: ActivityId,
+    critical: bool,
+}
+
+/// Network of the activities of a PERT, to be rendered by Graphviz or Mermaid.
+pub struct GraphReport {
+    pert: Pert,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    /// Activity ids of every phase of the roadmap, when nodes are clustered by phase.
+    phases: Option<Vec<Vec<ActivityId>>>,
+}
+
+fn node_id(id: ActivityId) -> String {
+    format!("a{}", id)
+}
+
+impl GraphReport {
+    pub fn new(
+        pert: Pert,
+        activities: &[Activity],
+        graph: &DependencyGraph,
+        critical_path: &CriticalPath,
+        roadmap: Option<Roadmap>,
+    ) -> Self {
+        let nodes = activities
+            .iter()
+            .map(|act| GraphNode {
+                id: act.id,
+                name: act.name.to_owned(),
+                mean: act.estimated(),
+                standard_deviation: act.standard_deviation(),
+            })
+            .collect();
+        let edges = graph
+            .nodes()
+            .iter()
+            .flat_map(|tail| {
+                graph.predecessors(*tail).iter().map(|head| GraphEdge {
+                    head: *head,
+                    tail: *tail,
+                    critical: critical_path.is_critical_dependency(*head, *tail),
+                })
+            })
+            .collect();
+        let phases = roadmap.map(|roadmap| {
+            roadmap
+                .phases
+                .into_iter()
+                .map(|phase| phase.activities.into_iter().map(|act| act.id).collect())
+                .collect()
+        });
+        Self {
+            pert,
+            nodes,
+            edges,
+            phases,
+        }
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.dot(),
+            GraphFormat::Mermaid => self.mermaid(),
+        }
+    }
+
+    fn label(&self, node: &GraphNode, new_line: &str) -> String {
+        format!(
+            "{}{new_line}PERT: {} {unit}{new_line}σ: {} {unit}",
+            node.name,
+            round(node.mean),
+            round(node.standard_deviation),
+            unit = self.pert.unit.symbol(),
+        )
+    }
+
+    /// Nodes grouped by phase, a single group without title when not clustered.
+    fn groups(&self) -> Vec<(Option<usize>, Vec<&GraphNode>)> {
+        match &self.phases {
+            Some(phases) => phases
+                .iter()
+                .enumerate()
+                .map(|(index, ids)| {
+                    let nodes = ids
+                        .iter()
+                        .filter_map(|id| self.nodes.iter().find(|node| node.id == *id))
+                        .collect();
+                    (Some(index + 1), nodes)
+                })
+                .collect(),
+            None => vec![(None, self.nodes.iter().collect())],
+        }
+    }
+
+    pub fn dot(&self) -> String {
+        let mut lines = vec![
+            format!("digraph \"{}\" {{", self.pert.name.replace('"', "\\\"")),
+            "    rankdir=LR;".to_string(),
+            "    node [shape=box, style=rounded];".to_string(),
+        ];
+        for (phase, nodes) in self.groups() {
+            let indent = match phase {
+                Some(phase) => {
+                    lines.push(format!("    subgraph cluster_phase_{} {{", phase));
+                    lines.push(format!("        label=\"Phase #{}\";", phase));
+                    "        "
+                }
+                None => "    ",
+            };
+            for node in nodes {
+                lines.push(format!(
+                    "{}{} [label=\"{}\"];",
+                    indent,
+                    node_id(node.id),
+                    self.label(node, "\\n").replace('"', "\\\"")
+                ));
+            }
+            if phase.is_some() {
+                lines.push("    }".to_string());
+            }
+        }
+        for edge in &self.edges {
+            let style = if edge.critical {
+                format!(" [color=\"{}\", penwidth=2]", CRITICAL_COLOR)
+            } else {
+                String::new()
+            };
+            lines.push(format!(
+                "    {} -> {}{};",
+                node_id(edge.head),
+                node_id(edge.tail),
+                style
+            ));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Critical dependencies are drawn as thick links.
+    pub fn mermaid(&self) -> String {
+        let mut lines = vec!["flowchart LR".to_string()];
+        for (phase, nodes) in self.groups() {
+            let indent = match phase {
+                Some(phase) => {
+                    lines.push(format!("    subgraph phase{} [Phase #{}]", phase, phase));
+                    "        "
+                }
+                None => "    ",
+            };
+            for node in nodes {
+                lines.push(format!(
+                    "{}{}[\"{}\"]",
+                    indent,
+                    node_id(node.id),
+                    self.label(node, "<br/>").replace('"', "#quot;")
+                ));
+            }
+            if phase.is_some() {
+                lines.push("    end".to_string());
+            }
+        }
+        for edge in &self.edges {
+            let arrow = if edge.critical { "==>" } else { "-->" };
+            lines.push(format!(
+                "    {} {} {}",
+                node_id(edge.head),
+                arrow,
+                node_id(edge.tail)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GraphReport;
+    use crate::modules::{
+        activity::Activity, critical_path::CriticalPath, graph::DependencyGraph, pert::Pert,
+        roadmap::Roadmap, test_util::dep,
+    };
+
+    fn example_report(clustered: bool) -> GraphReport {
+        let dependencies = [
+            (1, None),
+            (2, Some(1)),
+            (3, Some(1)),
+            (4, Some(2)),
+            (4, Some(3)),
+        ]
+        .into_iter()
+        .map(|(id, head)| dep(id, head))
+        .collect::<Vec<_>>();
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 1.0, 2.0, 3.0).unwrap(),
+            Activity::new(2, "activity \"2\"".to_string(), 4.0, 6.0, 8.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 0.5, 1.0, 2.0).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let roadmap = clustered.then(|| Roadmap::new(dependencies).unwrap());
+        let pert = Pert::new(1, "example".to_string());
+        GraphReport::new(pert, &activities, &graph, &critical_path, roadmap)
+    }
+
+    #[test]
+    fn graph_dot() {
+        insta::assert_display_snapshot!(example_report(false).dot());
+    }
+
+    #[test]
+    fn graph_dot_clustered() {
+        insta::assert_display_snapshot!(example_report(true).dot());
+    }
+
+    #[test]
+    fn graph_mermaid() {
+        insta::assert_display_snapshot!(example_report(false).mermaid());
+    }
+
+    #[test]
+    fn graph_mermaid_clustered() {
+        insta::assert_display_snapshot!(example_report(true).mermaid());
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::pert::{Pert, PertId};
+
+/// Increased on every breaking change of the JSON documents, new fields can be added
+/// within the same version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Document<T: Serialize> {
+    schema_version: u32,
+    kind: &'static str,
+    data: T,
+}
+
+/// Wraps `data` with the schema version and the kind of document.
+pub fn to_json<T: Serialize>(kind: &'static str, data: T) -> Result<String> {
+    let document = Document {
+        schema_version: JSON_SCHEMA_VERSION,
+        kind,
+        data,
+    };
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+#[derive(Serialize)]
+pub struct EstimationModelJson {
+    name: &'static str,
+    lambda: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct PertJson {
+    id: PertId,
+    name: String,
+    estimation_model: EstimationModelJson,
+    unit: &'static str,
+    hours_per_day: f64,
+    start_date: Option<String>,
+    working_days: Vec<String>,
+}
+
+impl From<&Pert> for PertJson {
+    fn from(pert: &Pert) -> Self {
+        Self {
+            id: pert.id,
+            name: pert.name.to_owned(),
+            estimation_model: EstimationModelJson {
+                name: pert.estimation_model.name(),
+                lambda: pert.estimation_model.lambda(),
+            },
+            unit: pert.unit.name(),
+            hours_per_day: pert.hours_per_day,
+            start_date: pert.start_date.map(|date| date.to_string()),
+            working_days: pert
+                .working_days
+                .iter()
+                .map(|weekday| weekday.to_string().to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+pub fn list_json(perts: &[Pert]) -> Result<String> {
+    let perts: Vec<PertJson> = perts.iter().map(|pert| pert.into()).collect();
+    to_json("pert_list", perts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::list_json;
+    use crate::modules::{estimation_model::EstimationModel, pert::Pert};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn list() {
+        let mut modified = Pert::new(2, "modified".to_string());
+        modified.estimation_model = EstimationModel::ModifiedPert { lambda: 3.0 };
+        modified.start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+        let perts = vec![Pert::new(1, "example".to_string()), modified];
+        insta::assert_display_snapshot!(list_json(&perts).unwrap());
+    }
+}
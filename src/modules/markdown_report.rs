@@ -0,0 +1,33 @@
+/// Escapes the characters that would break a GitHub-flavoured Markdown table cell.
+pub fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\n', " ")
+}
+
+/// GitHub-flavoured Markdown table, cells are escaped.
+pub fn markdown_table(header: &[String], rows: &[Vec<String>]) -> String {
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|cell| escape_markdown(cell)).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let separator = format!("|{}|", vec![" --- "; header.len()].join("|"));
+    let mut lines = vec![line(header), separator];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::markdown_table;
+
+    #[test]
+    fn table_with_escaped_cells() {
+        let header = vec!["ID".to_string(), "Name".to_string()];
+        let rows = vec![vec!["1".to_string(), "a | b".to_string()]];
+        assert_eq!(
+            markdown_table(&header, &rows),
+            "| ID | Name |\n| --- | --- |\n| 1 | a \\| b |"
+        );
+    }
+}
@@ -1,8 +1,37 @@
+pub mod accuracy;
+pub mod accuracy_report;
 pub mod activity;
 pub mod activity_report;
+pub mod baseline;
+pub mod baseline_report;
+pub mod calendar;
+pub mod critical_path;
+pub mod critical_path_report;
+pub mod delphi;
+pub mod delphi_report;
+pub mod duration;
+pub mod estimation_model;
+pub mod gantt_report;
 pub mod github;
+pub mod graph;
+pub mod graph_report;
+pub mod json_report;
+pub mod markdown_report;
 pub mod pert;
 pub mod pert_report;
+pub mod probability;
+pub mod probability_report;
+pub mod progress;
+pub mod report;
 pub mod roadmap;
 pub mod roadmap_report;
+pub mod schedule;
+pub mod schedule_report;
+pub mod simulation;
+pub mod simulation_report;
 pub mod storage;
+pub mod tags;
+pub mod tags_report;
+pub mod templates;
+#[cfg(test)]
+mod test_util;
@@ -1,13 +1,40 @@
+use chrono::{NaiveDate, Weekday};
+
+use super::{
+    calendar::default_working_days,
+    delphi::Aggregation,
+    duration::{DurationUnit, DEFAULT_HOURS_PER_DAY},
+    estimation_model::EstimationModel,
+};
+
 pub type PertId = i32;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Pert {
     pub id: PertId,
     pub name: String,
+    pub estimation_model: EstimationModel,
+    /// How the estimates of several estimators make the estimation of an activity.
+    pub aggregation: Aggregation,
+    /// Unit of every estimation of the PERT.
+    pub unit: DurationUnit,
+    pub hours_per_day: f64,
+    /// First day of work, activities are not scheduled on a calendar without it.
+    pub start_date: Option<NaiveDate>,
+    pub working_days: Vec<Weekday>,
 }
 
 impl Pert {
     pub fn new(id: PertId, name: String) -> Self {
-        Self { id, name }
+        Self {
+            id,
+            name,
+            estimation_model: EstimationModel::default(),
+            aggregation: Aggregation::default(),
+            unit: DurationUnit::default(),
+            hours_per_day: DEFAULT_HOURS_PER_DAY,
+            start_date: None,
+            working_days: default_working_days(),
+        }
     }
 }
@@ -1,36 +1,26 @@
 use super::{
-    activity::{Activity, EstimationValue},
+    activity::{Activity, ActivityId, EstimationValue},
+    critical_path::{CriticalPath, ScheduledActivity},
+    duration::DurationUnit,
+    gantt_report::GanttReport,
+    json_report::{to_json, PertJson},
+    markdown_report::{escape_markdown, markdown_table},
     pert::Pert,
+    progress::Forecast,
+    report::round,
+    schedule::Schedule,
+    schedule_report::{dates_rows, dates_table, project_dates},
+    tags::{tag_subtotals, TagSubtotal},
+    tags_report::{display_tags, subtotals_body, subtotals_header, subtotals_table},
+    templates::render,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use cli_table::{format::Justify, Table, WithTitle};
 use serde::Serialize;
-use std::path::Path;
-
-type Partials = liquid::partials::EagerCompiler<liquid::partials::InMemorySource>;
-
-fn get_partials() -> Result<Partials> {
-    let mut partials = Partials::empty();
-    let current_dir = std::env::current_dir().unwrap();
-    let relative_template_folder = "src/modules/templates/_includes".to_string();
-    let partials_folder_path = current_dir.join(relative_template_folder);
-    let folder_str = partials_folder_path.to_str().unwrap();
-    let paths = std::fs::read_dir(&folder_str).unwrap();
-    for entry in paths {
-        let entry = entry.unwrap();
-        let file_name = entry.file_name();
-        let file_name = file_name.to_str().unwrap();
-        let file_path = entry.path();
-        let file_path = file_path.to_str().unwrap();
-        let content = std::fs::read_to_string(file_path).expect("failed to read partial file");
-        partials.add(file_name, content);
-    }
-
-    Ok(partials)
-}
-
 #[derive(Table, Serialize)]
 pub struct PertReportRow {
+    #[table(skip)]
+    id: ActivityId,
     #[table(title = "Activity", justify = "Justify::Right")]
     name: String,
     #[table(title = "Optimistic")]
@@ -41,63 +31,253 @@ pub struct PertReportRow {
     pessimistic: EstimationValue,
     #[table(title = "PERT estimation")]
     pert: f64,
+    #[table(title = "σ")]
+    standard_deviation: f64,
+    #[table(title = "Variance")]
+    variance: f64,
+    #[table(title = "ES")]
+    earliest_start: f64,
+    #[table(title = "EF")]
+    earliest_finish: f64,
+    #[table(title = "LS")]
+    latest_start: f64,
+    #[table(title = "LF")]
+    latest_finish: f64,
+    #[table(title = "Total float")]
+    total_float: f64,
+    #[table(title = "Free float")]
+    free_float: f64,
+    #[table(title = "Critical")]
+    critical: bool,
+    #[table(title = "Status")]
+    status: &'static str,
+    #[table(title = "% complete")]
+    percent_complete: f64,
+    #[table(title = "Remaining")]
+    remaining: f64,
+    #[table(title = "Tags", display_fn = "display_tags")]
+    tags: Vec<String>,
+    #[table(skip)]
+    start: Option<String>,
+    #[table(skip)]
+    finish: Option<String>,
 }
 
 pub struct PertReport {
     pert: Pert,
     rows: Vec<PertReportRow>,
     estimated_total: f64,
+    duration: f64,
+    standard_deviation: f64,
+    schedule: Option<Schedule>,
+    forecast: Forecast,
+    gantt: GanttReport,
+    /// Only the activities with this tag are listed.
+    tag: Option<String>,
+    subtotals: Vec<TagSubtotal>,
 }
 
-impl From<&Activity> for PertReportRow {
-    fn from(act: &Activity) -> PertReportRow {
+fn subtotals(rows: &[PertReportRow]) -> Vec<TagSubtotal> {
+    tag_subtotals(
+        rows.iter()
+            .map(|row| (row.tags.as_slice(), row.pert, row.variance)),
+    )
+}
+
+impl From<(&Activity, &ScheduledActivity)> for PertReportRow {
+    fn from((act, scheduled): (&Activity, &ScheduledActivity)) -> PertReportRow {
         Self {
+            id: act.id,
             name: act.name.to_owned(),
-            optimistic: act.estimation.optimistic,
-            probable: act.estimation.probable,
-            pessimistic: act.estimation.pessimistic,
-            pert: act.estimation.estimated(),
+            optimistic: round(act.estimation.optimistic),
+            probable: round(act.estimation.probable),
+            pessimistic: round(act.estimation.pessimistic),
+            pert: round(act.estimated()),
+            standard_deviation: round(act.standard_deviation()),
+            variance: round(act.variance()),
+            earliest_start: round(scheduled.earliest_start),
+            earliest_finish: round(scheduled.earliest_finish),
+            latest_start: round(scheduled.latest_start),
+            latest_finish: round(scheduled.latest_finish),
+            total_float: round(scheduled.total_float),
+            free_float: round(scheduled.free_float),
+            critical: scheduled.is_critical(),
+            status: act.progress.status.name(),
+            percent_complete: round(act.progress.percent_complete),
+            remaining: round(act.remaining().estimated()),
+            tags: act.tags.clone(),
+            start: None,
+            finish: None,
         }
     }
 }
 
+/// Title lines shared by every report of a PERT.
+pub fn pert_header(pert: &Pert) -> String {
+    format!(
+        "Project: {}\nEstimation model: {}\nUnit: {}",
+        pert.name,
+        pert.estimation_model,
+        unit_description(pert)
+    )
+}
+
+pub fn unit_description(pert: &Pert) -> String {
+    match pert.unit {
+        DurationUnit::Points => pert.unit.to_string(),
+        unit => format!("{} ({} hours per day)", unit, pert.hours_per_day),
+    }
+}
+
 fn estimated_total(activities: &[Activity]) -> f64 {
     activities.iter().map(|activity| activity.estimated()).sum()
 }
 
+/// Titles and values of the progress summary, shared by the reports.
+pub fn progress_lines(forecast: &Forecast, unit: DurationUnit) -> Vec<(&'static str, String)> {
+    let mut lines = vec![
+        ("Complete", format!("{}%", round(forecast.percent_complete))),
+        (
+            "Remaining",
+            format!("{} {}", round(forecast.remaining_total), unit),
+        ),
+        (
+            "Remaining duration",
+            format!("{} {}", round(forecast.remaining_duration), unit),
+        ),
+    ];
+    if forecast.blocked > 0 {
+        lines.push(("Blocked", forecast.blocked.to_string()));
+    }
+    if let Some(finish) = forecast.finish {
+        lines.push(("Forecast finish", finish.to_string()));
+    }
+    lines
+}
+
 impl PertReport {
-    pub fn new(pert: Pert, activities: Vec<Activity>) -> Self {
-        Self {
+    pub fn new(
+        pert: Pert,
+        activities: Vec<Activity>,
+        critical_path: CriticalPath,
+        schedule: Option<Schedule>,
+        forecast: Forecast,
+        gantt: GanttReport,
+    ) -> Result<Self> {
+        let rows = activities
+            .iter()
+            .map(|act| {
+                let scheduled = critical_path.get(act.id).ok_or_else(|| {
+                    anyhow!("Activity {} is missing from the critical path", act.id)
+                })?;
+                let mut row: PertReportRow = (act, scheduled).into();
+                if let Some(dates) = schedule.as_ref().and_then(|sch| sch.get(act.id)) {
+                    row.start = Some(dates.start.to_string());
+                    row.finish = Some(dates.finish.to_string());
+                }
+                Ok(row)
+            })
+            .collect::<Result<Vec<PertReportRow>>>()?;
+        Ok(Self {
             pert,
-            rows: activities.iter().map(|act| (act).into()).collect(),
-            estimated_total: estimated_total(&activities),
+            subtotals: subtotals(&rows),
+            rows,
+            estimated_total: round(estimated_total(&activities)),
+            duration: round(critical_path.duration),
+            standard_deviation: round(critical_path.standard_deviation()),
+            schedule,
+            forecast,
+            gantt,
+            tag: None,
+        })
+    }
+
+    /// Lists the activities with the tag only, totals and forecasts stay the ones of
+    /// the whole PERT.
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        if let Some(tag) = &tag {
+            self.rows.retain(|row| row.tags.contains(tag));
+            self.subtotals = subtotals(&self.rows);
         }
+        self.tag = tag;
+        self
     }
 
-    pub fn csv(&mut self) -> String {
-        let mut csv_rows = vec![vec![
+    /// Header of the reports, with the tag the activities are filtered by.
+    fn header(&self) -> String {
+        match &self.tag {
+            Some(tag) => format!("{}\nTag: {}", pert_header(&self.pert), tag),
+            None => pert_header(&self.pert),
+        }
+    }
+
+    fn table_header(&self) -> Vec<String> {
+        let unit = self.pert.unit;
+        let mut header = vec![
             "Name".to_string(),
-            "Optimistic".to_string(),
-            "Probable".to_string(),
-            "Pessimistic".to_string(),
-            "PERT".to_string(),
-        ]];
-
-        let mut body_rows = self
-            .rows
+            format!("Optimistic ({})", unit),
+            format!("Probable ({})", unit),
+            format!("Pessimistic ({})", unit),
+            format!("PERT ({}) ({})", self.pert.estimation_model, unit),
+            format!("Standard deviation ({})", unit),
+            "Variance".to_string(),
+            format!("ES ({})", unit),
+            format!("EF ({})", unit),
+            format!("LS ({})", unit),
+            format!("LF ({})", unit),
+            format!("Total float ({})", unit),
+            format!("Free float ({})", unit),
+            "Critical".to_string(),
+            "Status".to_string(),
+            "% complete".to_string(),
+            format!("Remaining ({})", unit),
+            "Tags".to_string(),
+        ];
+        if self.schedule.is_some() {
+            header.push("Start".to_string());
+            header.push("Finish".to_string());
+        }
+        header
+    }
+
+    /// `separator` joins the tags of an activity.
+    fn table_body(&self, separator: &str) -> Vec<Vec<String>> {
+        self.rows
             .iter()
             .map(|row| {
-                vec![
+                let mut csv_row = vec![
                     row.name.to_owned(),
                     row.optimistic.to_string(),
                     row.probable.to_string(),
                     row.pessimistic.to_string(),
                     row.pert.to_string(),
-                ]
+                    row.standard_deviation.to_string(),
+                    row.variance.to_string(),
+                    row.earliest_start.to_string(),
+                    row.earliest_finish.to_string(),
+                    row.latest_start.to_string(),
+                    row.latest_finish.to_string(),
+                    row.total_float.to_string(),
+                    row.free_float.to_string(),
+                    row.critical.to_string(),
+                    row.status.to_string(),
+                    row.percent_complete.to_string(),
+                    row.remaining.to_string(),
+                    row.tags.join(separator),
+                ];
+                if self.schedule.is_some() {
+                    csv_row.push(row.start.to_owned().unwrap_or_default());
+                    csv_row.push(row.finish.to_owned().unwrap_or_default());
+                }
+                csv_row
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
 
-        csv_rows.append(&mut body_rows);
+    pub fn csv(&mut self) -> String {
+        let mut csv_rows = vec![self.table_header()];
+        // commas separate the columns
+        csv_rows.append(&mut self.table_body(";"));
         csv_rows.push(vec![
             "Total".to_string(),
             "".to_string(),
@@ -105,6 +285,32 @@ impl PertReport {
             "".to_string(),
             self.estimated_total.to_string(),
         ]);
+        csv_rows.push(vec![
+            "Duration".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            self.duration.to_string(),
+        ]);
+        csv_rows.push(vec![
+            "Standard deviation".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            self.standard_deviation.to_string(),
+        ]);
+        if let Some(schedule) = &self.schedule {
+            csv_rows.push(vec!["Start".to_string(), schedule.start.to_string()]);
+            csv_rows.push(vec!["Finish".to_string(), schedule.finish.to_string()]);
+        }
+        for (title, value) in progress_lines(&self.forecast, self.pert.unit) {
+            csv_rows.push(vec![title.to_string(), value]);
+        }
+        if !self.subtotals.is_empty() {
+            csv_rows.push(subtotals_header(self.pert.unit));
+            csv_rows.append(&mut subtotals_body(&self.subtotals));
+        }
 
         csv_rows
             .into_iter()
@@ -113,80 +319,388 @@ impl PertReport {
             .join("\n")
     }
 
-    pub fn html(&mut self) -> String {
-        let partials = get_partials().unwrap();
-
-        let path = Path::new("src/modules/templates/report_pert.liquid");
-        let template = liquid::ParserBuilder::with_stdlib()
-            .partials(partials)
-            .build()
-            .unwrap();
-
-        let template = template.parse_file(path).unwrap();
-
+    pub fn html(&mut self) -> Result<String> {
         let globals = liquid::object!({
             "pert_name": self.pert.name,
+            "estimation_model": self.pert.estimation_model.to_string(),
+            "unit": self.pert.unit.name(),
+            "tag": self.tag,
             "activities": self.rows,
-            "estimated_total": self.estimated_total
+            "estimated_total": self.estimated_total,
+            "duration": self.duration,
+            "standard_deviation": self.standard_deviation,
+            "scheduled": self.schedule.is_some(),
+            "start_date": self.schedule.as_ref().map(|sch| sch.start.to_string()),
+            "finish_date": self.schedule.as_ref().map(|sch| sch.finish.to_string()),
+            "progress": progress_lines(&self.forecast, self.pert.unit),
+            "tag_subtotals": self.subtotals,
+            "gantt": self.gantt.svg()
         });
 
-        template.render(&globals).unwrap()
+        render("report_pert.liquid", &globals)
+    }
+
+    pub fn markdown(&mut self) -> String {
+        let unit = self.pert.unit;
+        let mut summary = vec![
+            format!("**Total:** {} {}", self.estimated_total, unit),
+            format!("**Duration:** {} {}", self.duration, unit),
+            format!(
+                "**Standard deviation:** {} {}",
+                self.standard_deviation, unit
+            ),
+        ];
+        if let Some(schedule) = &self.schedule {
+            summary.push(format!("**Start:** {}", schedule.start));
+            summary.push(format!("**Finish:** {}", schedule.finish));
+        }
+        summary.extend(
+            progress_lines(&self.forecast, unit)
+                .into_iter()
+                .map(|(title, value)| format!("**{}:** {}", title, value)),
+        );
+        let mut lines = vec![
+            format!("# {}", escape_markdown(&self.pert.name)),
+            String::new(),
+            format!("Estimation model: {}  ", self.pert.estimation_model),
+            format!("Unit: {}", unit_description(&self.pert)),
+        ];
+        if let Some(tag) = &self.tag {
+            lines.last_mut().unwrap().push_str("  ");
+            lines.push(format!("Tag: {}", escape_markdown(tag)));
+        }
+        lines.extend([
+            String::new(),
+            markdown_table(&self.table_header(), &self.table_body(", ")),
+            String::new(),
+            summary.join("  \n"),
+        ]);
+        if !self.subtotals.is_empty() {
+            lines.extend([
+                String::new(),
+                "## Tags".to_string(),
+                String::new(),
+                markdown_table(&subtotals_header(unit), &subtotals_body(&self.subtotals)),
+            ]);
+        }
+        lines.join("\n")
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct PertJsonReport<'a> {
+            pert: PertJson,
+            activities: &'a [PertReportRow],
+            estimated_total: f64,
+            duration: f64,
+            standard_deviation: f64,
+            start_date: Option<String>,
+            finish_date: Option<String>,
+            progress: &'a Forecast,
+            tag: Option<&'a str>,
+            tag_subtotals: &'a [TagSubtotal],
+        }
+
+        to_json(
+            "pert",
+            PertJsonReport {
+                pert: (&self.pert).into(),
+                activities: &self.rows,
+                estimated_total: self.estimated_total,
+                duration: self.duration,
+                standard_deviation: self.standard_deviation,
+                start_date: self.schedule.as_ref().map(|sch| sch.start.to_string()),
+                finish_date: self.schedule.as_ref().map(|sch| sch.finish.to_string()),
+                progress: &self.forecast,
+                tag: self.tag.as_deref(),
+                tag_subtotals: &self.subtotals,
+            },
+        )
+    }
+
+    pub fn svg(&mut self) -> String {
+        self.gantt.svg()
     }
 
     pub fn ascii(&mut self) -> String {
         let rows_str = self.rows.with_title().display().unwrap().to_string();
 
-        format!(
-            "Project: {}\n\n{}\nTOTAL: {}",
-            self.pert.name, rows_str, self.estimated_total
-        )
+        let report = format!(
+            "{}\n\n{}\nTOTAL: {} {unit}\nDURATION: {} {unit}\nSTANDARD DEVIATION: {} {unit}",
+            self.header(),
+            rows_str,
+            self.estimated_total,
+            self.duration,
+            self.standard_deviation,
+            unit = self.pert.unit
+        );
+        let report = match &self.schedule {
+            Some(schedule) => format!(
+                "{}\n\n{}\n{}",
+                report,
+                dates_table(&dates_rows(schedule)),
+                project_dates(schedule)
+            ),
+            None => report,
+        };
+        let progress: Vec<String> = progress_lines(&self.forecast, self.pert.unit)
+            .into_iter()
+            .map(|(title, value)| format!("{}: {}", title.to_uppercase(), value))
+            .collect();
+        let report = format!("{}\n{}", report, progress.join("\n"));
+        if self.subtotals.is_empty() {
+            return report;
+        }
+        format!("{}\n\nBY TAG\n{}", report, subtotals_table(&self.subtotals))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::PertReport;
-    use crate::modules::{activity::Activity, pert::Pert};
+    use super::{pert_header, PertReport};
+    use crate::modules::{
+        activity::{parse_tags, Activity, Progress, Status},
+        calendar::Calendar,
+        critical_path::CriticalPath,
+        duration::DurationUnit,
+        estimation_model::EstimationModel,
+        gantt_report::GanttReport,
+        graph::DependencyGraph,
+        pert::Pert,
+        progress::Forecast,
+        schedule::Schedule,
+        test_util::dep,
+    };
+    use chrono::NaiveDate;
+
+    fn example_report() -> PertReport {
+        example_report_with_model(EstimationModel::Classic)
+    }
+
+    fn example_report_with_model(estimation_model: EstimationModel) -> PertReport {
+        example_report_with(estimation_model, None)
+    }
+
+    fn example_report_with(
+        estimation_model: EstimationModel,
+        start_date: Option<NaiveDate>,
+    ) -> PertReport {
+        example_report_tagged(estimation_model, start_date, ["", "", "", ""])
+    }
+
+    /// `tags` of every activity, separated by commas.
+    fn example_report_tagged(
+        estimation_model: EstimationModel,
+        start_date: Option<NaiveDate>,
+        tags: [&str; 4],
+    ) -> PertReport {
+        let mut pert = Pert::new(1, "example".to_string());
+        pert.estimation_model = estimation_model;
+        let mut activities = vec![
+            Activity::new(1, "activity 1".to_string(), 6.0, 10.0, 15.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 18.0, 25.0, 39.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 14.0, 22.0, 35.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 23.0, 34.0, 62.0).unwrap(),
+        ];
+        for (activity, tags) in activities.iter_mut().zip(tags) {
+            activity.model = estimation_model;
+            activity.tags = parse_tags(tags);
+        }
+        let dependencies = [(1, None), (2, Some(1)), (3, Some(1)), (4, Some(3))]
+            .into_iter()
+            .map(|(id, head)| dep(id, head))
+            .collect::<Vec<_>>();
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        let calendar = Calendar::new(&pert, vec![]).unwrap();
+        let schedule = start_date
+            .map(|start_date| Schedule::new(start_date, &critical_path, &calendar).unwrap());
+        let forecast = Forecast::new(
+            &activities,
+            &graph,
+            start_date.map(|start_date| (start_date, &calendar)),
+        )
+        .unwrap();
+
+        PertReport::new(pert, activities, critical_path, schedule, forecast, gantt).unwrap()
+    }
+
+    fn report_with_progress() -> PertReport {
+        let mut pert = Pert::new(1, "example".to_string());
+        pert.start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+        let mut activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 4.0, 4.0, 4.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 3.0, 3.0, 3.0).unwrap(),
+        ];
+        activities[0].progress = Progress::new(Status::Done, 100.0).unwrap();
+        activities[1].progress = Progress::new(Status::InProgress, 25.0).unwrap();
+        activities[2].progress = Progress::new(Status::Blocked, 0.0).unwrap();
+        let dependencies = [(1, None), (2, Some(1)), (3, Some(2))]
+            .into_iter()
+            .map(|(id, head)| dep(id, head))
+            .collect::<Vec<_>>();
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        let calendar = Calendar::new(&pert, vec![]).unwrap();
+        let schedule = Schedule::new(pert.start_date.unwrap(), &critical_path, &calendar).unwrap();
+        // two days later, the first activity is done
+        let as_of = NaiveDate::from_ymd_opt(2022, 6, 3).unwrap();
+        let forecast = Forecast::new(&activities, &graph, Some((as_of, &calendar))).unwrap();
+
+        PertReport::new(
+            pert,
+            activities,
+            critical_path,
+            Some(schedule),
+            forecast,
+            gantt,
+        )
+        .unwrap()
+    }
 
     #[test]
-    fn table() {
+    fn table_with_fractional_estimates() {
         let pert = Pert::new(1, "example".to_string());
         let activities = vec![
-            Activity::new(1, "activity 1".to_string(), 6, 10, 15),
-            Activity::new(2, "activity 2".to_string(), 18, 25, 39),
-            Activity::new(3, "activity 3".to_string(), 14, 22, 35),
-            Activity::new(4, "activity 4".to_string(), 23, 34, 62),
+            Activity::new(1, "activity 1".to_string(), 1.0, 2.0, 4.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 2.0, 3.0, 7.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.5, 3.0).unwrap(),
         ];
+        let dependencies = [(1, None), (2, Some(1)), (3, Some(1))]
+            .into_iter()
+            .map(|(id, head)| dep(id, head))
+            .collect::<Vec<_>>();
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        let forecast = Forecast::new(&activities, &graph, None).unwrap();
 
-        let mut report = PertReport::new(pert, activities);
+        let mut report =
+            PertReport::new(pert, activities, critical_path, None, forecast, gantt).unwrap();
         insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.csv());
+        insta::assert_display_snapshot!(report.markdown());
     }
 
     #[test]
-    fn table_html() {
+    fn activity_missing_from_critical_path() {
         let pert = Pert::new(1, "example".to_string());
         let activities = vec![
-            Activity::new(1, "activity 1".to_string(), 6, 10, 15),
-            Activity::new(2, "activity 2".to_string(), 18, 25, 39),
-            Activity::new(3, "activity 3".to_string(), 14, 22, 35),
-            Activity::new(4, "activity 4".to_string(), 23, 34, 62),
+            Activity::new(1, "activity 1".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 4.0, 4.0, 4.0).unwrap(),
         ];
+        let dependencies = vec![dep(1, None)];
+        let graph = DependencyGraph::new(&dependencies);
+        // the critical path knows the first activity only
+        let critical_path = CriticalPath::new(&activities[..1], &graph).unwrap();
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        let forecast = Forecast::new(&activities, &graph, None).unwrap();
 
-        let mut report = PertReport::new(pert, activities);
-        insta::assert_display_snapshot!(report.html());
+        let error = PertReport::new(pert, activities, critical_path, None, forecast, gantt)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Activity 2 is missing from the critical path"
+        );
+    }
+
+    #[test]
+    fn table() {
+        let mut report = example_report();
+        insta::assert_display_snapshot!(report.ascii());
+    }
+
+    #[test]
+    fn table_html() {
+        let mut report = example_report();
+        insta::assert_display_snapshot!(report.html().unwrap());
     }
 
     #[test]
     fn table_csv() {
-        let pert = Pert::new(1, "example".to_string());
-        let activities = vec![
-            Activity::new(1, "activity 1".to_string(), 6, 10, 15),
-            Activity::new(2, "activity 2".to_string(), 18, 25, 39),
-            Activity::new(3, "activity 3".to_string(), 14, 22, 35),
-            Activity::new(4, "activity 4".to_string(), 23, 34, 62),
-        ];
+        let mut report = example_report();
+        insta::assert_display_snapshot!(report.csv());
+    }
 
-        let mut report = PertReport::new(pert, activities);
+    #[test]
+    fn table_csv_triangular() {
+        let mut report = example_report_with_model(EstimationModel::Triangular);
         insta::assert_display_snapshot!(report.csv());
     }
+
+    #[test]
+    fn table_with_dates() {
+        let start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+        let mut report = example_report_with(EstimationModel::Classic, start_date);
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.csv());
+        insta::assert_display_snapshot!(report.html().unwrap());
+    }
+
+    #[test]
+    fn table_json() {
+        let start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+        let mut report = example_report_with(EstimationModel::Classic, start_date);
+        insta::assert_display_snapshot!(report.json().unwrap());
+    }
+
+    #[test]
+    fn table_markdown() {
+        let mut report = example_report();
+        insta::assert_display_snapshot!(report.markdown());
+    }
+
+    #[test]
+    fn table_markdown_with_dates() {
+        let start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+        let mut report = example_report_with(EstimationModel::Classic, start_date);
+        insta::assert_display_snapshot!(report.markdown());
+    }
+
+    #[test]
+    fn header_with_points() {
+        let mut pert = Pert::new(1, "example".to_string());
+        pert.unit = DurationUnit::Points;
+        assert_eq!(
+            pert_header(&pert),
+            "Project: example\nEstimation model: Classic PERT (o + 4m + p) / 6\nUnit: points"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn table_with_progress() {
+        let mut report = report_with_progress();
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.markdown());
+    }
+
+    fn tagged_report() -> PertReport {
+        example_report_tagged(
+            EstimationModel::Classic,
+            None,
+            ["backend", "backend, ui", "ui", ""],
+        )
+    }
+
+    #[test]
+    fn table_with_tags() {
+        let mut report = tagged_report();
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.csv());
+        insta::assert_display_snapshot!(report.markdown());
+        insta::assert_display_snapshot!(report.html().unwrap());
+    }
+
+    #[test]
+    fn filtered_by_tag() {
+        let mut report = tagged_report().with_tag(Some("ui".to_string()));
+        assert_eq!(report.rows.len(), 2);
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.json().unwrap());
+    }
 }
@@ -0,0 +1,151 @@
+use anyhow::{bail, Result};
+
+use super::critical_path::CriticalPath;
+
+/// Cumulative distribution function of the standard normal distribution.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1f64 + erf(z / std::f64::consts::SQRT_2))
+}
+
+// Abramowitz and Stegun formula 7.1.26, maximum error 1.5e-7
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0f64 { -1f64 } else { 1f64 };
+    let x = x.abs();
+    let t = 1f64 / (1f64 + 0.3275911 * x);
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1f64 - polynomial * (-x * x).exp())
+}
+
+/// Inverse of `normal_cdf`, computed with Acklam's rational approximation.
+pub fn normal_inverse_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1f64)
+    };
+    if p < P_LOW {
+        tail((-2f64 * p.ln()).sqrt())
+    } else if p > 1f64 - P_LOW {
+        -tail((-2f64 * (1f64 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1f64)
+    }
+}
+
+/// Normal approximation of the project duration, centered on the critical path.
+#[derive(Debug, PartialEq)]
+pub struct CompletionProbability {
+    pub expected_duration: f64,
+    pub standard_deviation: f64,
+}
+
+impl CompletionProbability {
+    pub fn new(critical_path: &CriticalPath) -> Self {
+        Self {
+            expected_duration: critical_path.duration,
+            standard_deviation: critical_path.standard_deviation(),
+        }
+    }
+
+    /// Probability of completing the project within `deadline`.
+    pub fn probability_by(&self, deadline: f64) -> f64 {
+        if self.standard_deviation == 0f64 {
+            return if deadline >= self.expected_duration {
+                1f64
+            } else {
+                0f64
+            };
+        }
+        normal_cdf((deadline - self.expected_duration) / self.standard_deviation)
+    }
+
+    /// Duration needed to complete the project with the given `confidence`, between 0 and 1.
+    pub fn duration_for(&self, confidence: f64) -> Result<f64> {
+        if confidence <= 0f64 || confidence >= 1f64 {
+            bail!("Confidence must be between 0 and 100%, exclusive");
+        }
+        Ok(self.expected_duration + normal_inverse_cdf(confidence) * self.standard_deviation)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{normal_cdf, normal_inverse_cdf, CompletionProbability};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {} to be close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn normal_distribution() {
+        assert_close(normal_cdf(0.0), 0.5);
+        assert_close(normal_cdf(1.0), 0.841344746);
+        assert_close(normal_cdf(-1.96), 0.024997895);
+        assert_close(normal_inverse_cdf(0.5), 0.0);
+        assert_close(normal_inverse_cdf(0.95), 1.644853627);
+        assert_close(normal_inverse_cdf(0.01), -2.326347874);
+    }
+
+    #[test]
+    fn probability_by_deadline() {
+        let probability = CompletionProbability {
+            expected_duration: 20.0,
+            standard_deviation: 2.0,
+        };
+        assert_close(probability.probability_by(20.0), 0.5);
+        assert_close(probability.probability_by(22.0), 0.841344746);
+        assert_close(probability.duration_for(0.95).unwrap(), 23.289707254);
+        assert!(probability.duration_for(1.0).is_err());
+    }
+
+    #[test]
+    fn probability_without_uncertainty() {
+        let probability = CompletionProbability {
+            expected_duration: 20.0,
+            standard_deviation: 0.0,
+        };
+        assert_eq!(probability.probability_by(19.0), 0.0);
+        assert_eq!(probability.probability_by(20.0), 1.0);
+        assert_eq!(probability.duration_for(0.95).unwrap(), 20.0);
+    }
+}
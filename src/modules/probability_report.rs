@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use super::{pert::Pert, pert_report::pert_header, probability::CompletionProbability};
+
+pub struct ProbabilityReport {
+    pert: Pert,
+    probability: CompletionProbability,
+}
+
+impl ProbabilityReport {
+    pub fn new(pert: Pert, probability: CompletionProbability) -> Self {
+        Self { pert, probability }
+    }
+
+    /// `confidence` is expressed as a percentage.
+    pub fn ascii(&mut self, deadline: f64, confidence: f64) -> Result<String> {
+        let probability = self.probability.probability_by(deadline) * 100f64;
+        let duration = self.probability.duration_for(confidence / 100f64)?;
+
+        Ok(format!(
+            "{}\n\nExpected duration: {:.2} {unit}\nStandard deviation: {:.2} {unit}\nProbability of completing within {} {unit}: {:.2}%\nDuration needed for {}% confidence: {:.2} {unit}",
+            pert_header(&self.pert),
+            self.probability.expected_duration,
+            self.probability.standard_deviation,
+            deadline,
+            probability,
+            confidence,
+            duration,
+            unit = self.pert.unit
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ProbabilityReport;
+    use crate::modules::{pert::Pert, probability::CompletionProbability};
+
+    #[test]
+    fn text() {
+        let pert = Pert::new(1, "example".to_string());
+        let probability = CompletionProbability {
+            expected_duration: 20.0,
+            standard_deviation: 2.0,
+        };
+
+        let mut report = ProbabilityReport::new(pert, probability);
+        insta::assert_display_snapshot!(report.ascii(22.0, 95.0).unwrap());
+    }
+}
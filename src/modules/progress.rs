@@ -0,0 +1,121 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::{
+    activity::{Activity, Status},
+    calendar::Calendar,
+    critical_path::CriticalPath,
+    graph::DependencyGraph,
+    schedule::Schedule,
+};
+
+/// Completion of the remaining network, computed from the unfinished portion of
+/// each estimate. Done activities take no time, the others keep their dependencies.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Forecast {
+    /// Share of the estimated work done, weighted by the estimates.
+    pub percent_complete: f64,
+    /// Sum of the remaining estimates.
+    pub remaining_total: f64,
+    /// Duration of the remaining network along its critical path.
+    pub remaining_duration: f64,
+    pub remaining_standard_deviation: f64,
+    pub blocked: usize,
+    /// Day the remaining work is scheduled from.
+    pub as_of: Option<NaiveDate>,
+    /// Last working day of the remaining work, `None` when nothing is left.
+    pub finish: Option<NaiveDate>,
+}
+
+impl Forecast {
+    /// `from` is the day work resumes, with the calendar of the PERT, when it is scheduled.
+    pub fn new(
+        activities: &[Activity],
+        graph: &DependencyGraph,
+        from: Option<(NaiveDate, &Calendar)>,
+    ) -> Result<Self> {
+        let remaining: Vec<Activity> = activities.iter().map(|act| act.remaining()).collect();
+        let critical_path = CriticalPath::new(&remaining, graph)?;
+        let total: f64 = activities.iter().map(|act| act.estimated()).sum();
+        let remaining_total: f64 = remaining.iter().map(|act| act.estimated()).sum();
+        let finish = match from {
+            Some((from, calendar)) if critical_path.duration > 0f64 => {
+                Some(Schedule::new(from, &critical_path, calendar)?.finish)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            percent_complete: if total > 0f64 {
+                (1f64 - remaining_total / total) * 100f64
+            } else {
+                0f64
+            },
+            remaining_total,
+            remaining_duration: critical_path.duration,
+            remaining_standard_deviation: critical_path.standard_deviation(),
+            blocked: activities
+                .iter()
+                .filter(|act| act.progress.status == Status::Blocked)
+                .count(),
+            as_of: from.map(|(from, calendar)| calendar.working_day(from, 0)),
+            finish,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Forecast;
+    use crate::modules::{
+        activity::{Activity, Progress, Status},
+        calendar::Calendar,
+        graph::DependencyGraph,
+        pert::Pert,
+        test_util::dep,
+    };
+    use chrono::NaiveDate;
+
+    fn activities() -> Vec<Activity> {
+        let mut activities = vec![
+            Activity::new(1, "design".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "build".to_string(), 4.0, 4.0, 4.0).unwrap(),
+            Activity::new(3, "doc".to_string(), 2.0, 2.0, 2.0).unwrap(),
+        ];
+        activities[0].progress = Progress::new(Status::Done, 100.0).unwrap();
+        activities[1].progress = Progress::new(Status::InProgress, 50.0).unwrap();
+        activities[2].progress = Progress::new(Status::Blocked, 0.0).unwrap();
+        activities
+    }
+
+    #[test]
+    fn remaining_network() {
+        let graph = DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, Some(1))]);
+        let forecast = Forecast::new(&activities(), &graph, None).unwrap();
+        assert_eq!(forecast.remaining_total, 4.0);
+        assert_eq!(forecast.remaining_duration, 2.0);
+        assert_eq!(forecast.percent_complete, 50.0);
+        assert_eq!(forecast.blocked, 1);
+        assert_eq!(forecast.finish, None);
+    }
+
+    #[test]
+    fn forecast_finish() {
+        let graph = DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, Some(2))]);
+        let calendar = Calendar::new(&Pert::new(1, "example".to_string()), vec![]).unwrap();
+        // a Saturday, work resumes on Monday 2022-06-06 for 4 days
+        let from = NaiveDate::from_ymd_opt(2022, 6, 4).unwrap();
+        let forecast = Forecast::new(&activities(), &graph, Some((from, &calendar))).unwrap();
+        assert_eq!(forecast.as_of, NaiveDate::from_ymd_opt(2022, 6, 6));
+        assert_eq!(forecast.finish, NaiveDate::from_ymd_opt(2022, 6, 9));
+
+        let mut done = activities();
+        for activity in done.iter_mut() {
+            activity.progress = Progress::new(Status::Done, 100.0).unwrap();
+        }
+        let forecast = Forecast::new(&done, &graph, Some((from, &calendar))).unwrap();
+        assert_eq!(forecast.remaining_duration, 0.0);
+        assert_eq!(forecast.percent_complete, 100.0);
+        assert_eq!(forecast.finish, None);
+    }
+}
@@ -0,0 +1,6 @@
+//! Helpers shared by the reports.
+
+/// Rounds to two decimals, as the values are displayed.
+pub fn round(value: f64) -> f64 {
+    (value * 100f64).round() / 100f64
+}
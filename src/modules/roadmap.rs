@@ -1,4 +1,7 @@
-use super::activity::ActivityId;
+use anyhow::Result;
+use std::collections::HashMap;
+
+use super::{activity::ActivityId, graph::DependencyGraph};
 
 #[derive(Debug)]
 pub struct ActivityWithRelatedDependencies {
@@ -23,64 +26,44 @@ pub struct Roadmap {
 }
 
 impl Roadmap {
-    pub fn new(acts_with_deps: Vec<ActivityWithRelatedDependencies>) -> Self {
-        Self {
-            phases: get_phases(acts_with_deps),
-        }
+    pub fn new(acts_with_deps: Vec<ActivityWithRelatedDependencies>) -> Result<Self> {
+        Ok(Self {
+            phases: get_phases(acts_with_deps)?,
+        })
     }
 }
 
-fn get_phases_recursive(
-    acts_with_deps: Vec<ActivityWithRelatedDependencies>,
-    previous_ids: Option<&Vec<ActivityId>>,
-    head_vec: &mut Vec<Phase>,
-) {
-    if acts_with_deps.is_empty() {
-        return;
+/// Every activity is put in the phase right after the one of its last predecessor,
+/// activities without dependencies are in the first phase.
+fn get_phases(acts_with_deps: Vec<ActivityWithRelatedDependencies>) -> Result<Vec<Phase>> {
+    let graph = DependencyGraph::new(&acts_with_deps);
+    let mut names: HashMap<ActivityId, String> = HashMap::new();
+    for act in acts_with_deps {
+        names.entry(act.activity_id).or_insert(act.head_name);
     }
-    let (heads, tails): (
-        Vec<Option<ActivitySum>>,
-        Vec<Option<ActivityWithRelatedDependencies>>,
-    ) = acts_with_deps
-        .into_iter()
-        .map(|act| match act.activity_id_head {
-            None => (
-                Some(ActivitySum {
-                    id: act.activity_id,
-                    name: act.head_name,
-                }),
-                None,
-            ),
-            Some(head_id) => {
-                if previous_ids.is_some() && previous_ids.unwrap().contains(&head_id) {
-                    return (
-                        Some(ActivitySum {
-                            id: act.activity_id,
-                            name: act.head_name,
-                        }),
-                        None,
-                    );
-                }
-                (None, Some(act))
-            }
-        })
-        .unzip();
-
-    let heads: Vec<ActivitySum> = heads.into_iter().flatten().collect();
-    let tails: Vec<ActivityWithRelatedDependencies> = tails.into_iter().flatten().collect();
 
-    let head_ids: Vec<ActivityId> = heads.iter().map(|act| act.id).collect();
-
-    head_vec.push(Phase { activities: heads });
-
-    get_phases_recursive(tails, Some(&head_ids), head_vec)
-}
-
-fn get_phases(acts_with_deps: Vec<ActivityWithRelatedDependencies>) -> Vec<Phase> {
-    let mut accumulator: Vec<Phase> = vec![];
-    get_phases_recursive(acts_with_deps, None, &mut accumulator);
+    let mut phase_of: HashMap<ActivityId, usize> = HashMap::new();
+    for id in graph.topological_order()? {
+        let phase = graph
+            .predecessors(id)
+            .iter()
+            .map(|pred| phase_of[pred] + 1)
+            .max()
+            .unwrap_or(0);
+        phase_of.insert(id, phase);
+    }
 
-    accumulator
+    let phases_count = phase_of.values().max().map_or(0, |last| last + 1);
+    let mut phases: Vec<Phase> = (0..phases_count)
+        .map(|_| Phase { activities: vec![] })
+        .collect();
+    for id in graph.nodes() {
+        phases[phase_of[id]].activities.push(ActivitySum {
+            id: *id,
+            name: names.remove(id).unwrap_or_default(),
+        });
+    }
+    Ok(phases)
 }
 
 #[cfg(test)]
@@ -92,7 +75,7 @@ mod test {
     #[test]
     fn test_get_phases_empty() {
         let acts_with_deps: Vec<ActivityWithRelatedDependencies> = vec![];
-        let roadmap = get_phases(acts_with_deps);
+        let roadmap = get_phases(acts_with_deps).unwrap();
         let expected: Vec<Phase> = vec![];
         assert_eq!(roadmap, expected)
     }
@@ -111,7 +94,7 @@ mod test {
                 activity_id_head: None,
             },
         ];
-        let roadmap = get_phases(acts_with_deps);
+        let roadmap = get_phases(acts_with_deps).unwrap();
         let expected: Vec<Phase> = vec![Phase {
             activities: vec![
                 ActivitySum {
@@ -151,7 +134,7 @@ mod test {
                 activity_id_head: Some(1),
             },
         ];
-        let roadmap = get_phases(acts_with_deps);
+        let roadmap = get_phases(acts_with_deps).unwrap();
         let expected: Vec<Phase> = vec![
             Phase {
                 activities: vec![ActivitySum {
@@ -180,4 +163,115 @@ mod test {
         ];
         assert_eq!(roadmap, expected)
     }
+
+    #[test]
+    fn test_get_phases_after_last_predecessor() {
+        // "Third C" depends on both "First A" and "Second B": it must appear
+        // once, after "Second B", even if the rows come in a different order
+        let acts_with_deps: Vec<ActivityWithRelatedDependencies> = vec![
+            ActivityWithRelatedDependencies {
+                head_name: "Third C".to_string(),
+                activity_id: 3,
+                activity_id_head: Some(1),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "First A".to_string(),
+                activity_id: 1,
+                activity_id_head: None,
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Third C".to_string(),
+                activity_id: 3,
+                activity_id_head: Some(2),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Second B".to_string(),
+                activity_id: 2,
+                activity_id_head: Some(1),
+            },
+        ];
+        let roadmap = get_phases(acts_with_deps).unwrap();
+        let expected: Vec<Phase> = vec![
+            Phase {
+                activities: vec![ActivitySum {
+                    name: "First A".to_string(),
+                    id: 1,
+                }],
+            },
+            Phase {
+                activities: vec![ActivitySum {
+                    name: "Second B".to_string(),
+                    id: 2,
+                }],
+            },
+            Phase {
+                activities: vec![ActivitySum {
+                    name: "Third C".to_string(),
+                    id: 3,
+                }],
+            },
+        ];
+        assert_eq!(roadmap, expected)
+    }
+
+    #[test]
+    fn test_get_phases_predecessors_in_non_adjacent_phases() {
+        // "Fourth D" depends on "First A" (phase 1) and "Third C" (phase 3),
+        // "Second E" depends on "First A" only
+        let acts_with_deps: Vec<ActivityWithRelatedDependencies> = vec![
+            ActivityWithRelatedDependencies {
+                head_name: "First A".to_string(),
+                activity_id: 1,
+                activity_id_head: None,
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Second B".to_string(),
+                activity_id: 2,
+                activity_id_head: Some(1),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Third C".to_string(),
+                activity_id: 3,
+                activity_id_head: Some(2),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Fourth D".to_string(),
+                activity_id: 4,
+                activity_id_head: Some(1),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Fourth D".to_string(),
+                activity_id: 4,
+                activity_id_head: Some(3),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Second E".to_string(),
+                activity_id: 5,
+                activity_id_head: Some(1),
+            },
+        ];
+        let roadmap = get_phases(acts_with_deps).unwrap();
+        let phases: Vec<Vec<i32>> = roadmap
+            .iter()
+            .map(|phase| phase.activities.iter().map(|act| act.id).collect())
+            .collect();
+        assert_eq!(phases, vec![vec![1], vec![2, 5], vec![3], vec![4]]);
+    }
+
+    #[test]
+    fn test_get_phases_with_cycle() {
+        let acts_with_deps: Vec<ActivityWithRelatedDependencies> = vec![
+            ActivityWithRelatedDependencies {
+                head_name: "First A".to_string(),
+                activity_id: 1,
+                activity_id_head: Some(2),
+            },
+            ActivityWithRelatedDependencies {
+                head_name: "Second B".to_string(),
+                activity_id: 2,
+                activity_id_head: Some(1),
+            },
+        ];
+        assert!(get_phases(acts_with_deps).is_err());
+    }
 }
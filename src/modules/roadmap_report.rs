@@ -1,9 +1,22 @@
+use anyhow::Result;
 use cli_table::{format::Justify, Table, WithTitle};
 use serde::Serialize;
 
 use super::{
-    activity::ActivityId,
-    roadmap::{ActivitySum, Roadmap},
+    activity::{Activity, ActivityId, EstimationValue},
+    gantt_report::GanttReport,
+    json_report::{to_json, PertJson},
+    markdown_report::{escape_markdown, markdown_table},
+    pert::Pert,
+    pert_report::{pert_header, progress_lines, unit_description},
+    progress::Forecast,
+    report::round,
+    roadmap::Roadmap,
+    schedule::Schedule,
+    schedule_report::project_dates,
+    tags::{tag_subtotals, TagSubtotal},
+    tags_report::{subtotals_body, subtotals_header, subtotals_table},
+    templates::render,
 };
 
 #[derive(Table, Serialize)]
@@ -12,61 +25,453 @@ struct ActivityRow {
     id: ActivityId,
     #[table(title = "Name")]
     name: String,
+    #[table(skip)]
+    optimistic: EstimationValue,
+    #[table(skip)]
+    probable: EstimationValue,
+    #[table(skip)]
+    pessimistic: EstimationValue,
+    #[table(skip)]
+    pert: f64,
+    #[table(title = "Status")]
+    status: &'static str,
+    #[table(title = "Remaining")]
+    remaining: f64,
+    #[table(skip)]
+    tags: Vec<String>,
+    #[table(skip)]
+    start: Option<String>,
+    #[table(skip)]
+    finish: Option<String>,
 }
 
-impl From<ActivitySum> for ActivityRow {
-    fn from(act: ActivitySum) -> Self {
-        ActivityRow {
-            id: act.id,
-            name: act.name,
-        }
-    }
+#[derive(Table)]
+struct DatedActivityRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Status")]
+    status: &'static str,
+    #[table(title = "Remaining")]
+    remaining: f64,
+    #[table(title = "Start")]
+    start: String,
+    #[table(title = "Finish")]
+    finish: String,
 }
 
+#[derive(Serialize)]
 struct PhasesRow {
     id: usize,
     activities: Vec<ActivityRow>,
+    start: Option<String>,
+    finish: Option<String>,
 }
 
-impl From<Roadmap> for Vec<PhasesRow> {
-    fn from(roadmap: Roadmap) -> Self {
-        roadmap
+pub struct RoadmapReport {
+    pert: Pert,
+    phases: Vec<PhasesRow>,
+    schedule: Option<Schedule>,
+    forecast: Forecast,
+    gantt: GanttReport,
+    subtotals: Vec<TagSubtotal>,
+}
+
+impl RoadmapReport {
+    pub fn new(
+        pert: Pert,
+        roadmap: Roadmap,
+        activities: Vec<Activity>,
+        schedule: Option<Schedule>,
+        forecast: Forecast,
+        gantt: GanttReport,
+    ) -> Self {
+        let phases = roadmap
             .phases
             .into_iter()
             .enumerate()
-            .map(|(id, ph)| {
-                let activities = ph.activities.into_iter().map(|act| act.into()).collect();
+            .map(|(index, phase)| {
+                let activities: Vec<ActivityRow> = phase
+                    .activities
+                    .into_iter()
+                    .map(|act| {
+                        let activity = activities.iter().find(|activity| activity.id == act.id);
+                        let dates = schedule.as_ref().and_then(|sch| sch.get(act.id));
+                        ActivityRow {
+                            id: act.id,
+                            name: act.name,
+                            optimistic: activity.map_or(0f64, |a| round(a.estimation.optimistic)),
+                            probable: activity.map_or(0f64, |a| round(a.estimation.probable)),
+                            pessimistic: activity.map_or(0f64, |a| round(a.estimation.pessimistic)),
+                            pert: activity.map_or(0f64, |a| round(a.estimated())),
+                            status: activity.map_or("", |a| a.progress.status.name()),
+                            remaining: activity.map_or(0f64, |a| round(a.remaining().estimated())),
+                            tags: activity.map_or(vec![], |a| a.tags.clone()),
+                            start: dates.map(|dates| dates.start.to_string()),
+                            finish: dates.map(|dates| dates.finish.to_string()),
+                        }
+                    })
+                    .collect();
+                let phase_dates: Vec<_> = activities
+                    .iter()
+                    .filter_map(|act| schedule.as_ref().and_then(|sch| sch.get(act.id)))
+                    .collect();
                 PhasesRow {
-                    id: id + 1,
+                    id: index + 1,
+                    start: phase_dates
+                        .iter()
+                        .map(|dates| dates.start)
+                        .min()
+                        .map(|date| date.to_string()),
+                    finish: phase_dates
+                        .iter()
+                        .map(|dates| dates.finish)
+                        .max()
+                        .map(|date| date.to_string()),
                     activities,
                 }
             })
-            .collect()
+            .collect();
+        let subtotals = tag_subtotals(
+            activities
+                .iter()
+                .map(|act| (act.tags.as_slice(), act.estimated(), act.variance())),
+        );
+        Self {
+            pert,
+            phases,
+            schedule,
+            forecast,
+            gantt,
+            subtotals,
+        }
     }
-}
-
-pub struct RoadmapReport {
-    phases: Vec<PhasesRow>,
-}
 
-impl RoadmapReport {
-    pub fn new(roadmap: Roadmap) -> Self {
-        Self {
-            phases: roadmap.into(),
+    fn dated_phase(phase: &PhasesRow, schedule: &Schedule) -> String {
+        let rows: Vec<DatedActivityRow> = phase
+            .activities
+            .iter()
+            .filter_map(|act| {
+                schedule.get(act.id).map(|dates| DatedActivityRow {
+                    id: act.id,
+                    name: act.name.to_owned(),
+                    status: act.status,
+                    remaining: act.remaining,
+                    start: dates.start.to_string(),
+                    finish: dates.finish.to_string(),
+                })
+            })
+            .collect();
+        let table = rows.with_title().display().unwrap();
+        match (&phase.start, &phase.finish) {
+            (Some(start), Some(finish)) => {
+                format!("Phase #{} ({} - {})\n{}", phase.id, start, finish, table)
+            }
+            _ => format!("Phase #{}\n{}", phase.id, table),
         }
     }
 
     pub fn ascii(&mut self) -> String {
-        self.phases
+        let phases = self
+            .phases
             .iter()
-            .map(|phase| {
-                format!(
+            .map(|phase| match &self.schedule {
+                Some(schedule) => Self::dated_phase(phase, schedule),
+                None => format!(
                     "Phase #{}\n{}",
                     phase.id,
                     phase.activities.with_title().display().unwrap()
-                )
+                ),
             })
             .collect::<Vec<String>>()
-            .join("\n\n")
+            .join("\n\n");
+        let phases = match &self.schedule {
+            Some(schedule) => format!("{}\n\n{}", phases, project_dates(schedule)),
+            None => phases,
+        };
+        let progress: Vec<String> = progress_lines(&self.forecast, self.pert.unit)
+            .into_iter()
+            .map(|(title, value)| format!("{}: {}", title.to_uppercase(), value))
+            .collect();
+        let progress = if self.subtotals.is_empty() {
+            progress.join("\n")
+        } else {
+            format!(
+                "{}\n\nBY TAG\n{}",
+                progress.join("\n"),
+                subtotals_table(&self.subtotals)
+            )
+        };
+        format!(
+            "{}\n\n{}\n\n{}\n\n{}",
+            pert_header(&self.pert),
+            phases,
+            progress,
+            self.gantt.ascii()
+        )
+    }
+
+    fn table_header(&self, with_phase: bool) -> Vec<String> {
+        let unit = self.pert.unit;
+        let mut header = vec![
+            "ID".to_string(),
+            "Name".to_string(),
+            format!("Optimistic ({})", unit),
+            format!("Probable ({})", unit),
+            format!("Pessimistic ({})", unit),
+            format!("PERT ({}) ({})", self.pert.estimation_model, unit),
+            "Status".to_string(),
+            format!("Remaining ({})", unit),
+        ];
+        if self.schedule.is_some() {
+            header.push("Start".to_string());
+            header.push("Finish".to_string());
+        }
+        if with_phase {
+            header.insert(0, "Phase".to_string());
+        }
+        header
+    }
+
+    fn table_row(&self, act: &ActivityRow) -> Vec<String> {
+        let mut row = vec![
+            act.id.to_string(),
+            act.name.to_owned(),
+            act.optimistic.to_string(),
+            act.probable.to_string(),
+            act.pessimistic.to_string(),
+            act.pert.to_string(),
+            act.status.to_string(),
+            act.remaining.to_string(),
+        ];
+        if self.schedule.is_some() {
+            row.push(act.start.to_owned().unwrap_or_default());
+            row.push(act.finish.to_owned().unwrap_or_default());
+        }
+        row
+    }
+
+    pub fn csv(&mut self) -> String {
+        let mut csv_rows = vec![self.table_header(true)];
+        for phase in &self.phases {
+            for act in &phase.activities {
+                let mut row = self.table_row(act);
+                row.insert(0, phase.id.to_string());
+                csv_rows.push(row);
+            }
+        }
+        if !self.subtotals.is_empty() {
+            csv_rows.push(subtotals_header(self.pert.unit));
+            csv_rows.append(&mut subtotals_body(&self.subtotals));
+        }
+
+        csv_rows
+            .into_iter()
+            .map(|vec_row| vec_row.join(","))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn html(&mut self) -> Result<String> {
+        let globals = liquid::object!({
+            "pert_name": self.pert.name,
+            "estimation_model": self.pert.estimation_model.to_string(),
+            "unit": self.pert.unit.name(),
+            "phases": self.phases,
+            "scheduled": self.schedule.is_some(),
+            "start_date": self.schedule.as_ref().map(|sch| sch.start.to_string()),
+            "finish_date": self.schedule.as_ref().map(|sch| sch.finish.to_string()),
+            "progress": progress_lines(&self.forecast, self.pert.unit),
+            "tag_subtotals": self.subtotals,
+            "gantt": self.gantt.svg()
+        });
+
+        render("report_roadmap.liquid", &globals)
+    }
+
+    pub fn markdown(&mut self) -> String {
+        let mut sections = vec![format!(
+            "# {} - Roadmap\n\nEstimation model: {}  \nUnit: {}",
+            escape_markdown(&self.pert.name),
+            self.pert.estimation_model,
+            unit_description(&self.pert)
+        )];
+        let header = self.table_header(false);
+        for phase in &self.phases {
+            let title = match (&phase.start, &phase.finish) {
+                (Some(start), Some(finish)) => {
+                    format!("## Phase #{} ({} - {})", phase.id, start, finish)
+                }
+                _ => format!("## Phase #{}", phase.id),
+            };
+            let rows: Vec<Vec<String>> = phase
+                .activities
+                .iter()
+                .map(|act| self.table_row(act))
+                .collect();
+            sections.push(format!("{}\n\n{}", title, markdown_table(&header, &rows)));
+        }
+        let mut summary = vec![];
+        if let Some(schedule) = &self.schedule {
+            summary.push(format!("**Start:** {}", schedule.start));
+            summary.push(format!("**Finish:** {}", schedule.finish));
+        }
+        summary.extend(
+            progress_lines(&self.forecast, self.pert.unit)
+                .into_iter()
+                .map(|(title, value)| format!("**{}:** {}", title, value)),
+        );
+        sections.push(summary.join("  \n"));
+        if !self.subtotals.is_empty() {
+            sections.push(format!(
+                "## Tags\n\n{}",
+                markdown_table(
+                    &subtotals_header(self.pert.unit),
+                    &subtotals_body(&self.subtotals)
+                )
+            ));
+        }
+        sections.join("\n\n")
+    }
+
+    pub fn json(&mut self) -> Result<String> {
+        #[derive(Serialize)]
+        struct RoadmapJsonReport<'a> {
+            pert: PertJson,
+            phases: &'a [PhasesRow],
+            start_date: Option<String>,
+            finish_date: Option<String>,
+            progress: &'a Forecast,
+            tag_subtotals: &'a [TagSubtotal],
+        }
+
+        to_json(
+            "roadmap",
+            RoadmapJsonReport {
+                pert: (&self.pert).into(),
+                phases: &self.phases,
+                start_date: self.schedule.as_ref().map(|sch| sch.start.to_string()),
+                finish_date: self.schedule.as_ref().map(|sch| sch.finish.to_string()),
+                progress: &self.forecast,
+                tag_subtotals: &self.subtotals,
+            },
+        )
+    }
+
+    pub fn svg(&mut self) -> String {
+        self.gantt.svg()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RoadmapReport;
+    use crate::modules::{
+        activity::{parse_tags, Activity, Progress, Status},
+        calendar::Calendar,
+        critical_path::CriticalPath,
+        gantt_report::GanttReport,
+        graph::DependencyGraph,
+        pert::Pert,
+        progress::Forecast,
+        roadmap::Roadmap,
+        schedule::Schedule,
+        test_util::dep,
+    };
+    use chrono::NaiveDate;
+
+    fn example_report(start_date: Option<NaiveDate>) -> RoadmapReport {
+        example_report_tagged(start_date, ["", "", "", ""])
+    }
+
+    /// `tags` of every activity, separated by commas.
+    fn example_report_tagged(start_date: Option<NaiveDate>, tags: [&str; 4]) -> RoadmapReport {
+        let dependencies = [(1, None), (2, Some(1)), (3, Some(1)), (4, Some(3))]
+            .into_iter()
+            .map(|(id, head)| dep(id, head))
+            .collect::<Vec<_>>();
+        let mut activities = vec![
+            Activity::new(1, "activity 1".to_string(), 1.0, 2.0, 3.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 2.0, 4.0, 6.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.0, 1.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 0.5, 0.5, 0.5).unwrap(),
+        ];
+        activities[0].progress = Progress::new(Status::Done, 100.0).unwrap();
+        activities[1].progress = Progress::new(Status::InProgress, 50.0).unwrap();
+        for (activity, tags) in activities.iter_mut().zip(tags) {
+            activity.tags = parse_tags(tags);
+        }
+        let pert = Pert::new(1, "example".to_string());
+        let graph = DependencyGraph::new(&dependencies);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        let calendar = Calendar::new(&pert, vec![]).unwrap();
+        let schedule = start_date
+            .map(|start_date| Schedule::new(start_date, &critical_path, &calendar).unwrap());
+        let forecast = Forecast::new(
+            &activities,
+            &graph,
+            start_date.map(|start_date| (start_date, &calendar)),
+        )
+        .unwrap();
+
+        let roadmap = Roadmap::new(dependencies).unwrap();
+        RoadmapReport::new(pert, roadmap, activities, schedule, forecast, gantt)
+    }
+
+    #[test]
+    fn table() {
+        let mut report = example_report(None);
+        insta::assert_display_snapshot!(report.ascii());
+    }
+
+    #[test]
+    fn table_with_dates() {
+        let mut report = example_report(NaiveDate::from_ymd_opt(2022, 6, 1));
+        insta::assert_display_snapshot!(report.ascii());
+    }
+
+    #[test]
+    fn table_html() {
+        let mut report = example_report(None);
+        insta::assert_display_snapshot!(report.html().unwrap());
+    }
+
+    #[test]
+    fn table_csv() {
+        let mut report = example_report(None);
+        insta::assert_display_snapshot!(report.csv());
+    }
+
+    #[test]
+    fn table_json() {
+        let mut report = example_report(NaiveDate::from_ymd_opt(2022, 6, 1));
+        insta::assert_display_snapshot!(report.json().unwrap());
+    }
+
+    #[test]
+    fn table_markdown() {
+        let mut report = example_report(None);
+        insta::assert_display_snapshot!(report.markdown());
+    }
+
+    #[test]
+    fn table_markdown_with_dates() {
+        let mut report = example_report(NaiveDate::from_ymd_opt(2022, 6, 1));
+        insta::assert_display_snapshot!(report.markdown());
+    }
+
+    #[test]
+    fn table_csv_with_dates() {
+        let mut report = example_report(NaiveDate::from_ymd_opt(2022, 6, 1));
+        insta::assert_display_snapshot!(report.csv());
+    }
+
+    #[test]
+    fn table_with_tags() {
+        let mut report = example_report_tagged(None, ["backend", "backend, ui", "ui", ""]);
+        insta::assert_display_snapshot!(report.ascii());
+        insta::assert_display_snapshot!(report.markdown());
     }
 }
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use super::{activity::ActivityId, calendar::Calendar, critical_path::CriticalPath};
+
+const FLOAT_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ActivityDates {
+    pub id: ActivityId,
+    pub name: String,
+    /// First working day of the activity.
+    pub start: NaiveDate,
+    /// Last working day of the activity.
+    pub finish: NaiveDate,
+}
+
+/// Planned dates of the activities, starting each one as early as possible.
+#[derive(Debug, PartialEq)]
+pub struct Schedule {
+    pub start: NaiveDate,
+    pub finish: NaiveDate,
+    pub activities: Vec<ActivityDates>,
+}
+
+impl Schedule {
+    pub fn new(
+        start: NaiveDate,
+        critical_path: &CriticalPath,
+        calendar: &Calendar,
+    ) -> Result<Self> {
+        let start = calendar.working_day(start, 0);
+        let mut finish = start;
+        let mut activities = vec![];
+        for scheduled in &critical_path.activities {
+            // offsets are in working days from the beginning of the first one
+            let from = calendar.working_days_for(scheduled.earliest_start)?;
+            let to = calendar.working_days_for(scheduled.earliest_finish)?;
+            let first_day = (from + FLOAT_TOLERANCE).floor() as usize;
+            let last_day = ((to - FLOAT_TOLERANCE).ceil() as usize).max(first_day + 1) - 1;
+            let dates = ActivityDates {
+                id: scheduled.id,
+                name: scheduled.name.to_owned(),
+                start: calendar.working_day(start, first_day),
+                finish: calendar.working_day(start, last_day),
+            };
+            finish = finish.max(dates.finish);
+            activities.push(dates);
+        }
+        Ok(Self {
+            start,
+            finish,
+            activities,
+        })
+    }
+
+    pub fn get(&self, id: ActivityId) -> Option<&ActivityDates> {
+        self.activities.iter().find(|act| act.id == id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Schedule;
+    use crate::modules::{
+        activity::Activity, calendar::Calendar, critical_path::CriticalPath,
+        duration::DurationUnit, graph::DependencyGraph, pert::Pert, test_util::dep,
+    };
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn dates_of_activities() {
+        let activities = vec![
+            Activity::new(1, "A".to_string(), 2.0, 2.0, 2.0).unwrap(),
+            Activity::new(2, "B".to_string(), 3.0, 3.0, 3.0).unwrap(),
+            Activity::new(3, "C".to_string(), 0.5, 0.5, 0.5).unwrap(),
+            Activity::new(4, "D".to_string(), 1.5, 1.5, 1.5).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&[
+            dep(1, None),
+            dep(2, Some(1)),
+            dep(3, Some(1)),
+            dep(4, Some(3)),
+        ]);
+        let critical_path = CriticalPath::new(&activities, &graph).unwrap();
+        let pert = Pert::new(1, "example".to_string());
+        // 2022-06-02 is a holiday
+        let calendar = Calendar::new(&pert, vec![date(2022, 6, 2)]).unwrap();
+
+        // Saturday, the project starts on Monday 2022-05-30
+        let schedule = Schedule::new(date(2022, 5, 28), &critical_path, &calendar).unwrap();
+        assert_eq!(schedule.start, date(2022, 5, 30));
+        let dates = |id| {
+            let act = schedule.get(id).unwrap();
+            (act.start, act.finish)
+        };
+        assert_eq!(dates(1), (date(2022, 5, 30), date(2022, 5, 31)));
+        assert_eq!(dates(2), (date(2022, 6, 1), date(2022, 6, 6)));
+        // half a day, shares its last day with D
+        assert_eq!(dates(3), (date(2022, 6, 1), date(2022, 6, 1)));
+        assert_eq!(dates(4), (date(2022, 6, 1), date(2022, 6, 3)));
+        assert_eq!(schedule.finish, date(2022, 6, 6));
+    }
+
+    #[test]
+    fn dates_in_hours() {
+        let activities = vec![Activity::new(1, "A".to_string(), 12.0, 12.0, 12.0).unwrap()];
+        let critical_path =
+            CriticalPath::new(&activities, &DependencyGraph::new(&[dep(1, None)])).unwrap();
+        let mut pert = Pert::new(1, "example".to_string());
+        pert.unit = DurationUnit::Hours;
+        let calendar = Calendar::new(&pert, vec![]).unwrap();
+
+        let schedule = Schedule::new(date(2022, 5, 30), &critical_path, &calendar).unwrap();
+        assert_eq!(schedule.finish, date(2022, 5, 31));
+    }
+}
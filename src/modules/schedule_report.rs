@@ -0,0 +1,47 @@
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    activity::ActivityId,
+    schedule::{ActivityDates, Schedule},
+};
+
+#[derive(Table, Serialize)]
+pub struct ActivityDatesRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Start")]
+    start: String,
+    #[table(title = "Finish")]
+    finish: String,
+}
+
+impl From<&ActivityDates> for ActivityDatesRow {
+    fn from(dates: &ActivityDates) -> Self {
+        Self {
+            id: dates.id,
+            name: dates.name.to_owned(),
+            start: dates.start.to_string(),
+            finish: dates.finish.to_string(),
+        }
+    }
+}
+
+pub fn dates_rows(schedule: &Schedule) -> Vec<ActivityDatesRow> {
+    schedule
+        .activities
+        .iter()
+        .map(|dates| dates.into())
+        .collect()
+}
+
+pub fn dates_table(rows: &[ActivityDatesRow]) -> String {
+    rows.with_title().display().unwrap().to_string()
+}
+
+/// Project dates, shown at the bottom of the reports.
+pub fn project_dates(schedule: &Schedule) -> String {
+    format!("START: {}\nFINISH: {}", schedule.start, schedule.finish)
+}
@@ -0,0 +1,300 @@
+use anyhow::{bail, Context, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Beta, Distribution, Triangular};
+use std::collections::HashMap;
+
+use super::{
+    activity::{Activity, ActivityId},
+    estimation_model::{EstimationModel, DEFAULT_LAMBDA},
+    graph::DependencyGraph,
+};
+
+pub const DEFAULT_ITERATIONS: usize = 10_000;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SamplingDistribution {
+    /// The distribution of the estimation model of each activity, see `model_sample`.
+    BetaPert,
+    Triangular,
+}
+
+impl SamplingDistribution {
+    fn sample<R: Rng>(&self, activity: &Activity, rng: &mut R) -> Result<f64> {
+        let estimation = &activity.estimation;
+        if estimation.pessimistic - estimation.optimistic <= 0f64 {
+            return Ok(estimation.probable);
+        }
+        match self {
+            SamplingDistribution::BetaPert => model_sample(activity, rng),
+            SamplingDistribution::Triangular => triangular_sample(activity, rng),
+        }
+    }
+}
+
+/// Sample with the mean of the estimation model of the activity: a beta-PERT for the
+/// classic and modified PERT, a triangular distribution for the triangular model, and
+/// for the pessimism-weighted one a beta moving weight from the most probable to the
+/// pessimistic value, `α = 2 + 3(m - o) / (p - o)` and `β = 1 + 3(p - m) / (p - o)`.
+fn model_sample<R: Rng>(activity: &Activity, rng: &mut R) -> Result<f64> {
+    let estimation = &activity.estimation;
+    let optimistic = estimation.optimistic;
+    let probable = estimation.probable;
+    let pessimistic = estimation.pessimistic;
+    let range = pessimistic - optimistic;
+    let (alpha, beta) = match activity.model {
+        EstimationModel::Triangular => return triangular_sample(activity, rng),
+        EstimationModel::PessimismWeighted => (
+            2f64 + 3f64 * (probable - optimistic) / range,
+            1f64 + 3f64 * (pessimistic - probable) / range,
+        ),
+        EstimationModel::Classic | EstimationModel::ModifiedPert { .. } => {
+            let lambda = activity.model.lambda().unwrap_or(DEFAULT_LAMBDA);
+            (
+                1f64 + lambda * (probable - optimistic) / range,
+                1f64 + lambda * (pessimistic - probable) / range,
+            )
+        }
+    };
+    let distribution =
+        Beta::new(alpha, beta).context("Unable to sample a beta-PERT distribution")?;
+    Ok(optimistic + distribution.sample(rng) * range)
+}
+
+fn triangular_sample<R: Rng>(activity: &Activity, rng: &mut R) -> Result<f64> {
+    let estimation = &activity.estimation;
+    let distribution = Triangular::new(
+        estimation.optimistic,
+        estimation.pessimistic,
+        estimation.probable,
+    )
+    .context("Unable to sample a triangular distribution")?;
+    Ok(distribution.sample(rng))
+}
+
+pub struct MonteCarlo {
+    pub iterations: usize,
+    pub seed: Option<u64>,
+    pub distribution: SamplingDistribution,
+}
+
+impl Default for MonteCarlo {
+    fn default() -> Self {
+        Self {
+            iterations: DEFAULT_ITERATIONS,
+            seed: None,
+            distribution: SamplingDistribution::BetaPert,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ActivityCriticality {
+    pub id: ActivityId,
+    pub name: String,
+    /// Share of the iterations in which the activity was on the critical path.
+    pub criticality: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SimulationResult {
+    /// Simulated project durations, sorted ascending.
+    pub durations: Vec<f64>,
+    pub activities: Vec<ActivityCriticality>,
+}
+
+impl SimulationResult {
+    pub fn iterations(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.durations.iter().sum::<f64>() / self.durations.len() as f64
+    }
+
+    /// Duration not exceeded in `percentile` percent of the iterations (nearest rank).
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        let rank = (percentile / 100f64 * self.durations.len() as f64).ceil() as usize;
+        self.durations[rank.clamp(1, self.durations.len()) - 1]
+    }
+}
+
+impl MonteCarlo {
+    pub fn run(
+        &self,
+        activities: &[Activity],
+        graph: &DependencyGraph,
+    ) -> Result<SimulationResult> {
+        if self.iterations == 0 {
+            bail!("The simulation needs at least one iteration");
+        }
+        let mut rng = match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
+        let activities_by_id: HashMap<ActivityId, &Activity> =
+            activities.iter().map(|act| (act.id, act)).collect();
+        let order = graph.topological_order()?;
+        let mut critical_count: HashMap<ActivityId, usize> = HashMap::new();
+        let mut durations = Vec::with_capacity(self.iterations);
+
+        for _ in 0..self.iterations {
+            let mut finish: HashMap<ActivityId, f64> = HashMap::new();
+            // the predecessor that determined the start of each activity
+            let mut driving: HashMap<ActivityId, ActivityId> = HashMap::new();
+            for id in &order {
+                let activity = activities_by_id
+                    .get(id)
+                    .with_context(|| format!("Activity {} not found", id))?;
+                let mut start = 0f64;
+                for pred in graph.predecessors(*id) {
+                    if !driving.contains_key(id) || finish[pred] > start {
+                        start = finish[pred];
+                        driving.insert(*id, *pred);
+                    }
+                }
+                let duration = self.distribution.sample(activity, &mut rng)?;
+                finish.insert(*id, start + duration);
+            }
+
+            let last = order.iter().max_by(|a, b| finish[a].total_cmp(&finish[b]));
+            durations.push(last.map_or(0f64, |id| finish[id]));
+            let mut current = last.copied();
+            while let Some(id) = current {
+                *critical_count.entry(id).or_default() += 1;
+                current = driving.get(&id).copied();
+            }
+        }
+
+        durations.sort_by(f64::total_cmp);
+        let activities = activities
+            .iter()
+            .map(|act| ActivityCriticality {
+                id: act.id,
+                name: act.name.to_owned(),
+                criticality: *critical_count.get(&act.id).unwrap_or(&0) as f64
+                    / self.iterations as f64,
+            })
+            .collect();
+
+        Ok(SimulationResult {
+            durations,
+            activities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MonteCarlo, SamplingDistribution};
+    use crate::modules::{
+        activity::Activity, estimation_model::EstimationModel, graph::DependencyGraph,
+        test_util::dep,
+    };
+
+    fn example() -> (Vec<Activity>, DependencyGraph) {
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 2.0, 4.0, 9.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 5.0, 6.0, 12.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 4.0, 6.0, 8.0).unwrap(),
+            Activity::new(4, "activity 4".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&[
+            dep(1, None),
+            dep(2, Some(1)),
+            dep(3, Some(1)),
+            dep(4, Some(2)),
+            dep(4, Some(3)),
+        ]);
+        (activities, graph)
+    }
+
+    #[test]
+    fn simulation_without_uncertainty() {
+        let activities = vec![
+            Activity::new(1, "activity 1".to_string(), 3.0, 3.0, 3.0).unwrap(),
+            Activity::new(2, "activity 2".to_string(), 4.0, 4.0, 4.0).unwrap(),
+            Activity::new(3, "activity 3".to_string(), 1.0, 1.0, 1.0).unwrap(),
+        ];
+        let graph = DependencyGraph::new(&[dep(1, None), dep(2, Some(1)), dep(3, None)]);
+        let simulation = MonteCarlo {
+            iterations: 100,
+            seed: Some(42),
+            ..MonteCarlo::default()
+        };
+
+        let result = simulation.run(&activities, &graph).unwrap();
+
+        assert_eq!(result.iterations(), 100);
+        assert_eq!(result.percentile(50.0), 7.0);
+        assert_eq!(result.percentile(95.0), 7.0);
+        let criticality: Vec<f64> = result.activities.iter().map(|a| a.criticality).collect();
+        assert_eq!(criticality, vec![1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn simulation_is_reproducible_with_seed() {
+        let (activities, graph) = example();
+        for distribution in [
+            SamplingDistribution::BetaPert,
+            SamplingDistribution::Triangular,
+        ] {
+            let simulation = MonteCarlo {
+                iterations: 1000,
+                seed: Some(7),
+                distribution,
+            };
+            let first = simulation.run(&activities, &graph).unwrap();
+            let second = simulation.run(&activities, &graph).unwrap();
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn simulation_percentiles() {
+        let (activities, graph) = example();
+        let simulation = MonteCarlo {
+            iterations: 5000,
+            seed: Some(1),
+            ..MonteCarlo::default()
+        };
+
+        let result = simulation.run(&activities, &graph).unwrap();
+
+        let p50 = result.percentile(50.0);
+        let p80 = result.percentile(80.0);
+        let p95 = result.percentile(95.0);
+        assert!(p50 <= p80 && p80 <= p95);
+        // the sum of the PERT means along 1 -> 2 -> 4 is about 12.33, parallel
+        // branches can only push the simulated duration up
+        assert!(result.mean() > 12.33 && result.mean() < 14.0);
+        assert!(result.durations.iter().all(|d| *d >= 7.0 && *d <= 22.0));
+        let criticality: Vec<f64> = result.activities.iter().map(|a| a.criticality).collect();
+        assert_eq!(criticality[0], 1.0);
+        assert_eq!(criticality[3], 1.0);
+        assert!((criticality[1] + criticality[2] - 1.0).abs() < 1e-9);
+        assert!(criticality[1] > criticality[2]);
+    }
+
+    #[test]
+    fn simulation_follows_the_estimation_model() {
+        let graph = DependencyGraph::new(&[dep(1, None)]);
+        for model in [
+            EstimationModel::Classic,
+            EstimationModel::Triangular,
+            EstimationModel::ModifiedPert { lambda: 2.0 },
+            EstimationModel::PessimismWeighted,
+        ] {
+            let mut activity = Activity::new(1, "activity 1".to_string(), 6.0, 12.0, 24.0).unwrap();
+            activity.model = model;
+            let simulation = MonteCarlo {
+                iterations: 20_000,
+                seed: Some(3),
+                ..MonteCarlo::default()
+            };
+            let expected = activity.estimated();
+            let result = simulation.run(&[activity], &graph).unwrap();
+            assert!((result.mean() - expected).abs() < 0.1, "{}", model);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use cli_table::{format::Justify, Table, WithTitle};
+use serde::Serialize;
+
+use super::{
+    activity::ActivityId,
+    pert::Pert,
+    pert_report::pert_header,
+    simulation::{ActivityCriticality, SimulationResult},
+};
+
+pub const PERCENTILES: [f64; 3] = [50f64, 80f64, 95f64];
+
+#[derive(Table, Serialize)]
+struct CriticalityRow {
+    #[table(title = "ID", justify = "Justify::Right")]
+    id: ActivityId,
+    #[table(title = "Name")]
+    name: String,
+    #[table(title = "Critical (%)")]
+    criticality: f64,
+}
+
+impl From<&ActivityCriticality> for CriticalityRow {
+    fn from(act: &ActivityCriticality) -> Self {
+        Self {
+            id: act.id,
+            name: act.name.to_owned(),
+            criticality: act.criticality * 100f64,
+        }
+    }
+}
+
+pub struct SimulationReport {
+    pert: Pert,
+    result: SimulationResult,
+}
+
+impl SimulationReport {
+    pub fn new(pert: Pert, result: SimulationResult) -> Self {
+        Self { pert, result }
+    }
+
+    pub fn ascii(&mut self) -> String {
+        let percentiles = PERCENTILES
+            .iter()
+            .map(|p| {
+                format!(
+                    "P{}: {:.2} {}",
+                    p,
+                    self.result.percentile(*p),
+                    self.pert.unit
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let rows: Vec<CriticalityRow> = self
+            .result
+            .activities
+            .iter()
+            .map(|act| act.into())
+            .collect();
+
+        format!(
+            "{}\n\nIterations: {}\nMean: {:.2} {}\n{}\n\n{}",
+            pert_header(&self.pert),
+            self.result.iterations(),
+            self.result.mean(),
+            self.pert.unit,
+            percentiles,
+            rows.with_title().display().unwrap()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SimulationReport;
+    use crate::modules::{
+        pert::Pert,
+        simulation::{ActivityCriticality, SimulationResult},
+    };
+
+    #[test]
+    fn table() {
+        let pert = Pert::new(1, "example".to_string());
+        let result = SimulationResult {
+            durations: (1..=20).map(f64::from).collect(),
+            activities: vec![
+                ActivityCriticality {
+                    id: 1,
+                    name: "activity 1".to_string(),
+                    criticality: 1.0,
+                },
+                ActivityCriticality {
+                    id: 2,
+                    name: "activity 2".to_string(),
+                    criticality: 0.25,
+                },
+            ],
+        };
+
+        let mut report = SimulationReport::new(pert, result);
+        insta::assert_display_snapshot!(report.ascii());
+    }
+}
@@ -0,0 +1,541 @@
+//! Behaviour every `Storage` implementation must have, run by each of them with
+//! `storage_conformance_tests!`. Checks only rely on the data they create, so they
+//! can run against a database that is shared or not empty. Other crates get them
+//! with the `conformance` feature, to test their own `Storage`.
+
+use super::Storage;
+use crate::modules::{
+    activity::{ActivityId, Actual, Estimation, Progress, Status},
+    baseline::{Baseline, Snapshot},
+    calendar::{default_working_days, parse_weekdays},
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::{DurationUnit, DEFAULT_HOURS_PER_DAY},
+    estimation_model::EstimationModel,
+    pert::PertId,
+};
+use chrono::NaiveDate;
+use tempfile::TempDir;
+
+const UNKNOWN_ID: i32 = -1;
+
+/// Generates a test for every check, `$storage` is evaluated for each of them and
+/// the test is skipped when it is `None`.
+#[macro_export]
+macro_rules! storage_conformance_tests {
+    ($storage:expr) => {
+        $crate::storage_conformance_tests!(
+            $storage;
+            add_and_get_perts,
+            pert_settings,
+            unknown_ids,
+            activities_get_ids,
+            ids_are_not_reused,
+            activities_follow_the_estimation_model,
+            invalid_estimation_is_refused,
+            activity_of_unknown_pert_is_refused,
+            dependencies_are_listed,
+            invalid_dependencies_are_refused,
+            boards,
+            migrate_is_idempotent,
+            rename_pert,
+            update_activity,
+            delete_dependency,
+            delete_activity_with_its_dependencies,
+            delete_pert_with_its_activities,
+            baselines,
+            actuals,
+            progress,
+            estimates,
+            tags
+        );
+    };
+    ($storage:expr; $($check:ident),+) => {
+        $(
+            #[test]
+            fn $check() {
+                if let Some(mut subject) = $storage {
+                    use $crate::modules::storage::conformance::Subject;
+                    $crate::modules::storage::conformance::$check(subject.storage());
+                }
+            }
+        )+
+    };
+}
+
+/// What the checks run on: a storage, or a storage with the temporary folder that
+/// must outlive it.
+pub trait Subject {
+    fn storage(&mut self) -> &mut dyn Storage;
+}
+
+impl<S: Storage> Subject for S {
+    fn storage(&mut self) -> &mut dyn Storage {
+        self
+    }
+}
+
+impl<S: Storage> Subject for (S, TempDir) {
+    fn storage(&mut self) -> &mut dyn Storage {
+        &mut self.0
+    }
+}
+
+fn add_pert(storage: &mut dyn Storage, name: &str) -> PertId {
+    storage
+        .add_pert(name, EstimationModel::Classic, DurationUnit::Days)
+        .unwrap()
+}
+
+/// Ids of the new activities, in the order of `names`.
+fn add_activities(storage: &mut dyn Storage, pert_id: PertId, names: &[&str]) -> Vec<ActivityId> {
+    for name in names {
+        let estimation = Estimation::new(1.0, 2.0, 3.0).unwrap();
+        storage
+            .add_activity(pert_id, name.to_string(), estimation)
+            .unwrap();
+    }
+    let mut activities = storage.get_activities(pert_id).unwrap();
+    activities.sort_by_key(|act| act.id);
+    names
+        .iter()
+        .map(|name| activities.iter().find(|act| act.name == *name).unwrap().id)
+        .collect()
+}
+
+pub fn add_and_get_perts(storage: &mut dyn Storage) {
+    let first = add_pert(storage, "first");
+    let second = add_pert(storage, "second");
+    assert!(second > first);
+
+    let pert = storage.get_pert(first).unwrap().unwrap();
+    assert_eq!(pert.id, first);
+    assert_eq!(pert.name, "first");
+    assert_eq!(pert.estimation_model, EstimationModel::Classic);
+    assert_eq!(pert.unit, DurationUnit::Days);
+    assert_eq!(pert.hours_per_day, DEFAULT_HOURS_PER_DAY);
+    assert_eq!(pert.start_date, None);
+    assert_eq!(pert.working_days, default_working_days());
+
+    let ids: Vec<PertId> = storage
+        .get_perts()
+        .unwrap()
+        .iter()
+        .map(|pert| pert.id)
+        .collect();
+    assert!(ids.contains(&first));
+    assert!(ids.contains(&second));
+}
+
+pub fn pert_settings(storage: &mut dyn Storage) {
+    let model = EstimationModel::ModifiedPert { lambda: 3.0 };
+    let pert_id = storage
+        .add_pert("settings", model, DurationUnit::Hours)
+        .unwrap();
+    storage.set_hours_per_day(pert_id, 6.0).unwrap();
+    let start_date = NaiveDate::from_ymd_opt(2022, 6, 1);
+    storage.set_start_date(pert_id, start_date).unwrap();
+    let working_days = parse_weekdays("mon,wed").unwrap();
+    storage.set_working_days(pert_id, &working_days).unwrap();
+
+    let pert = storage.get_pert(pert_id).unwrap().unwrap();
+    assert_eq!(pert.estimation_model, model);
+    assert_eq!(pert.unit, DurationUnit::Hours);
+    assert_eq!(pert.hours_per_day, 6.0);
+    assert_eq!(pert.start_date, start_date);
+    assert_eq!(pert.working_days, working_days);
+
+    storage
+        .set_estimation_model(pert_id, EstimationModel::Triangular)
+        .unwrap();
+    storage.set_start_date(pert_id, None).unwrap();
+    let pert = storage.get_pert(pert_id).unwrap().unwrap();
+    assert_eq!(pert.estimation_model, EstimationModel::Triangular);
+    assert_eq!(pert.start_date, None);
+}
+
+pub fn unknown_ids(storage: &mut dyn Storage) {
+    assert!(storage.get_pert(UNKNOWN_ID).unwrap().is_none());
+    assert!(storage.get_activities(UNKNOWN_ID).unwrap().is_empty());
+    assert_eq!(storage.get_activity_pert_id(UNKNOWN_ID).unwrap(), None);
+}
+
+pub fn activities_get_ids(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "activities");
+    let other_pert_id = add_pert(storage, "other activities");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    let other_ids = add_activities(storage, other_pert_id, &["other"]);
+    assert!(ids[1] > ids[0]);
+    assert!(other_ids[0] > ids[1]);
+
+    assert_eq!(storage.get_activities(pert_id).unwrap().len(), 2);
+    assert_eq!(storage.get_activity_pert_id(ids[1]).unwrap(), Some(pert_id));
+    assert_eq!(
+        storage.get_activity_pert_id(other_ids[0]).unwrap(),
+        Some(other_pert_id)
+    );
+}
+
+pub fn ids_are_not_reused(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "deleted ids");
+    let ids = add_activities(storage, pert_id, &["first", "last"]);
+    storage.delete_activity(ids[1]).unwrap();
+    let new_ids = add_activities(storage, pert_id, &["new"]);
+    assert!(new_ids[0] > ids[1]);
+
+    storage.delete_pert(pert_id).unwrap();
+    assert!(add_pert(storage, "new") > pert_id);
+}
+
+pub fn activities_follow_the_estimation_model(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "model");
+    add_activities(storage, pert_id, &["activity"]);
+    storage
+        .set_estimation_model(pert_id, EstimationModel::Triangular)
+        .unwrap();
+
+    let activities = storage.get_activities(pert_id).unwrap();
+    assert_eq!(activities[0].model, EstimationModel::Triangular);
+    assert_eq!(
+        activities[0].estimation,
+        Estimation::new(1.0, 2.0, 3.0).unwrap()
+    );
+}
+
+pub fn invalid_estimation_is_refused(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "invalid estimation");
+    let estimation = Estimation {
+        optimistic: 3.0,
+        probable: 2.0,
+        pessimistic: 1.0,
+    };
+    assert!(storage
+        .add_activity(pert_id, "wrong".to_string(), estimation)
+        .is_err());
+    assert!(storage.get_activities(pert_id).unwrap().is_empty());
+}
+
+pub fn activity_of_unknown_pert_is_refused(storage: &mut dyn Storage) {
+    let estimation = Estimation::new(1.0, 2.0, 3.0).unwrap();
+    assert!(storage
+        .add_activity(UNKNOWN_ID, "orphan".to_string(), estimation)
+        .is_err());
+}
+
+pub fn dependencies_are_listed(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "dependencies");
+    let ids = add_activities(storage, pert_id, &["first", "second", "third"]);
+    storage.add_dependency(ids[1], ids[2]).unwrap();
+    storage.add_dependency(ids[0], ids[2]).unwrap();
+
+    // ordered by activity then head, the dependency graph takes its node order from them
+    let dependencies: Vec<_> = storage
+        .get_activities_with_related_dependencies(pert_id)
+        .unwrap()
+        .into_iter()
+        .map(|dep| (dep.activity_id, dep.head_name, dep.activity_id_head))
+        .collect();
+    assert_eq!(
+        dependencies,
+        vec![
+            (ids[0], "first".to_string(), None),
+            (ids[1], "second".to_string(), None),
+            (ids[2], "third".to_string(), Some(ids[0])),
+            (ids[2], "third".to_string(), Some(ids[1])),
+        ]
+    );
+}
+
+pub fn invalid_dependencies_are_refused(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "invalid dependencies");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    storage.add_dependency(ids[0], ids[1]).unwrap();
+
+    assert!(storage.add_dependency(ids[0], ids[1]).is_err());
+    assert!(storage.add_dependency(ids[0], UNKNOWN_ID).is_err());
+    assert!(storage.add_dependency(UNKNOWN_ID, ids[1]).is_err());
+}
+
+pub fn boards(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "board");
+    storage.create_board(pert_id, 42).unwrap();
+    assert!(storage.create_board(pert_id, 42).is_err());
+    assert!(storage.create_board(UNKNOWN_ID, 43).is_err());
+}
+
+pub fn migrate_is_idempotent(storage: &mut dyn Storage) {
+    storage.migrate().unwrap();
+    let version = storage.schema_version().unwrap();
+    assert!(storage.migrate().unwrap().is_empty());
+    assert_eq!(storage.schema_version().unwrap(), version);
+}
+
+fn dependencies(storage: &mut dyn Storage, pert_id: PertId) -> Vec<(ActivityId, ActivityId)> {
+    let mut dependencies: Vec<_> = storage
+        .get_activities_with_related_dependencies(pert_id)
+        .unwrap()
+        .into_iter()
+        .filter_map(|dep| dep.activity_id_head.map(|head| (head, dep.activity_id)))
+        .collect();
+    dependencies.sort_unstable();
+    dependencies
+}
+
+pub fn rename_pert(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "before");
+    storage.rename_pert(pert_id, "after").unwrap();
+    assert_eq!(storage.get_pert(pert_id).unwrap().unwrap().name, "after");
+}
+
+pub fn update_activity(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "update activity");
+    let ids = add_activities(storage, pert_id, &["before", "other"]);
+    let estimation = Estimation::new(2.0, 3.0, 5.0).unwrap();
+    storage
+        .update_activity(ids[0], "after".to_string(), estimation)
+        .unwrap();
+
+    // still ordered by id once updated
+    let activities = storage.get_activities(pert_id).unwrap();
+    let activity_ids: Vec<ActivityId> = activities.iter().map(|act| act.id).collect();
+    assert_eq!(activity_ids, ids);
+    assert_eq!(activities[0].name, "after");
+    assert_eq!(
+        activities[0].estimation,
+        Estimation::new(2.0, 3.0, 5.0).unwrap()
+    );
+
+    let invalid = Estimation {
+        optimistic: 3.0,
+        probable: 2.0,
+        pessimistic: 1.0,
+    };
+    assert!(storage
+        .update_activity(ids[0], "invalid".to_string(), invalid)
+        .is_err());
+    assert_eq!(storage.get_activities(pert_id).unwrap()[0].name, "after");
+}
+
+pub fn delete_dependency(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "delete dependency");
+    let ids = add_activities(storage, pert_id, &["first", "second", "third"]);
+    storage.add_dependency(ids[0], ids[2]).unwrap();
+    storage.add_dependency(ids[1], ids[2]).unwrap();
+    storage.delete_dependency(ids[0], ids[2]).unwrap();
+
+    assert_eq!(dependencies(storage, pert_id), vec![(ids[1], ids[2])]);
+    // a deleted dependency can be added again
+    storage.add_dependency(ids[0], ids[2]).unwrap();
+}
+
+pub fn delete_activity_with_its_dependencies(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "delete activity");
+    let ids = add_activities(storage, pert_id, &["first", "second", "third"]);
+    storage.add_dependency(ids[0], ids[1]).unwrap();
+    storage.add_dependency(ids[1], ids[2]).unwrap();
+    storage.add_dependency(ids[0], ids[2]).unwrap();
+    storage.delete_activity(ids[1]).unwrap();
+
+    let remaining: Vec<ActivityId> = storage
+        .get_activities(pert_id)
+        .unwrap()
+        .iter()
+        .map(|act| act.id)
+        .collect();
+    assert_eq!(remaining.len(), 2);
+    assert!(!remaining.contains(&ids[1]));
+    assert_eq!(storage.get_activity_pert_id(ids[1]).unwrap(), None);
+    assert_eq!(dependencies(storage, pert_id), vec![(ids[0], ids[2])]);
+}
+
+pub fn delete_pert_with_its_activities(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "delete pert");
+    let other_pert_id = add_pert(storage, "kept pert");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    let other_ids = add_activities(storage, other_pert_id, &["kept"]);
+    storage.add_dependency(ids[0], ids[1]).unwrap();
+    storage.create_board(pert_id, 44).unwrap();
+    let baseline = baseline(storage, pert_id, "v1");
+    storage.add_baseline(pert_id, &baseline).unwrap();
+    storage.delete_pert(pert_id).unwrap();
+
+    assert!(storage.get_pert(pert_id).unwrap().is_none());
+    assert!(storage.get_activities(pert_id).unwrap().is_empty());
+    assert!(storage.get_baselines(pert_id).unwrap().is_empty());
+    assert_eq!(storage.get_activity_pert_id(ids[0]).unwrap(), None);
+    assert_eq!(
+        storage.get_activity_pert_id(other_ids[0]).unwrap(),
+        Some(other_pert_id)
+    );
+    let ids: Vec<PertId> = storage
+        .get_perts()
+        .unwrap()
+        .iter()
+        .map(|pert| pert.id)
+        .collect();
+    assert!(!ids.contains(&pert_id));
+    assert!(ids.contains(&other_pert_id));
+}
+
+fn baseline(storage: &mut dyn Storage, pert_id: PertId, name: &str) -> Baseline {
+    let pert = storage.get_pert(pert_id).unwrap().unwrap();
+    let activities = storage.get_activities(pert_id).unwrap();
+    let acts_with_deps = storage
+        .get_activities_with_related_dependencies(pert_id)
+        .unwrap();
+    Baseline {
+        name: name.to_string(),
+        created_at: NaiveDate::from_ymd_opt(2022, 6, 1)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap(),
+        snapshot: Snapshot::new(&pert, &activities, &acts_with_deps).unwrap(),
+    }
+}
+
+pub fn baselines(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "baselines");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    storage.add_dependency(ids[0], ids[1]).unwrap();
+    let first = baseline(storage, pert_id, "v1");
+    storage.add_baseline(pert_id, &first).unwrap();
+    storage.delete_activity(ids[1]).unwrap();
+    let second = baseline(storage, pert_id, "v2");
+    storage.add_baseline(pert_id, &second).unwrap();
+
+    assert_eq!(storage.get_baselines(pert_id).unwrap(), vec![first, second]);
+    let duplicated = baseline(storage, pert_id, "v1");
+    assert!(storage.add_baseline(pert_id, &duplicated).is_err());
+    assert!(storage.add_baseline(UNKNOWN_ID, &duplicated).is_err());
+    assert!(storage.get_baselines(UNKNOWN_ID).unwrap().is_empty());
+}
+
+pub fn actuals(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "actuals");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    let actual = Actual {
+        start: NaiveDate::from_ymd_opt(2022, 6, 1),
+        finish: NaiveDate::from_ymd_opt(2022, 6, 3),
+        effort: Some(2.5),
+    };
+    storage.set_actual(ids[0], &actual).unwrap();
+    let invalid = Actual {
+        effort: Some(-1.0),
+        ..Actual::default()
+    };
+    assert!(storage.set_actual(ids[1], &invalid).is_err());
+
+    let activities = storage.get_activities(pert_id).unwrap();
+    let get = |id: ActivityId| activities.iter().find(|act| act.id == id).unwrap();
+    assert_eq!(get(ids[0]).actual, actual);
+    assert!(get(ids[1]).actual.is_empty());
+
+    storage.set_actual(ids[0], &Actual::default()).unwrap();
+    let activities = storage.get_activities(pert_id).unwrap();
+    assert!(activities.iter().all(|act| act.actual.is_empty()));
+}
+
+pub fn progress(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "progress");
+    let ids = add_activities(storage, pert_id, &["first", "second", "third"]);
+    let in_progress = Progress::new(Status::InProgress, 40.0).unwrap();
+    storage.set_progress(ids[0], &in_progress).unwrap();
+    let done = Progress::new(Status::Done, 100.0).unwrap();
+    storage.set_progress(ids[1], &done).unwrap();
+    let invalid = Progress {
+        status: Status::Done,
+        percent_complete: 50.0,
+    };
+    assert!(storage.set_progress(ids[2], &invalid).is_err());
+
+    let activities = storage.get_activities(pert_id).unwrap();
+    let get = |id: ActivityId| activities.iter().find(|act| act.id == id).unwrap();
+    assert_eq!(get(ids[0]).progress, in_progress);
+    assert_eq!(get(ids[1]).progress, done);
+    assert_eq!(get(ids[2]).progress, Progress::default());
+
+    storage.set_progress(ids[0], &Progress::default()).unwrap();
+    let activities = storage.get_activities(pert_id).unwrap();
+    let first = activities.iter().find(|act| act.id == ids[0]).unwrap();
+    assert_eq!(first.progress, Progress::default());
+}
+
+pub fn estimates(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "estimates");
+    assert_eq!(
+        storage.get_pert(pert_id).unwrap().unwrap().aggregation,
+        Aggregation::Mean
+    );
+    storage
+        .set_aggregation(pert_id, Aggregation::ConfidenceWeighted)
+        .unwrap();
+    assert_eq!(
+        storage.get_pert(pert_id).unwrap().unwrap().aggregation,
+        Aggregation::ConfidenceWeighted
+    );
+
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    let estimate = |estimator: &str, round: u32, probable: f64| EstimatorEstimate {
+        estimator: estimator.to_string(),
+        round,
+        estimation: Estimation::new(1.0, probable, 5.0).unwrap(),
+        confidence: 0.5,
+    };
+    storage
+        .add_estimate(ids[1], &estimate("ann", 1, 2.0))
+        .unwrap();
+    storage
+        .add_estimate(ids[0], &estimate("bob", 1, 3.0))
+        .unwrap();
+    storage
+        .add_estimate(ids[1], &estimate("ann", 2, 4.0))
+        .unwrap();
+    assert!(storage
+        .add_estimate(ids[1], &estimate("ann", 2, 3.0))
+        .is_err());
+    let unordered = EstimatorEstimate {
+        estimation: Estimation {
+            optimistic: 3.0,
+            probable: 2.0,
+            pessimistic: 1.0,
+        },
+        ..estimate("eve", 1, 2.0)
+    };
+    assert!(storage.add_estimate(ids[1], &unordered).is_err());
+    assert!(storage
+        .add_estimate(UNKNOWN_ID, &estimate("eve", 1, 2.0))
+        .is_err());
+
+    let estimates = storage.get_estimates(pert_id).unwrap();
+    assert_eq!(
+        estimates,
+        vec![
+            (ids[0], estimate("bob", 1, 3.0)),
+            (ids[1], estimate("ann", 1, 2.0)),
+            (ids[1], estimate("ann", 2, 4.0)),
+        ]
+    );
+
+    storage.delete_activity(ids[1]).unwrap();
+    assert_eq!(
+        storage.get_estimates(pert_id).unwrap(),
+        vec![(ids[0], estimate("bob", 1, 3.0))]
+    );
+}
+
+pub fn tags(storage: &mut dyn Storage) {
+    let pert_id = add_pert(storage, "tags");
+    let ids = add_activities(storage, pert_id, &["first", "second"]);
+    let tags = vec!["backend".to_string(), "tech debt".to_string()];
+    storage.set_tags(ids[0], &tags).unwrap();
+    assert!(storage.set_tags(ids[1], &["a,b".to_string()]).is_err());
+    assert!(storage.set_tags(ids[1], &[" ".to_string()]).is_err());
+
+    let activities = storage.get_activities(pert_id).unwrap();
+    let get = |id: ActivityId| activities.iter().find(|act| act.id == id).unwrap();
+    assert_eq!(get(ids[0]).tags, tags);
+    assert!(get(ids[1]).tags.is_empty());
+
+    storage.set_tags(ids[0], &[]).unwrap();
+    let activities = storage.get_activities(pert_id).unwrap();
+    assert!(activities.iter().all(|act| act.tags.is_empty()));
+}
@@ -0,0 +1,1020 @@
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use super::Storage;
+use crate::modules::{
+    activity::{validate_tags, Activity, ActivityId, Actual, Estimation, Progress, Status},
+    baseline::Baseline,
+    calendar::{default_working_days, parse_weekdays, weekdays_name},
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::{DurationUnit, DEFAULT_HOURS_PER_DAY},
+    estimation_model::EstimationModel,
+    github::BoardId,
+    pert::{Pert, PertId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+const FILE_PREFIX: &str = "pert-";
+const BASELINES_FILE_PREFIX: &str = "baselines-";
+const FILE_EXTENSION: &str = "toml";
+const IDS_FILE: &str = "ids.toml";
+
+#[derive(Serialize, Deserialize)]
+struct ActivityFile {
+    id: ActivityId,
+    /// Unique in the PERT and never changed, even when the id is.
+    key: String,
+    name: String,
+    optimistic: f64,
+    probable: f64,
+    pessimistic: f64,
+    /// Keys of the activities this one depends on, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual_start: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual_finish: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual_effort: Option<f64>,
+    /// Name of the status, left out for activities not started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    percent_complete: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Every round of every estimator, last as TOML needs tables after the values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    estimates: Vec<EstimateFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EstimateFile {
+    estimator: String,
+    round: u32,
+    optimistic: f64,
+    probable: f64,
+    pessimistic: f64,
+    confidence: f64,
+}
+
+/// Content of a `pert-ID.toml` file. Values come before the activities, as TOML
+/// needs them before any table.
+#[derive(Serialize, Deserialize)]
+struct PertFile {
+    id: PertId,
+    name: String,
+    estimation_model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimation_lambda: Option<f64>,
+    /// Name of the aggregation of the estimates, left out for the mean.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aggregation: Option<String>,
+    unit: String,
+    #[serde(default = "default_hours_per_day")]
+    hours_per_day: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    #[serde(default = "default_working_days_name")]
+    working_days: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    boards: Vec<BoardId>,
+    #[serde(default)]
+    activities: Vec<ActivityFile>,
+}
+
+/// Content of a `baselines-ID.toml` file, kept apart from the PERT so that its file
+/// stays short.
+#[derive(Default, Serialize, Deserialize)]
+struct BaselinesFile {
+    #[serde(default)]
+    baselines: Vec<Baseline>,
+}
+
+/// Highest ids given so far, saved in `ids.toml` when PERTs or activities are deleted
+/// so that their ids are not given again. Adding only writes the file of the PERT,
+/// new ids being above these and above the ids of the files.
+#[derive(Default, Serialize, Deserialize)]
+struct IdsFile {
+    #[serde(default)]
+    last_pert_id: PertId,
+    #[serde(default)]
+    last_activity_id: ActivityId,
+}
+
+fn default_hours_per_day() -> f64 {
+    DEFAULT_HOURS_PER_DAY
+}
+
+fn default_working_days_name() -> String {
+    weekdays_name(&default_working_days())
+}
+
+impl PertFile {
+    fn to_pert(&self) -> Result<Pert> {
+        let mut pert = Pert::new(self.id, self.name.to_owned());
+        pert.estimation_model =
+            EstimationModel::from_name(&self.estimation_model, self.estimation_lambda)?;
+        pert.aggregation = match &self.aggregation {
+            Some(aggregation) => Aggregation::from_name(aggregation)?,
+            None => Aggregation::default(),
+        };
+        pert.unit = DurationUnit::from_name(&self.unit)?;
+        pert.hours_per_day = self.hours_per_day;
+        pert.start_date = match &self.start_date {
+            Some(date) => Some(
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid start date {}", date))?,
+            ),
+            None => None,
+        };
+        pert.working_days = parse_weekdays(&self.working_days)?;
+        Ok(pert)
+    }
+
+    fn activity_mut(&mut self, activity_id: ActivityId) -> Option<&mut ActivityFile> {
+        self.activities.iter_mut().find(|act| act.id == activity_id)
+    }
+
+    fn key(&self, activity_id: ActivityId) -> Option<String> {
+        self.activities
+            .iter()
+            .find(|act| act.id == activity_id)
+            .map(|act| act.key.to_owned())
+    }
+
+    /// Key for a new activity: its name in lower case with dashes, followed by a number
+    /// when another activity of the PERT already has it.
+    fn new_key(&self, name: &str) -> String {
+        let words: Vec<String> = name
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect();
+        let slug = if words.is_empty() {
+            "activity".to_string()
+        } else {
+            words.join("-")
+        };
+        let is_free = |key: &str| self.activities.iter().all(|act| act.key != key);
+        if is_free(&slug) {
+            return slug;
+        }
+        (2..)
+            .map(|count| format!("{}-{}", slug, count))
+            .find(|key| is_free(key))
+            .unwrap()
+    }
+}
+
+fn last_activity_id(perts: &[PertFile]) -> ActivityId {
+    perts
+        .iter()
+        .flat_map(|pert| pert.activities.iter().map(|act| act.id))
+        .max()
+        .unwrap_or(0)
+}
+
+/// One human-readable TOML file per PERT in a folder, meant to be versioned with git.
+/// Activities are sorted by id and dependencies are listed by the key of the head, so
+/// saving the same data always gives the same file and a renumbered activity keeps
+/// its dependencies.
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Unable to create folder {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, pert_id: PertId) -> PathBuf {
+        self.dir
+            .join(format!("{}{}.{}", FILE_PREFIX, pert_id, FILE_EXTENSION))
+    }
+
+    fn baselines_path(&self, pert_id: PertId) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}.{}",
+            BASELINES_FILE_PREFIX, pert_id, FILE_EXTENSION
+        ))
+    }
+
+    fn ids_path(&self) -> PathBuf {
+        self.dir.join(IDS_FILE)
+    }
+
+    /// Id of the PERT of a `pert-ID.toml` file, `None` for the other files.
+    fn pert_file_id(path: &Path) -> Option<&str> {
+        if path.extension().and_then(|ext| ext.to_str()) != Some(FILE_EXTENSION) {
+            return None;
+        }
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix(FILE_PREFIX))
+    }
+
+    fn read(&self, path: &Path) -> Result<PertFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid PERT file {}", path.display()))
+    }
+
+    fn load(&self, pert_id: PertId) -> Result<Option<PertFile>> {
+        Ok(self.load_all()?.into_iter().find(|pert| pert.id == pert_id))
+    }
+
+    /// Every PERT of the folder, sorted by id, with the activity ids repaired in memory
+    /// only: reading never changes the files.
+    fn load_all(&self) -> Result<Vec<PertFile>> {
+        Ok(self.read_all()?.0)
+    }
+
+    /// Same as `load_all`, saving the repaired ids first. Called by every change, so the
+    /// ids given by the reads before are the ones kept.
+    fn load_all_for_update(&self) -> Result<Vec<PertFile>> {
+        let (mut perts, repairs) = self.read_all()?;
+        for repair in &repairs {
+            let pert = perts
+                .iter_mut()
+                .find(|pert| pert.id == repair.pert_id)
+                .unwrap();
+            self.save(pert)?;
+            if !repair.renumbered.is_empty() && self.baselines_path(pert.id).is_file() {
+                let mut file = self.load_baselines(pert.id)?;
+                renumber_baselines(&mut file, &repair.renumbered);
+                write(&self.baselines_path(pert.id), toml::to_string(&file)?)?;
+            }
+        }
+        Ok(perts)
+    }
+
+    /// Reads every PERT file and gives new ids to duplicated activity ids. Activity ids
+    /// are global: when a merge gave the same id to two activities, the one of the PERT
+    /// with the highest id, or the last one of its file, gets a new id.
+    fn read_all(&self) -> Result<(Vec<PertFile>, Vec<Repair>)> {
+        let entries = std::fs::read_dir(&self.dir)
+            .with_context(|| format!("Unable to read folder {}", self.dir.display()))?;
+        let mut perts = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if let Some(file_id) = Self::pert_file_id(&path) {
+                let pert = self.read(&path)?;
+                if file_id != pert.id.to_string() {
+                    bail!("File {} holds PERT {}", path.display(), pert.id);
+                }
+                perts.push(pert);
+            }
+        }
+        perts.sort_by_key(|pert| pert.id);
+
+        let mut next_id = self.next_activity_id(&perts)?;
+        let mut owners: HashMap<ActivityId, PertId> = HashMap::new();
+        let mut repairs = vec![];
+        for pert in &mut perts {
+            let mut keys = HashSet::new();
+            let mut repair = Repair {
+                pert_id: pert.id,
+                renumbered: vec![],
+            };
+            let mut changed = false;
+            for act in &mut pert.activities {
+                if !keys.insert(act.key.to_owned()) {
+                    bail!("Activity key {} is used twice in PERT {}", act.key, pert.id);
+                }
+                if let Some(owner) = owners.insert(act.id, pert.id) {
+                    // the baselines of a PERT only name its own activities, those of
+                    // two activities of the same PERT cannot be told apart
+                    if owner != pert.id {
+                        repair.renumbered.push((act.id, next_id));
+                    }
+                    act.id = next_id;
+                    owners.insert(next_id, pert.id);
+                    next_id += 1;
+                    changed = true;
+                }
+            }
+            if changed {
+                repairs.push(repair);
+            }
+        }
+        Ok((perts, repairs))
+    }
+
+    /// Baselines of the PERT with the ids repaired as `read_all` does.
+    fn load_repaired_baselines(&self, pert_id: PertId) -> Result<BaselinesFile> {
+        let mut file = self.load_baselines(pert_id)?;
+        if let Some(repair) = self
+            .read_all()?
+            .1
+            .into_iter()
+            .find(|repair| repair.pert_id == pert_id)
+        {
+            renumber_baselines(&mut file, &repair.renumbered);
+        }
+        Ok(file)
+    }
+
+    fn save(&self, pert: &mut PertFile) -> Result<()> {
+        pert.activities.sort_by_key(|act| act.id);
+        for act in &mut pert.activities {
+            act.depends_on.sort_unstable();
+        }
+        pert.boards.sort_unstable();
+        let content = toml::to_string(pert)?;
+        write(&self.path(pert.id), content)
+    }
+
+    fn load_ids(&self) -> Result<IdsFile> {
+        let path = self.ids_path();
+        if !path.is_file() {
+            return Ok(IdsFile::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid ids file {}", path.display()))
+    }
+
+    /// Keeps the ids of `perts` and of their activities from being given again, before
+    /// some of them are deleted.
+    fn keep_ids(&self, perts: &[PertFile]) -> Result<()> {
+        let mut ids = self.load_ids()?;
+        let last_pert_id = perts.iter().map(|pert| pert.id).max().unwrap_or(0);
+        let last_activity_id = last_activity_id(perts);
+        if last_pert_id <= ids.last_pert_id && last_activity_id <= ids.last_activity_id {
+            return Ok(());
+        }
+        ids.last_pert_id = ids.last_pert_id.max(last_pert_id);
+        ids.last_activity_id = ids.last_activity_id.max(last_activity_id);
+        write(&self.ids_path(), toml::to_string(&ids)?)
+    }
+
+    fn next_pert_id(&self, perts: &[PertFile]) -> Result<PertId> {
+        let last = perts.iter().map(|pert| pert.id).max().unwrap_or(0);
+        Ok(self.load_ids()?.last_pert_id.max(last) + 1)
+    }
+
+    fn next_activity_id(&self, perts: &[PertFile]) -> Result<ActivityId> {
+        Ok(self
+            .load_ids()?
+            .last_activity_id
+            .max(last_activity_id(perts))
+            + 1)
+    }
+
+    fn load_baselines(&self, pert_id: PertId) -> Result<BaselinesFile> {
+        let path = self.baselines_path(pert_id);
+        if !path.is_file() {
+            return Ok(BaselinesFile::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid baselines file {}", path.display()))
+    }
+
+    fn update(&self, pert_id: PertId, change: impl FnOnce(&mut PertFile)) -> Result<()> {
+        if let Some(mut pert) = self.load_for_update(pert_id)? {
+            change(&mut pert);
+            self.save(&mut pert)?;
+        }
+        Ok(())
+    }
+
+    fn load_for_update(&self, pert_id: PertId) -> Result<Option<PertFile>> {
+        Ok(self
+            .load_all_for_update()?
+            .into_iter()
+            .find(|pert| pert.id == pert_id))
+    }
+
+    /// PERT of the activity, read to be changed.
+    fn find_activity_pert(&self, activity_id: ActivityId) -> Result<Option<PertFile>> {
+        Ok(find_activity_pert(self.load_all_for_update()?, activity_id))
+    }
+}
+
+/// Activities renumbered in a PERT by `read_all`, as `(old, new)` ids for its baselines.
+struct Repair {
+    pert_id: PertId,
+    renumbered: Vec<(ActivityId, ActivityId)>,
+}
+
+fn find_activity_pert(perts: Vec<PertFile>, activity_id: ActivityId) -> Option<PertFile> {
+    perts
+        .into_iter()
+        .find(|pert| pert.activities.iter().any(|act| act.id == activity_id))
+}
+
+/// Gives the new ids of renumbered activities, as `(old, new)`, to the baselines of
+/// their PERT.
+fn renumber_baselines(file: &mut BaselinesFile, renumbered: &[(ActivityId, ActivityId)]) {
+    let renumber = |id: &mut ActivityId| {
+        if let Some((_, new)) = renumbered.iter().find(|(old, _)| old == id) {
+            *id = *new;
+        }
+    };
+    for baseline in &mut file.baselines {
+        let snapshot = &mut baseline.snapshot;
+        snapshot.critical_path.iter_mut().for_each(renumber);
+        snapshot
+            .critical_chains
+            .iter_mut()
+            .for_each(|chain| chain.iter_mut().for_each(renumber));
+        for act in &mut snapshot.activities {
+            renumber(&mut act.id);
+            act.depends_on.iter_mut().for_each(renumber);
+            act.depends_on.sort_unstable();
+        }
+        snapshot.activities.sort_by_key(|act| act.id);
+    }
+}
+
+/// Written next to the file, then renamed, to never leave a half written file.
+fn write(path: &Path, content: String) -> Result<()> {
+    let temp_path = path.with_extension("toml.tmp");
+    std::fs::write(&temp_path, content)
+        .with_context(|| format!("Unable to write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path)
+        .with_context(|| format!("Unable to write {}", path.display()))?;
+    Ok(())
+}
+
+impl Storage for FileStorage {
+    fn add_pert(
+        &mut self,
+        name: &str,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId> {
+        let id = self.next_pert_id(&self.load_all_for_update()?)?;
+        let mut pert = PertFile {
+            id,
+            name: name.to_string(),
+            estimation_model: estimation_model.name().to_string(),
+            estimation_lambda: estimation_model.lambda(),
+            aggregation: None,
+            unit: unit.name().to_string(),
+            hours_per_day: DEFAULT_HOURS_PER_DAY,
+            start_date: None,
+            working_days: default_working_days_name(),
+            boards: vec![],
+            activities: vec![],
+        };
+        self.save(&mut pert)?;
+        Ok(id)
+    }
+
+    fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>> {
+        self.load(pert_id)?.map(|pert| pert.to_pert()).transpose()
+    }
+
+    fn get_perts(&mut self) -> Result<Vec<Pert>> {
+        self.load_all()?.iter().map(PertFile::to_pert).collect()
+    }
+
+    fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        self.update(pert_id, |pert| pert.name = name.to_string())
+    }
+
+    fn delete_pert(&mut self, pert_id: PertId) -> Result<()> {
+        let perts = self.load_all_for_update()?;
+        if !perts.iter().any(|pert| pert.id == pert_id) {
+            return Ok(());
+        }
+        self.keep_ids(&perts)?;
+        for path in [self.baselines_path(pert_id), self.path(pert_id)] {
+            if path.is_file() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Unable to delete {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()> {
+        self.update(pert_id, |pert| {
+            pert.estimation_model = estimation_model.name().to_string();
+            pert.estimation_lambda = estimation_model.lambda();
+        })
+    }
+
+    fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+        self.update(pert_id, |pert| {
+            pert.aggregation = Some(aggregation)
+                .filter(|aggregation| *aggregation != Aggregation::default())
+                .map(|aggregation| aggregation.name().to_string())
+        })
+    }
+
+    fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+        self.update(pert_id, |pert| pert.hours_per_day = hours_per_day)
+    }
+
+    fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()> {
+        self.update(pert_id, |pert| {
+            pert.start_date = start_date.map(|date| date.to_string())
+        })
+    }
+
+    fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()> {
+        self.update(pert_id, |pert| {
+            pert.working_days = weekdays_name(working_days)
+        })
+    }
+
+    fn add_activity(
+        &mut self,
+        pert_id: PertId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        let perts = self.load_all_for_update()?;
+        let id = self.next_activity_id(&perts)?;
+        let mut pert = match perts.into_iter().find(|pert| pert.id == pert_id) {
+            Some(pert) => pert,
+            None => bail!("PERT {} not found", pert_id),
+        };
+        let key = pert.new_key(&name);
+        pert.activities.push(ActivityFile {
+            id,
+            key,
+            name,
+            optimistic: estimation.optimistic,
+            probable: estimation.probable,
+            pessimistic: estimation.pessimistic,
+            depends_on: vec![],
+            actual_start: None,
+            actual_finish: None,
+            actual_effort: None,
+            status: None,
+            percent_complete: None,
+            tags: vec![],
+            estimates: vec![],
+        });
+        self.save(&mut pert)
+    }
+
+    fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>> {
+        let pert = match self.load(pert_id)? {
+            Some(pert) => pert,
+            None => return Ok(vec![]),
+        };
+        let model = pert.to_pert()?.estimation_model;
+        pert.activities
+            .into_iter()
+            .map(|act| {
+                let mut activity = Activity::from_storage(
+                    act.id,
+                    act.name,
+                    Estimation {
+                        optimistic: act.optimistic,
+                        probable: act.probable,
+                        pessimistic: act.pessimistic,
+                    },
+                );
+                activity.model = model;
+                activity.actual = Actual {
+                    start: act.actual_start,
+                    finish: act.actual_finish,
+                    effort: act.actual_effort,
+                };
+                activity.progress = Progress {
+                    status: match &act.status {
+                        Some(status) => Status::from_name(status)?,
+                        None => Status::NotStarted,
+                    },
+                    percent_complete: act.percent_complete.unwrap_or(0f64),
+                };
+                activity.tags = act.tags;
+                Ok(activity)
+            })
+            .collect()
+    }
+
+    fn get_activity_pert_id(&mut self, activity_id: ActivityId) -> Result<Option<PertId>> {
+        Ok(find_activity_pert(self.load_all()?, activity_id).map(|pert| pert.id))
+    }
+
+    fn update_activity(
+        &mut self,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        if let Some(mut pert) = self.find_activity_pert(activity_id)? {
+            let activity = pert.activity_mut(activity_id).unwrap();
+            activity.name = name;
+            activity.optimistic = estimation.optimistic;
+            activity.probable = estimation.probable;
+            activity.pessimistic = estimation.pessimistic;
+            self.save(&mut pert)?;
+        }
+        Ok(())
+    }
+
+    fn set_actual(&mut self, activity_id: ActivityId, actual: &Actual) -> Result<()> {
+        actual.validate()?;
+        if let Some(mut pert) = self.find_activity_pert(activity_id)? {
+            let activity = pert.activity_mut(activity_id).unwrap();
+            activity.actual_start = actual.start;
+            activity.actual_finish = actual.finish;
+            activity.actual_effort = actual.effort;
+            self.save(&mut pert)?;
+        }
+        Ok(())
+    }
+
+    fn set_progress(&mut self, activity_id: ActivityId, progress: &Progress) -> Result<()> {
+        progress.validate()?;
+        if let Some(mut pert) = self.find_activity_pert(activity_id)? {
+            let activity = pert.activity_mut(activity_id).unwrap();
+            activity.status = Some(progress.status)
+                .filter(|status| *status != Status::NotStarted)
+                .map(|status| status.name().to_string());
+            activity.percent_complete = Some(progress.percent_complete).filter(|pct| *pct != 0f64);
+            self.save(&mut pert)?;
+        }
+        Ok(())
+    }
+
+    fn set_tags(&mut self, activity_id: ActivityId, tags: &[String]) -> Result<()> {
+        validate_tags(tags)?;
+        if let Some(mut pert) = self.find_activity_pert(activity_id)? {
+            pert.activity_mut(activity_id).unwrap().tags = tags.to_vec();
+            self.save(&mut pert)?;
+        }
+        Ok(())
+    }
+
+    fn add_estimate(
+        &mut self,
+        activity_id: ActivityId,
+        estimate: &EstimatorEstimate,
+    ) -> Result<()> {
+        estimate.validate()?;
+        let mut pert = match self.find_activity_pert(activity_id)? {
+            Some(pert) => pert,
+            None => bail!("Activity {} not found", activity_id),
+        };
+        let activity = pert.activity_mut(activity_id).unwrap();
+        if activity
+            .estimates
+            .iter()
+            .any(|other| other.estimator == estimate.estimator && other.round == estimate.round)
+        {
+            bail!(
+                "{} already estimated activity {} in round {}",
+                estimate.estimator,
+                activity_id,
+                estimate.round
+            );
+        }
+        activity.estimates.push(EstimateFile {
+            estimator: estimate.estimator.to_owned(),
+            round: estimate.round,
+            optimistic: estimate.estimation.optimistic,
+            probable: estimate.estimation.probable,
+            pessimistic: estimate.estimation.pessimistic,
+            confidence: estimate.confidence,
+        });
+        self.save(&mut pert)
+    }
+
+    fn get_estimates(&mut self, pert_id: PertId) -> Result<Vec<(ActivityId, EstimatorEstimate)>> {
+        let pert = match self.load(pert_id)? {
+            Some(pert) => pert,
+            None => return Ok(vec![]),
+        };
+        Ok(pert
+            .activities
+            .into_iter()
+            .flat_map(|act| {
+                act.estimates.into_iter().map(move |estimate| {
+                    (
+                        act.id,
+                        EstimatorEstimate {
+                            estimator: estimate.estimator,
+                            round: estimate.round,
+                            estimation: Estimation {
+                                optimistic: estimate.optimistic,
+                                probable: estimate.probable,
+                                pessimistic: estimate.pessimistic,
+                            },
+                            confidence: estimate.confidence,
+                        },
+                    )
+                })
+            })
+            .collect())
+    }
+
+    fn delete_activity(&mut self, activity_id: ActivityId) -> Result<()> {
+        let perts = self.load_all_for_update()?;
+        self.keep_ids(&perts)?;
+        let mut pert = match perts
+            .into_iter()
+            .find(|pert| pert.activities.iter().any(|act| act.id == activity_id))
+        {
+            Some(pert) => pert,
+            None => return Ok(()),
+        };
+        let key = pert.key(activity_id).unwrap();
+        pert.activities.retain(|act| act.id != activity_id);
+        for act in &mut pert.activities {
+            act.depends_on.retain(|head| *head != key);
+        }
+        self.save(&mut pert)
+    }
+
+    fn delete_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        if let Some(mut pert) = self.find_activity_pert(tail)? {
+            let head = match pert.key(head) {
+                Some(head) => head,
+                None => return Ok(()),
+            };
+            let activity = pert.activity_mut(tail).unwrap();
+            if activity.depends_on.contains(&head) {
+                activity.depends_on.retain(|key| *key != head);
+                self.save(&mut pert)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        let head_pert = self.find_activity_pert(head)?;
+        let mut pert = match (head_pert, self.find_activity_pert(tail)?) {
+            (None, _) => bail!("Activity {} not found", head),
+            (_, None) => bail!("Activity {} not found", tail),
+            (Some(head_pert), Some(pert)) if head_pert.id != pert.id => {
+                bail!("Activities {} and {} belong to different PERTs", head, tail)
+            }
+            (_, Some(pert)) => pert,
+        };
+        let head_key = pert.key(head).unwrap();
+        let activity = pert.activity_mut(tail).unwrap();
+        if activity.depends_on.contains(&head_key) {
+            bail!("Activity {} already depends on {}", tail, head);
+        }
+        activity.depends_on.push(head_key);
+        self.save(&mut pert)
+    }
+
+    fn get_activities_with_related_dependencies(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Vec<ActivityWithRelatedDependencies>> {
+        let pert = match self.load(pert_id)? {
+            Some(pert) => pert,
+            None => return Ok(vec![]),
+        };
+        let ids: HashMap<&str, ActivityId> = pert
+            .activities
+            .iter()
+            .map(|act| (act.key.as_str(), act.id))
+            .collect();
+        let mut acts = vec![];
+        for act in &pert.activities {
+            let heads = if act.depends_on.is_empty() {
+                vec![None]
+            } else {
+                act.depends_on
+                    .iter()
+                    .map(|key| match ids.get(key.as_str()) {
+                        Some(id) => Ok(Some(*id)),
+                        None => bail!("Activity {} depends on unknown activity {}", act.key, key),
+                    })
+                    .collect::<Result<_>>()?
+            };
+            acts.extend(heads.into_iter().map(|activity_id_head| {
+                ActivityWithRelatedDependencies {
+                    activity_id: act.id,
+                    head_name: act.name.to_owned(),
+                    activity_id_head,
+                }
+            }));
+        }
+        Ok(acts)
+    }
+
+    fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()> {
+        let mut pert = match self.load_for_update(pert_id)? {
+            Some(pert) => pert,
+            None => bail!("PERT {} not found", pert_id),
+        };
+        if pert.boards.contains(&github_board_id) {
+            bail!(
+                "Board {} already created for PERT {}",
+                github_board_id,
+                pert_id
+            );
+        }
+        pert.boards.push(github_board_id);
+        self.save(&mut pert)
+    }
+
+    fn add_baseline(&mut self, pert_id: PertId, baseline: &Baseline) -> Result<()> {
+        if self.load_for_update(pert_id)?.is_none() {
+            bail!("PERT {} not found", pert_id);
+        }
+        let mut file = self.load_baselines(pert_id)?;
+        if file.baselines.iter().any(|b| b.name == baseline.name) {
+            bail!(
+                "Baseline {} already created for PERT {}",
+                baseline.name,
+                pert_id
+            );
+        }
+        file.baselines.push(baseline.clone());
+        write(&self.baselines_path(pert_id), toml::to_string(&file)?)
+    }
+
+    fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>> {
+        Ok(self.load_repaired_baselines(pert_id)?.baselines)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FileStorage;
+    use crate::{
+        modules::{
+            activity::Estimation,
+            baseline::{Baseline, Snapshot},
+            duration::DurationUnit,
+            estimation_model::EstimationModel,
+            storage::Storage,
+        },
+        storage_conformance_tests,
+    };
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    /// The folder is removed when the `TempDir` is dropped.
+    fn temp_storage() -> (FileStorage, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        (FileStorage::open(dir.path()).unwrap(), dir)
+    }
+
+    storage_conformance_tests!(Some(temp_storage()));
+
+    #[test]
+    fn readable_file() {
+        let (mut storage, _dir) = temp_storage();
+        let model = EstimationModel::ModifiedPert { lambda: 3.0 };
+        let pert_id = storage
+            .add_pert("example", model, DurationUnit::Days)
+            .unwrap();
+        storage
+            .set_start_date(pert_id, NaiveDate::from_ymd_opt(2022, 6, 1))
+            .unwrap();
+        for name in ["Design", "Build & deploy", "design"] {
+            let estimation = Estimation::new(1.0, 2.0, 4.0).unwrap();
+            storage
+                .add_activity(pert_id, name.to_string(), estimation)
+                .unwrap();
+        }
+        storage.add_dependency(2, 3).unwrap();
+        storage.add_dependency(1, 3).unwrap();
+        storage.add_dependency(1, 2).unwrap();
+
+        let content = std::fs::read_to_string(storage.path(pert_id)).unwrap();
+        insta::assert_display_snapshot!(content);
+    }
+
+    #[test]
+    fn duplicated_activity_ids_are_renumbered() {
+        let (mut storage, _dir) = temp_storage();
+        for name in ["first", "second"] {
+            let pert_id = storage
+                .add_pert(name, EstimationModel::Classic, DurationUnit::Days)
+                .unwrap();
+            for activity in ["design", "build"] {
+                let estimation = Estimation::new(1.0, 1.0, 1.0).unwrap();
+                storage
+                    .add_activity(pert_id, activity.to_string(), estimation)
+                    .unwrap();
+            }
+        }
+        storage.add_dependency(3, 4).unwrap();
+        let snapshot = {
+            let pert = storage.get_pert(2).unwrap().unwrap();
+            let activities = storage.get_activities(2).unwrap();
+            let acts_with_deps = storage.get_activities_with_related_dependencies(2).unwrap();
+            Snapshot::new(&pert, &activities, &acts_with_deps).unwrap()
+        };
+        let baseline = Baseline {
+            name: "v1".to_string(),
+            created_at: NaiveDate::from_ymd_opt(2022, 6, 1)
+                .unwrap()
+                .and_hms_opt(10, 30, 0)
+                .unwrap(),
+            snapshot,
+        };
+        storage.add_baseline(2, &baseline).unwrap();
+        // as after merging two branches that both added activities 1 and 2
+        let content = std::fs::read_to_string(storage.path(2)).unwrap();
+        let content = content
+            .replace("id = 3\n", "id = 1\n")
+            .replace("id = 4\n", "id = 2\n");
+        std::fs::write(storage.path(2), content).unwrap();
+        let content = std::fs::read_to_string(storage.baselines_path(2)).unwrap();
+        let content = content
+            .replace("id = 3\n", "id = 1\n")
+            .replace("id = 4\n", "id = 2\n")
+            .replace("[3]", "[1]")
+            .replace("[3, 4]", "[1, 2]");
+        std::fs::write(storage.baselines_path(2), content).unwrap();
+        let merged = std::fs::read_to_string(storage.path(2)).unwrap();
+        let merged_baselines = std::fs::read_to_string(storage.baselines_path(2)).unwrap();
+
+        let ids: Vec<_> = storage
+            .get_activities(2)
+            .unwrap()
+            .iter()
+            .map(|act| act.id)
+            .collect();
+        assert_eq!(ids, vec![3, 4]);
+        let deps: Vec<_> = storage
+            .get_activities_with_related_dependencies(2)
+            .unwrap()
+            .iter()
+            .map(|dep| (dep.activity_id_head, dep.activity_id))
+            .collect();
+        assert_eq!(deps, vec![(None, 3), (Some(3), 4)]);
+        let baselines = storage.get_baselines(2).unwrap();
+        assert_eq!(baselines[0].snapshot, baseline.snapshot);
+        // the first PERT keeps its ids
+        assert_eq!(storage.get_activity_pert_id(1).unwrap(), Some(1));
+        // reading leaves the files as merged
+        assert_eq!(std::fs::read_to_string(storage.path(2)).unwrap(), merged);
+        assert_eq!(
+            std::fs::read_to_string(storage.baselines_path(2)).unwrap(),
+            merged_baselines
+        );
+
+        // the next change saves the ids given by the reads
+        storage.rename_pert(1, "renamed").unwrap();
+        assert_ne!(std::fs::read_to_string(storage.path(2)).unwrap(), merged);
+        let ids: Vec<_> = storage
+            .get_activities(2)
+            .unwrap()
+            .iter()
+            .map(|act| act.id)
+            .collect();
+        assert_eq!(ids, vec![3, 4]);
+        assert_eq!(
+            storage.get_baselines(2).unwrap()[0].snapshot,
+            baseline.snapshot
+        );
+        let content = std::fs::read_to_string(storage.baselines_path(2)).unwrap();
+        assert!(content.contains("id = 3\n") && !content.contains("id = 1\n"));
+    }
+
+    #[test]
+    fn pert_id_must_match_the_file_name() {
+        let (mut storage, _dir) = temp_storage();
+        let pert_id = storage
+            .add_pert("example", EstimationModel::Classic, DurationUnit::Days)
+            .unwrap();
+        std::fs::rename(storage.path(pert_id), storage.path(pert_id + 1)).unwrap();
+
+        let error = storage.get_perts().unwrap_err();
+        assert!(error.to_string().contains("holds PERT 1"));
+    }
+
+    #[test]
+    fn duplicated_activity_keys_are_reported() {
+        let (mut storage, _dir) = temp_storage();
+        let pert_id = storage
+            .add_pert("example", EstimationModel::Classic, DurationUnit::Days)
+            .unwrap();
+        for name in ["design", "build"] {
+            let estimation = Estimation::new(1.0, 1.0, 1.0).unwrap();
+            storage
+                .add_activity(pert_id, name.to_string(), estimation)
+                .unwrap();
+        }
+        let content = std::fs::read_to_string(storage.path(pert_id)).unwrap();
+        let content = content.replace("key = \"build\"", "key = \"design\"");
+        std::fs::write(storage.path(pert_id), content).unwrap();
+
+        assert!(storage.get_perts().is_err());
+    }
+}
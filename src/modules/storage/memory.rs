@@ -0,0 +1,374 @@
+use anyhow::{bail, Result};
+use chrono::{NaiveDate, Weekday};
+
+use super::Storage;
+use crate::modules::{
+    activity::{validate_tags, Activity, ActivityId, Actual, Estimation, Progress},
+    baseline::Baseline,
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::DurationUnit,
+    estimation_model::EstimationModel,
+    github::BoardId,
+    pert::{Pert, PertId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+struct StoredActivity {
+    id: ActivityId,
+    pert_id: PertId,
+    name: String,
+    estimation: Estimation,
+    actual: Actual,
+    progress: Progress,
+    tags: Vec<String>,
+    /// Every round of every estimator, in the order they were given.
+    estimates: Vec<EstimatorEstimate>,
+}
+
+/// Storage kept in memory, lost when dropped. Ids are generated like the
+/// database sequences: increasing and never reused.
+#[derive(Default)]
+pub struct MemoryStorage {
+    perts: Vec<Pert>,
+    activities: Vec<StoredActivity>,
+    /// (head, tail)
+    dependencies: Vec<(ActivityId, ActivityId)>,
+    boards: Vec<(PertId, BoardId)>,
+    baselines: Vec<(PertId, Baseline)>,
+    last_pert_id: PertId,
+    last_activity_id: ActivityId,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn pert_mut(&mut self, pert_id: PertId) -> Option<&mut Pert> {
+        self.perts.iter_mut().find(|pert| pert.id == pert_id)
+    }
+
+    fn activity(&self, activity_id: ActivityId) -> Option<&StoredActivity> {
+        self.activities.iter().find(|act| act.id == activity_id)
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn add_pert(
+        &mut self,
+        name: &str,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId> {
+        self.last_pert_id += 1;
+        let mut pert = Pert::new(self.last_pert_id, name.to_string());
+        pert.estimation_model = estimation_model;
+        pert.unit = unit;
+        self.perts.push(pert);
+        Ok(self.last_pert_id)
+    }
+
+    fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>> {
+        Ok(self.perts.iter().find(|pert| pert.id == pert_id).cloned())
+    }
+
+    fn get_perts(&mut self) -> Result<Vec<Pert>> {
+        Ok(self.perts.clone())
+    }
+
+    fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.name = name.to_string();
+        }
+        Ok(())
+    }
+
+    fn delete_pert(&mut self, pert_id: PertId) -> Result<()> {
+        let activity_ids: Vec<ActivityId> = self
+            .activities
+            .iter()
+            .filter(|act| act.pert_id == pert_id)
+            .map(|act| act.id)
+            .collect();
+        for activity_id in activity_ids {
+            self.delete_activity(activity_id)?;
+        }
+        self.boards
+            .retain(|(board_pert_id, _)| *board_pert_id != pert_id);
+        self.baselines
+            .retain(|(baseline_pert_id, _)| *baseline_pert_id != pert_id);
+        self.perts.retain(|pert| pert.id != pert_id);
+        Ok(())
+    }
+
+    fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.estimation_model = estimation_model;
+        }
+        Ok(())
+    }
+
+    fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.aggregation = aggregation;
+        }
+        Ok(())
+    }
+
+    fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.hours_per_day = hours_per_day;
+        }
+        Ok(())
+    }
+
+    fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.start_date = start_date;
+        }
+        Ok(())
+    }
+
+    fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()> {
+        if let Some(pert) = self.pert_mut(pert_id) {
+            pert.working_days = working_days.to_vec();
+        }
+        Ok(())
+    }
+
+    fn add_activity(
+        &mut self,
+        pert_id: PertId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        if self.pert_mut(pert_id).is_none() {
+            bail!("PERT {} not found", pert_id);
+        }
+        self.last_activity_id += 1;
+        self.activities.push(StoredActivity {
+            id: self.last_activity_id,
+            pert_id,
+            name,
+            estimation,
+            actual: Actual::default(),
+            progress: Progress::default(),
+            tags: vec![],
+            estimates: vec![],
+        });
+        Ok(())
+    }
+
+    fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>> {
+        let model = match self.perts.iter().find(|pert| pert.id == pert_id) {
+            Some(pert) => pert.estimation_model,
+            None => return Ok(vec![]),
+        };
+        Ok(self
+            .activities
+            .iter()
+            .filter(|act| act.pert_id == pert_id)
+            .map(|act| {
+                let mut activity =
+                    Activity::from_storage(act.id, act.name.to_owned(), act.estimation.clone());
+                activity.model = model;
+                activity.actual = act.actual.clone();
+                activity.progress = act.progress;
+                activity.tags = act.tags.clone();
+                activity
+            })
+            .collect())
+    }
+
+    fn get_activity_pert_id(&mut self, activity_id: ActivityId) -> Result<Option<PertId>> {
+        Ok(self.activity(activity_id).map(|act| act.pert_id))
+    }
+
+    fn update_activity(
+        &mut self,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        if let Some(act) = self.activities.iter_mut().find(|act| act.id == activity_id) {
+            act.name = name;
+            act.estimation = estimation;
+        }
+        Ok(())
+    }
+
+    fn set_actual(&mut self, activity_id: ActivityId, actual: &Actual) -> Result<()> {
+        actual.validate()?;
+        if let Some(act) = self.activities.iter_mut().find(|act| act.id == activity_id) {
+            act.actual = actual.clone();
+        }
+        Ok(())
+    }
+
+    fn set_progress(&mut self, activity_id: ActivityId, progress: &Progress) -> Result<()> {
+        progress.validate()?;
+        if let Some(act) = self.activities.iter_mut().find(|act| act.id == activity_id) {
+            act.progress = *progress;
+        }
+        Ok(())
+    }
+
+    fn set_tags(&mut self, activity_id: ActivityId, tags: &[String]) -> Result<()> {
+        validate_tags(tags)?;
+        if let Some(act) = self.activities.iter_mut().find(|act| act.id == activity_id) {
+            act.tags = tags.to_vec();
+        }
+        Ok(())
+    }
+
+    fn add_estimate(
+        &mut self,
+        activity_id: ActivityId,
+        estimate: &EstimatorEstimate,
+    ) -> Result<()> {
+        estimate.validate()?;
+        let act = match self.activities.iter_mut().find(|act| act.id == activity_id) {
+            Some(act) => act,
+            None => bail!("Activity {} not found", activity_id),
+        };
+        if act
+            .estimates
+            .iter()
+            .any(|other| other.estimator == estimate.estimator && other.round == estimate.round)
+        {
+            bail!(
+                "{} already estimated activity {} in round {}",
+                estimate.estimator,
+                activity_id,
+                estimate.round
+            );
+        }
+        act.estimates.push(estimate.clone());
+        Ok(())
+    }
+
+    fn get_estimates(&mut self, pert_id: PertId) -> Result<Vec<(ActivityId, EstimatorEstimate)>> {
+        Ok(self
+            .activities
+            .iter()
+            .filter(|act| act.pert_id == pert_id)
+            .flat_map(|act| {
+                act.estimates
+                    .iter()
+                    .map(|estimate| (act.id, estimate.clone()))
+            })
+            .collect())
+    }
+
+    fn delete_activity(&mut self, activity_id: ActivityId) -> Result<()> {
+        self.activities.retain(|act| act.id != activity_id);
+        self.dependencies
+            .retain(|(head, tail)| *head != activity_id && *tail != activity_id);
+        Ok(())
+    }
+
+    fn delete_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        self.dependencies
+            .retain(|dependency| *dependency != (head, tail));
+        Ok(())
+    }
+
+    fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        for activity_id in [head, tail] {
+            if self.activity(activity_id).is_none() {
+                bail!("Activity {} not found", activity_id);
+            }
+        }
+        if self.dependencies.contains(&(head, tail)) {
+            bail!("Activity {} already depends on {}", tail, head);
+        }
+        self.dependencies.push((head, tail));
+        Ok(())
+    }
+
+    fn get_activities_with_related_dependencies(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Vec<ActivityWithRelatedDependencies>> {
+        let mut acts = vec![];
+        for act in self.activities.iter().filter(|act| act.pert_id == pert_id) {
+            let mut heads: Vec<ActivityId> = self
+                .dependencies
+                .iter()
+                .filter(|(_, tail)| *tail == act.id)
+                .map(|(head, _)| *head)
+                .collect();
+            heads.sort_unstable();
+            let heads = if heads.is_empty() {
+                vec![None]
+            } else {
+                heads.into_iter().map(Some).collect()
+            };
+            acts.extend(heads.into_iter().map(|activity_id_head| {
+                ActivityWithRelatedDependencies {
+                    activity_id: act.id,
+                    head_name: act.name.to_owned(),
+                    activity_id_head,
+                }
+            }));
+        }
+        Ok(acts)
+    }
+
+    fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()> {
+        if self.pert_mut(pert_id).is_none() {
+            bail!("PERT {} not found", pert_id);
+        }
+        if self.boards.contains(&(pert_id, github_board_id)) {
+            bail!(
+                "Board {} already created for PERT {}",
+                github_board_id,
+                pert_id
+            );
+        }
+        self.boards.push((pert_id, github_board_id));
+        Ok(())
+    }
+
+    fn add_baseline(&mut self, pert_id: PertId, baseline: &Baseline) -> Result<()> {
+        if self.pert_mut(pert_id).is_none() {
+            bail!("PERT {} not found", pert_id);
+        }
+        if self
+            .get_baselines(pert_id)?
+            .iter()
+            .any(|b| b.name == baseline.name)
+        {
+            bail!(
+                "Baseline {} already created for PERT {}",
+                baseline.name,
+                pert_id
+            );
+        }
+        self.baselines.push((pert_id, baseline.clone()));
+        Ok(())
+    }
+
+    fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>> {
+        Ok(self
+            .baselines
+            .iter()
+            .filter(|(baseline_pert_id, _)| *baseline_pert_id == pert_id)
+            .map(|(_, baseline)| baseline.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MemoryStorage;
+    use crate::storage_conformance_tests;
+
+    storage_conformance_tests!(Some(MemoryStorage::new()));
+}
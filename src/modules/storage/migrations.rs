@@ -0,0 +1,154 @@
+use anyhow::{bail, Result};
+use std::fmt;
+
+/// Step of the schema of a database, applied once and recorded in `schema_migrations`.
+/// Migrations are never edited once released, a schema change is a new migration.
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}_{}", self.version, self.name)
+    }
+}
+
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("migrations/postgres/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "estimation_models_and_units",
+        sql: include_str!("migrations/postgres/0002_estimation_models_and_units.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "calendar",
+        sql: include_str!("migrations/postgres/0003_calendar.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "baselines",
+        sql: include_str!("migrations/postgres/0004_baselines.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "actuals",
+        sql: include_str!("migrations/postgres/0005_actuals.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "progress",
+        sql: include_str!("migrations/postgres/0006_progress.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "estimates",
+        sql: include_str!("migrations/postgres/0007_estimates.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "tags",
+        sql: include_str!("migrations/postgres/0008_tags.sql"),
+    },
+];
+
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("migrations/sqlite/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "baselines",
+        sql: include_str!("migrations/sqlite/0002_baselines.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "actuals",
+        sql: include_str!("migrations/sqlite/0003_actuals.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "progress",
+        sql: include_str!("migrations/sqlite/0004_progress.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "estimates",
+        sql: include_str!("migrations/sqlite/0005_estimates.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "tags",
+        sql: include_str!("migrations/sqlite/0006_tags.sql"),
+    },
+];
+
+pub const CREATE_MIGRATIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER NOT NULL PRIMARY KEY,
+    name VARCHAR NOT NULL
+);";
+
+/// Migrations not applied yet, in order. A database migrated by a newer version of
+/// Perty is refused, its schema is unknown.
+pub fn pending<'a>(migrations: &'a [Migration], applied: &[i32]) -> Result<Vec<&'a Migration>> {
+    let last = migrations.last().map_or(0, |migration| migration.version);
+    if let Some(version) = applied.iter().find(|version| **version > last) {
+        bail!(
+            "The database schema is at version {}, newer than this version of Perty ({})",
+            version,
+            last
+        );
+    }
+    Ok(migrations
+        .iter()
+        .filter(|migration| !applied.contains(&migration.version))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pending, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS};
+
+    #[test]
+    fn versions_follow_each_other() {
+        for migrations in [POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            for (index, migration) in migrations.iter().enumerate() {
+                assert_eq!(migration.version, index as i32 + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn pending_migrations() {
+        let names: Vec<String> = pending(POSTGRES_MIGRATIONS, &[1])
+            .unwrap()
+            .iter()
+            .map(|migration| migration.to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "0002_estimation_models_and_units",
+                "0003_calendar",
+                "0004_baselines",
+                "0005_actuals",
+                "0006_progress",
+                "0007_estimates",
+                "0008_tags"
+            ]
+        );
+        assert!(pending(POSTGRES_MIGRATIONS, &[1, 2, 3, 4, 5, 6, 7, 8])
+            .unwrap()
+            .is_empty());
+        assert!(pending(POSTGRES_MIGRATIONS, &[1, 2, 3, 4, 5, 6, 7, 8, 9]).is_err());
+    }
+}
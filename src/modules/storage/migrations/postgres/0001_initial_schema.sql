@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS public.pert (
+	"name" varchar NOT NULL,
+	id serial4 NOT NULL,
+	CONSTRAINT pert_pk PRIMARY KEY (id)
+);
+
+CREATE TABLE IF NOT EXISTS public.activities (
+	pert_id int4 NOT NULL,
+	pessimistic int4 NOT NULL,
+	probable int4 NOT NULL,
+	optimistic int4 NOT NULL,
+	id serial4 NOT NULL,
+	"name" varchar NOT NULL,
+	CONSTRAINT activities_pk PRIMARY KEY (id),
+	CONSTRAINT activities_fk FOREIGN KEY (pert_id) REFERENCES public.pert(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS public.activity_dependencies (
+	activity_id_head int4 NOT NULL,
+	activity_id_tail int4 NOT NULL,
+	CONSTRAINT activity_dependencies_pk PRIMARY KEY (activity_id_head, activity_id_tail),
+	CONSTRAINT activity_dependencies_fk FOREIGN KEY (activity_id_head) REFERENCES public.activities(id) ON DELETE CASCADE ON UPDATE CASCADE,
+	CONSTRAINT activity_dependencies_fk_1 FOREIGN KEY (activity_id_tail) REFERENCES public.activities(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS public.boards (
+	pert_id int4 NOT NULL,
+	github_board_id int4 NOT NULL,
+	CONSTRAINT boards_pk PRIMARY KEY (github_board_id, pert_id),
+	CONSTRAINT boards_fk FOREIGN KEY (pert_id) REFERENCES public.pert(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- databases created from the former README had defaults on sequences that are not needed
+ALTER TABLE public.activity_dependencies ALTER COLUMN activity_id_head DROP DEFAULT;
+ALTER TABLE public.activity_dependencies ALTER COLUMN activity_id_tail DROP DEFAULT;
@@ -0,0 +1,26 @@
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS estimation_model varchar NOT NULL DEFAULT 'classic';
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS estimation_lambda float8 NULL;
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS unit varchar NOT NULL DEFAULT 'days';
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS hours_per_day float8 NOT NULL DEFAULT 8;
+
+ALTER TABLE public.activities ALTER COLUMN pessimistic TYPE float8;
+ALTER TABLE public.activities ALTER COLUMN probable TYPE float8;
+ALTER TABLE public.activities ALTER COLUMN optimistic TYPE float8;
+
+-- the activities saved before estimations were validated are listed, the constraint
+-- cannot be added while one of them is invalid
+DO $$
+DECLARE
+	invalid text;
+BEGIN
+	IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'activities_estimation_check') THEN
+		SELECT string_agg(format('activity %s "%s" of PERT %s (%s / %s / %s)', id, name, pert_id, optimistic, probable, pessimistic), ', ' ORDER BY id)
+		INTO invalid
+		FROM public.activities
+		WHERE NOT (0 <= optimistic AND optimistic <= probable AND probable <= pessimistic);
+		IF invalid IS NOT NULL THEN
+			RAISE EXCEPTION 'Estimations must be 0 <= optimistic <= probable <= pessimistic, fix these activities then run db migrate again: %', invalid;
+		END IF;
+		ALTER TABLE public.activities ADD CONSTRAINT activities_estimation_check CHECK (0 <= optimistic AND optimistic <= probable AND probable <= pessimistic);
+	END IF;
+END $$;
@@ -0,0 +1,2 @@
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS start_date date NULL;
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS working_days varchar NOT NULL DEFAULT 'mon,tue,wed,thu,fri';
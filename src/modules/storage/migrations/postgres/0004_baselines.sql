@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS public.baselines (
+	id serial4 NOT NULL,
+	pert_id int4 NOT NULL,
+	"name" varchar NOT NULL,
+	created_at timestamp NOT NULL,
+	-- snapshot of the activities and totals, as JSON
+	snapshot text NOT NULL,
+	CONSTRAINT baselines_pk PRIMARY KEY (id),
+	CONSTRAINT baselines_name_un UNIQUE (pert_id, "name"),
+	CONSTRAINT baselines_fk FOREIGN KEY (pert_id) REFERENCES public.pert(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
@@ -0,0 +1,3 @@
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS actual_start date NULL;
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS actual_finish date NULL;
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS actual_effort float8 NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS status varchar NOT NULL DEFAULT 'not-started';
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS percent_complete float8 NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+ALTER TABLE public.pert ADD COLUMN IF NOT EXISTS aggregation varchar NOT NULL DEFAULT 'mean';
+
+CREATE TABLE IF NOT EXISTS public.estimates (
+	id serial4 NOT NULL,
+	activity_id int4 NOT NULL,
+	estimator varchar NOT NULL,
+	round int4 NOT NULL,
+	optimistic float8 NOT NULL,
+	probable float8 NOT NULL,
+	pessimistic float8 NOT NULL,
+	confidence float8 NOT NULL DEFAULT 1,
+	CONSTRAINT estimates_pk PRIMARY KEY (id),
+	CONSTRAINT estimates_round_un UNIQUE (activity_id, estimator, round),
+	CONSTRAINT estimates_fk FOREIGN KEY (activity_id) REFERENCES public.activities(id) ON DELETE CASCADE ON UPDATE CASCADE,
+	CONSTRAINT estimates_estimation_check CHECK (0 <= optimistic AND optimistic <= probable AND probable <= pessimistic)
+);
@@ -0,0 +1 @@
+ALTER TABLE public.activities ADD COLUMN IF NOT EXISTS tags varchar NOT NULL DEFAULT '';
@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS pert (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    estimation_model TEXT NOT NULL DEFAULT 'classic',
+    estimation_lambda REAL NULL,
+    unit TEXT NOT NULL DEFAULT 'days',
+    hours_per_day REAL NOT NULL DEFAULT 8,
+    start_date TEXT NULL,
+    working_days TEXT NOT NULL DEFAULT 'mon,tue,wed,thu,fri'
+);
+
+CREATE TABLE IF NOT EXISTS activities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pert_id INTEGER NOT NULL REFERENCES pert(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    pessimistic REAL NOT NULL,
+    probable REAL NOT NULL,
+    optimistic REAL NOT NULL,
+    CHECK (0 <= optimistic AND optimistic <= probable AND probable <= pessimistic)
+);
+
+CREATE TABLE IF NOT EXISTS activity_dependencies (
+    activity_id_head INTEGER NOT NULL REFERENCES activities(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    activity_id_tail INTEGER NOT NULL REFERENCES activities(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    PRIMARY KEY (activity_id_head, activity_id_tail)
+);
+
+CREATE TABLE IF NOT EXISTS boards (
+    pert_id INTEGER NOT NULL REFERENCES pert(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    github_board_id INTEGER NOT NULL,
+    PRIMARY KEY (github_board_id, pert_id)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS baselines (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pert_id INTEGER NOT NULL REFERENCES pert(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    -- snapshot of the activities and totals, as JSON
+    snapshot TEXT NOT NULL,
+    UNIQUE (pert_id, name)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE activities ADD COLUMN actual_start TEXT NULL;
+ALTER TABLE activities ADD COLUMN actual_finish TEXT NULL;
+ALTER TABLE activities ADD COLUMN actual_effort REAL NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE activities ADD COLUMN status TEXT NOT NULL DEFAULT 'not-started';
+ALTER TABLE activities ADD COLUMN percent_complete REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,14 @@
+ALTER TABLE pert ADD COLUMN aggregation TEXT NOT NULL DEFAULT 'mean';
+
+CREATE TABLE IF NOT EXISTS estimates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    activity_id INTEGER NOT NULL REFERENCES activities(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    estimator TEXT NOT NULL,
+    round INTEGER NOT NULL,
+    optimistic REAL NOT NULL,
+    probable REAL NOT NULL,
+    pessimistic REAL NOT NULL,
+    confidence REAL NOT NULL DEFAULT 1,
+    UNIQUE (activity_id, estimator, round),
+    CHECK (0 <= optimistic AND optimistic <= probable AND probable <= pessimistic)
+);
@@ -0,0 +1 @@
+ALTER TABLE activities ADD COLUMN tags TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,126 @@
+use anyhow::{bail, Result};
+use chrono::{NaiveDate, Weekday};
+
+use super::{
+    activity::{Activity, ActivityId, Actual, Estimation, Progress},
+    baseline::Baseline,
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::DurationUnit,
+    estimation_model::EstimationModel,
+    github::BoardId,
+    pert::{Pert, PertId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
+mod file;
+mod memory;
+mod migrations;
+mod postgres;
+mod sqlite;
+
+pub use self::file::FileStorage;
+pub use self::memory::MemoryStorage;
+pub use self::postgres::PostgresDb;
+pub use self::sqlite::SqliteDb;
+
+pub const DATABASE_URL_ENV: &str = "PERTY_DATABASE_URL";
+pub const DEFAULT_SQLITE_FILE: &str = "perty.db";
+
+pub trait Storage {
+    fn add_pert(
+        &mut self,
+        name: &str,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId>;
+    fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>>;
+    fn get_perts(&mut self) -> Result<Vec<Pert>>;
+    fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()>;
+    /// Deletes the PERT with its activities, their dependencies, its boards and baselines.
+    fn delete_pert(&mut self, pert_id: PertId) -> Result<()>;
+    fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()>;
+    fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()>;
+    fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()>;
+    fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()>;
+    fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()>;
+    fn add_activity(&mut self, pert_id: PertId, name: String, estimation: Estimation)
+        -> Result<()>;
+    fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>>;
+    fn get_activity_pert_id(&mut self, activity_id: ActivityId) -> Result<Option<PertId>>;
+    fn update_activity(
+        &mut self,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()>;
+    fn set_actual(&mut self, activity_id: ActivityId, actual: &Actual) -> Result<()>;
+    fn set_progress(&mut self, activity_id: ActivityId, progress: &Progress) -> Result<()>;
+    /// Replaces the tags of the activity.
+    fn set_tags(&mut self, activity_id: ActivityId, tags: &[String]) -> Result<()>;
+    /// Refused when the estimator already gave an estimate in the same round.
+    fn add_estimate(&mut self, activity_id: ActivityId, estimate: &EstimatorEstimate)
+        -> Result<()>;
+    /// Estimates of the activities of the PERT by activity id, rounds in the order they
+    /// were given.
+    fn get_estimates(&mut self, pert_id: PertId) -> Result<Vec<(ActivityId, EstimatorEstimate)>>;
+    /// Deletes the activity with the dependencies from and to it and its estimates.
+    fn delete_activity(&mut self, activity_id: ActivityId) -> Result<()>;
+    fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()>;
+    fn delete_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()>;
+    fn get_activities_with_related_dependencies(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Vec<ActivityWithRelatedDependencies>>;
+    fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()>;
+    /// Refused when the PERT already has a baseline with the same name.
+    fn add_baseline(&mut self, pert_id: PertId, baseline: &Baseline) -> Result<()>;
+    /// Baselines of the PERT, oldest first.
+    fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>>;
+    /// Last migration applied, `None` when the schema is not created. Storages
+    /// without schema have no migrations.
+    fn schema_version(&mut self) -> Result<Option<i32>> {
+        Ok(None)
+    }
+    /// Applies the migrations not applied yet, returns their names.
+    fn migrate(&mut self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+    /// Names of the migrations applied when the storage was opened, `None` for storages
+    /// only migrated by `migrate`.
+    fn migrated_on_open(&self) -> Option<&[String]> {
+        None
+    }
+}
+
+/// Storage selected by the scheme of `url`: `sqlite://FILE`, `file://FOLDER` or
+/// `postgres://...`.
+pub fn open_url(url: &str) -> Result<Box<dyn Storage>> {
+    if let Some(path) = url.strip_prefix("sqlite://") {
+        return Ok(Box::new(SqliteDb::open(path)?));
+    }
+    if let Some(path) = url.strip_prefix("file://") {
+        return Ok(Box::new(FileStorage::open(path)?));
+    }
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        return Ok(Box::new(PostgresDb::connect(url)?));
+    }
+    bail!("Unsupported database URL {}", url)
+}
+
+/// Storage set in `PERTY_DATABASE_URL`, then Postgres when `POSTGRES_CONN_STR` is set,
+/// otherwise a SQLite file in the current folder.
+pub fn open_storage() -> Result<Box<dyn Storage>> {
+    if let Ok(url) = std::env::var(DATABASE_URL_ENV) {
+        return open_url(&url);
+    }
+    if std::env::var("POSTGRES_CONN_STR").is_ok() {
+        return Ok(Box::new(PostgresDb::new()?));
+    }
+    Ok(Box::new(SqliteDb::open(DEFAULT_SQLITE_FILE)?))
+}
@@ -0,0 +1,488 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Weekday};
+use postgres::{Client, NoTls, Row};
+
+use super::{
+    migrations::{pending, CREATE_MIGRATIONS_TABLE, POSTGRES_MIGRATIONS},
+    Storage,
+};
+use crate::modules::{
+    activity::{
+        parse_tags, validate_tags, Activity, ActivityId, Actual, Estimation, Progress, Status,
+    },
+    baseline::Baseline,
+    calendar::{parse_weekdays, weekdays_name},
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::DurationUnit,
+    estimation_model::EstimationModel,
+    github::BoardId,
+    pert::{Pert, PertId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+pub struct PostgresDb {
+    client: Client,
+}
+
+impl PostgresDb {
+    pub fn new() -> Result<Self> {
+        let conn_str = std::env::var("POSTGRES_CONN_STR")?;
+        Self::connect(&conn_str)
+    }
+
+    pub fn connect(conn_str: &str) -> Result<Self> {
+        let client = Client::connect(conn_str, NoTls)?;
+        Ok(Self { client })
+    }
+}
+
+fn estimation_model_from_row(row: &Row) -> Result<EstimationModel> {
+    EstimationModel::from_name(row.get("estimation_model"), row.get("estimation_lambda"))
+}
+
+fn pert_from_row(row: &Row) -> Result<Pert> {
+    let mut pert = Pert::new(row.get("id"), row.get("name"));
+    pert.estimation_model = estimation_model_from_row(row)?;
+    pert.aggregation = Aggregation::from_name(row.get("aggregation"))?;
+    pert.unit = DurationUnit::from_name(row.get("unit"))?;
+    pert.hours_per_day = row.get("hours_per_day");
+    pert.start_date = row.get("start_date");
+    pert.working_days = parse_weekdays(row.get("working_days"))?;
+    Ok(pert)
+}
+
+impl Storage for PostgresDb {
+    fn add_activity(
+        &mut self,
+        pert_id: PertId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        self.client.execute(
+            "INSERT INTO activities (pert_id, name, pessimistic, probable, optimistic) VALUES ($1, $2, $3, $4, $5)",
+            &[
+                &pert_id,
+                &name,
+                &estimation.pessimistic,
+                &estimation.probable,
+                &estimation.optimistic,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>> {
+        let rows = self.client.query(
+            "
+    SELECT
+        pert.name as pert_name,
+        pert_id,
+        act.id as activity_id,
+        pessimistic, probable, optimistic,
+        act.name as activity_name,
+        actual_start, actual_finish, actual_effort,
+        status, percent_complete, tags,
+        pert.estimation_model, pert.estimation_lambda
+    FROM activities as act
+    inner join pert on act.pert_id = pert.id
+    WHERE pert_id = $1
+    ORDER BY act.id
+	",
+            &[&pert_id],
+        )?;
+        let activities = rows
+            .into_iter()
+            .map(|row| {
+                let mut activity = Activity::from_storage(
+                    row.get("activity_id"),
+                    row.get("activity_name"),
+                    Estimation {
+                        optimistic: row.get("optimistic"),
+                        probable: row.get("probable"),
+                        pessimistic: row.get("pessimistic"),
+                    },
+                );
+                activity.model = estimation_model_from_row(&row)?;
+                activity.actual = Actual {
+                    start: row.get("actual_start"),
+                    finish: row.get("actual_finish"),
+                    effort: row.get("actual_effort"),
+                };
+                activity.progress = Progress {
+                    status: Status::from_name(row.get("status"))?,
+                    percent_complete: row.get("percent_complete"),
+                };
+                activity.tags = parse_tags(row.get("tags"));
+                Ok(activity)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(activities)
+    }
+
+    fn get_activity_pert_id(&mut self, activity_id: ActivityId) -> Result<Option<PertId>> {
+        let row = self.client.query_opt(
+            "SELECT pert_id FROM activities WHERE id = $1",
+            &[&activity_id],
+        )?;
+        Ok(row.map(|row| row.get("pert_id")))
+    }
+
+    fn add_pert(
+        &mut self,
+        name: &str,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId> {
+        let response = self.client.query(
+            "INSERT INTO pert (name, estimation_model, estimation_lambda, unit) VALUES ($1, $2, $3, $4) RETURNING id as pert_id",
+            &[
+                &name,
+                &estimation_model.name(),
+                &estimation_model.lambda(),
+                &unit.name(),
+            ],
+        )?;
+        let pert_id: PertId = response.first().unwrap().get("pert_id");
+
+        Ok(pert_id)
+    }
+
+    fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>> {
+        let res = match self
+            .client
+            .query_one("SELECT * FROM pert WHERE id = $1", &[&pert_id])
+        {
+            Err(_) => None,
+            Ok(row) => Some(pert_from_row(&row)?),
+        };
+        Ok(res)
+    }
+
+    fn get_perts(&mut self) -> Result<Vec<Pert>> {
+        let res = self.client.query("SELECT * FROM pert ORDER BY id", &[])?;
+        res.iter().map(pert_from_row).collect()
+    }
+
+    fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET name = $2 WHERE id = $1",
+            &[&pert_id, &name],
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_pert(&mut self, pert_id: PertId) -> Result<()> {
+        // activities, dependencies, boards and baselines are deleted in cascade
+        self.client
+            .execute("DELETE FROM pert WHERE id = $1", &[&pert_id])?;
+
+        Ok(())
+    }
+
+    fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET estimation_model = $2, estimation_lambda = $3 WHERE id = $1",
+            &[
+                &pert_id,
+                &estimation_model.name(),
+                &estimation_model.lambda(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET aggregation = $2 WHERE id = $1",
+            &[&pert_id, &aggregation.name()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET hours_per_day = $2 WHERE id = $1",
+            &[&pert_id, &hours_per_day],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET start_date = $2 WHERE id = $1",
+            &[&pert_id, &start_date],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()> {
+        self.client.execute(
+            "UPDATE pert SET working_days = $2 WHERE id = $1",
+            &[&pert_id, &weekdays_name(working_days)],
+        )?;
+
+        Ok(())
+    }
+
+    fn update_activity(
+        &mut self,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        self.client.execute(
+            "UPDATE activities SET name = $2, pessimistic = $3, probable = $4, optimistic = $5 WHERE id = $1",
+            &[
+                &activity_id,
+                &name,
+                &estimation.pessimistic,
+                &estimation.probable,
+                &estimation.optimistic,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_actual(&mut self, activity_id: ActivityId, actual: &Actual) -> Result<()> {
+        actual.validate()?;
+        self.client.execute(
+            "UPDATE activities SET actual_start = $2, actual_finish = $3, actual_effort = $4 WHERE id = $1",
+            &[&activity_id, &actual.start, &actual.finish, &actual.effort],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_progress(&mut self, activity_id: ActivityId, progress: &Progress) -> Result<()> {
+        progress.validate()?;
+        self.client.execute(
+            "UPDATE activities SET status = $2, percent_complete = $3 WHERE id = $1",
+            &[
+                &activity_id,
+                &progress.status.name(),
+                &progress.percent_complete,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_tags(&mut self, activity_id: ActivityId, tags: &[String]) -> Result<()> {
+        validate_tags(tags)?;
+        self.client.execute(
+            "UPDATE activities SET tags = $2 WHERE id = $1",
+            &[&activity_id, &tags.join(",")],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_estimate(
+        &mut self,
+        activity_id: ActivityId,
+        estimate: &EstimatorEstimate,
+    ) -> Result<()> {
+        estimate.validate()?;
+        self.client.execute(
+            "INSERT INTO estimates (activity_id, estimator, round, optimistic, probable, pessimistic, confidence) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            &[
+                &activity_id,
+                &estimate.estimator,
+                &(estimate.round as i32),
+                &estimate.estimation.optimistic,
+                &estimate.estimation.probable,
+                &estimate.estimation.pessimistic,
+                &estimate.confidence,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_estimates(&mut self, pert_id: PertId) -> Result<Vec<(ActivityId, EstimatorEstimate)>> {
+        let rows = self.client.query(
+            "
+    SELECT est.activity_id, estimator, round, est.optimistic, est.probable, est.pessimistic, confidence
+    FROM estimates as est
+    inner join activities as act on est.activity_id = act.id
+    WHERE act.pert_id = $1
+    ORDER BY est.activity_id, est.id
+	",
+            &[&pert_id],
+        )?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("activity_id"),
+                    EstimatorEstimate {
+                        estimator: row.get("estimator"),
+                        round: row.get::<_, i32>("round") as u32,
+                        estimation: Estimation {
+                            optimistic: row.get("optimistic"),
+                            probable: row.get("probable"),
+                            pessimistic: row.get("pessimistic"),
+                        },
+                        confidence: row.get("confidence"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    fn delete_activity(&mut self, activity_id: ActivityId) -> Result<()> {
+        self.client
+            .execute("DELETE FROM activities WHERE id = $1", &[&activity_id])?;
+
+        Ok(())
+    }
+
+    fn delete_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        self.client.execute(
+            "DELETE FROM activity_dependencies WHERE activity_id_head = $1 AND activity_id_tail = $2",
+            &[&head, &tail],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        self.client.execute(
+            "INSERT INTO activity_dependencies (activity_id_head, activity_id_tail) VALUES ($1, $2)",
+            &[
+                &head,
+                &tail,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_activities_with_related_dependencies(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Vec<ActivityWithRelatedDependencies>> {
+        let query = "
+        select 
+            id as activity_id, activities.name as head_name, activity_id_head
+            from activities
+            left join activity_dependencies on activities.id = activity_dependencies.activity_id_tail
+        where pert_id = $1
+        order by activities.id, activity_id_head";
+        let res = self.client.query(query, &[&pert_id])?;
+        let acts = res
+            .into_iter()
+            .map(|row| ActivityWithRelatedDependencies {
+                activity_id_head: row.get("activity_id_head"),
+                head_name: row.get("head_name"),
+                activity_id: row.get("activity_id"),
+            })
+            .collect::<Vec<_>>();
+        Ok(acts)
+    }
+
+    fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()> {
+        self.client.execute(
+            "INSERT INTO boards (pert_id, github_board_id) VALUES ($1, $2)",
+            &[&pert_id, &github_board_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_baseline(&mut self, pert_id: PertId, baseline: &Baseline) -> Result<()> {
+        self.client.execute(
+            "INSERT INTO baselines (pert_id, name, created_at, snapshot) VALUES ($1, $2, $3, $4)",
+            &[
+                &pert_id,
+                &baseline.name,
+                &baseline.created_at,
+                &serde_json::to_string(&baseline.snapshot)?,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>> {
+        let rows = self.client.query(
+            "SELECT name, created_at, snapshot FROM baselines WHERE pert_id = $1 ORDER BY id",
+            &[&pert_id],
+        )?;
+        let mut baselines = vec![];
+        for row in rows {
+            let name: String = row.get("name");
+            baselines.push(Baseline {
+                snapshot: serde_json::from_str(row.get("snapshot"))
+                    .with_context(|| format!("Invalid snapshot of baseline {}", name))?,
+                name,
+                created_at: row.get("created_at"),
+            });
+        }
+        Ok(baselines)
+    }
+
+    fn schema_version(&mut self) -> Result<Option<i32>> {
+        let row = self.client.query_one(
+            "SELECT to_regclass('schema_migrations') IS NOT NULL AS created",
+            &[],
+        )?;
+        if !row.get::<_, bool>("created") {
+            return Ok(None);
+        }
+        let row = self
+            .client
+            .query_one("SELECT max(version) AS version FROM schema_migrations", &[])?;
+        Ok(row.get("version"))
+    }
+
+    fn migrate(&mut self) -> Result<Vec<String>> {
+        self.client.batch_execute(CREATE_MIGRATIONS_TABLE)?;
+        let applied: Vec<i32> = self
+            .client
+            .query("SELECT version FROM schema_migrations", &[])?
+            .iter()
+            .map(|row| row.get("version"))
+            .collect();
+        let mut names = vec![];
+        for migration in pending(POSTGRES_MIGRATIONS, &applied)? {
+            let mut transaction = self.client.transaction()?;
+            transaction
+                .batch_execute(migration.sql)
+                .with_context(|| format!("Migration {} failed", migration))?;
+            transaction.execute(
+                "INSERT INTO schema_migrations (version, name) VALUES ($1, $2)",
+                &[&migration.version, &migration.name],
+            )?;
+            transaction.commit()?;
+            names.push(migration.to_string());
+        }
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PostgresDb;
+    use crate::storage_conformance_tests;
+
+    /// The checks add PERTs to this database, they are skipped when it is not set.
+    const TEST_DATABASE_ENV: &str = "PERTY_TEST_POSTGRES_URL";
+
+    storage_conformance_tests!(std::env::var(TEST_DATABASE_ENV)
+        .ok()
+        .map(|url| PostgresDb::connect(&url).unwrap()));
+}
@@ -0,0 +1,547 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Weekday};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::{
+    migrations::{pending, CREATE_MIGRATIONS_TABLE, SQLITE_MIGRATIONS},
+    Storage,
+};
+use crate::modules::{
+    activity::{
+        parse_tags, validate_tags, Activity, ActivityId, Actual, Estimation, Progress, Status,
+    },
+    baseline::Baseline,
+    calendar::{parse_weekdays, weekdays_name},
+    delphi::{Aggregation, EstimatorEstimate},
+    duration::DurationUnit,
+    estimation_model::EstimationModel,
+    github::BoardId,
+    pert::{Pert, PertId},
+    roadmap::ActivityWithRelatedDependencies,
+};
+
+/// Local file database, the schema is created and migrated when it is opened.
+pub struct SqliteDb {
+    conn: Connection,
+    migrated_on_open: Vec<String>,
+}
+
+impl SqliteDb {
+    pub fn open(path: &str) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        let mut db = Self {
+            conn,
+            migrated_on_open: vec![],
+        };
+        db.migrated_on_open = db.migrate()?;
+        Ok(db)
+    }
+}
+
+fn estimation_model_from_row(row: &Row) -> Result<EstimationModel> {
+    EstimationModel::from_name(
+        &row.get::<_, String>("estimation_model")?,
+        row.get("estimation_lambda")?,
+    )
+}
+
+fn pert_from_row(row: &Row) -> Result<Pert> {
+    let mut pert = Pert::new(row.get("id")?, row.get("name")?);
+    pert.estimation_model = estimation_model_from_row(row)?;
+    pert.aggregation = Aggregation::from_name(&row.get::<_, String>("aggregation")?)?;
+    pert.unit = DurationUnit::from_name(&row.get::<_, String>("unit")?)?;
+    pert.hours_per_day = row.get("hours_per_day")?;
+    pert.start_date = row.get("start_date")?;
+    pert.working_days = parse_weekdays(&row.get::<_, String>("working_days")?)?;
+    Ok(pert)
+}
+
+impl Storage for SqliteDb {
+    fn add_activity(
+        &mut self,
+        pert_id: PertId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        self.conn.execute(
+            "INSERT INTO activities (pert_id, name, pessimistic, probable, optimistic) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                pert_id,
+                name,
+                estimation.pessimistic,
+                estimation.probable,
+                estimation.optimistic
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>> {
+        let mut statement = self.conn.prepare(
+            "
+    SELECT
+        act.id as activity_id,
+        pessimistic, probable, optimistic,
+        act.name as activity_name,
+        actual_start, actual_finish, actual_effort,
+        status, percent_complete, tags,
+        pert.estimation_model, pert.estimation_lambda
+    FROM activities as act
+    inner join pert on act.pert_id = pert.id
+    WHERE pert_id = ?1
+    ORDER BY act.id
+	",
+        )?;
+        let mut rows = statement.query(params![pert_id])?;
+        let mut activities = vec![];
+        while let Some(row) = rows.next()? {
+            let mut activity = Activity::from_storage(
+                row.get("activity_id")?,
+                row.get("activity_name")?,
+                Estimation {
+                    optimistic: row.get("optimistic")?,
+                    probable: row.get("probable")?,
+                    pessimistic: row.get("pessimistic")?,
+                },
+            );
+            activity.model = estimation_model_from_row(row)?;
+            activity.actual = Actual {
+                start: row.get("actual_start")?,
+                finish: row.get("actual_finish")?,
+                effort: row.get("actual_effort")?,
+            };
+            activity.progress = Progress {
+                status: Status::from_name(&row.get::<_, String>("status")?)?,
+                percent_complete: row.get("percent_complete")?,
+            };
+            activity.tags = parse_tags(&row.get::<_, String>("tags")?);
+            activities.push(activity);
+        }
+        Ok(activities)
+    }
+
+    fn get_activity_pert_id(&mut self, activity_id: ActivityId) -> Result<Option<PertId>> {
+        let pert_id = self
+            .conn
+            .query_row(
+                "SELECT pert_id FROM activities WHERE id = ?1",
+                params![activity_id],
+                |row| row.get("pert_id"),
+            )
+            .optional()?;
+        Ok(pert_id)
+    }
+
+    fn add_pert(
+        &mut self,
+        name: &str,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId> {
+        self.conn.execute(
+            "INSERT INTO pert (name, estimation_model, estimation_lambda, unit) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                name,
+                estimation_model.name(),
+                estimation_model.lambda(),
+                unit.name()
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid() as PertId)
+    }
+
+    fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>> {
+        let mut statement = self.conn.prepare("SELECT * FROM pert WHERE id = ?1")?;
+        let mut rows = statement.query(params![pert_id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(pert_from_row(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_perts(&mut self) -> Result<Vec<Pert>> {
+        let mut statement = self.conn.prepare("SELECT * FROM pert ORDER BY id")?;
+        let mut rows = statement.query([])?;
+        let mut perts = vec![];
+        while let Some(row) = rows.next()? {
+            perts.push(pert_from_row(row)?);
+        }
+        Ok(perts)
+    }
+
+    fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET name = ?2 WHERE id = ?1",
+            params![pert_id, name],
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_pert(&mut self, pert_id: PertId) -> Result<()> {
+        // activities, dependencies, boards and baselines are deleted in cascade
+        self.conn
+            .execute("DELETE FROM pert WHERE id = ?1", params![pert_id])?;
+
+        Ok(())
+    }
+
+    fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET estimation_model = ?2, estimation_lambda = ?3 WHERE id = ?1",
+            params![pert_id, estimation_model.name(), estimation_model.lambda()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET aggregation = ?2 WHERE id = ?1",
+            params![pert_id, aggregation.name()],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET hours_per_day = ?2 WHERE id = ?1",
+            params![pert_id, hours_per_day],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET start_date = ?2 WHERE id = ?1",
+            params![pert_id, start_date],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE pert SET working_days = ?2 WHERE id = ?1",
+            params![pert_id, weekdays_name(working_days)],
+        )?;
+
+        Ok(())
+    }
+
+    fn update_activity(
+        &mut self,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        self.conn.execute(
+            "UPDATE activities SET name = ?2, pessimistic = ?3, probable = ?4, optimistic = ?5 WHERE id = ?1",
+            params![
+                activity_id,
+                name,
+                estimation.pessimistic,
+                estimation.probable,
+                estimation.optimistic
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_actual(&mut self, activity_id: ActivityId, actual: &Actual) -> Result<()> {
+        actual.validate()?;
+        self.conn.execute(
+            "UPDATE activities SET actual_start = ?2, actual_finish = ?3, actual_effort = ?4 WHERE id = ?1",
+            params![activity_id, actual.start, actual.finish, actual.effort],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_progress(&mut self, activity_id: ActivityId, progress: &Progress) -> Result<()> {
+        progress.validate()?;
+        self.conn.execute(
+            "UPDATE activities SET status = ?2, percent_complete = ?3 WHERE id = ?1",
+            params![
+                activity_id,
+                progress.status.name(),
+                progress.percent_complete
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_tags(&mut self, activity_id: ActivityId, tags: &[String]) -> Result<()> {
+        validate_tags(tags)?;
+        self.conn.execute(
+            "UPDATE activities SET tags = ?2 WHERE id = ?1",
+            params![activity_id, tags.join(",")],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_estimate(
+        &mut self,
+        activity_id: ActivityId,
+        estimate: &EstimatorEstimate,
+    ) -> Result<()> {
+        estimate.validate()?;
+        self.conn.execute(
+            "INSERT INTO estimates (activity_id, estimator, round, optimistic, probable, pessimistic, confidence) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                activity_id,
+                estimate.estimator,
+                estimate.round,
+                estimate.estimation.optimistic,
+                estimate.estimation.probable,
+                estimate.estimation.pessimistic,
+                estimate.confidence,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_estimates(&mut self, pert_id: PertId) -> Result<Vec<(ActivityId, EstimatorEstimate)>> {
+        let mut statement = self.conn.prepare(
+            "
+    SELECT est.activity_id, estimator, round, est.optimistic, est.probable, est.pessimistic, confidence
+    FROM estimates as est
+    inner join activities as act on est.activity_id = act.id
+    WHERE act.pert_id = ?1
+    ORDER BY est.activity_id, est.id
+	",
+        )?;
+        let mut rows = statement.query(params![pert_id])?;
+        let mut estimates = vec![];
+        while let Some(row) = rows.next()? {
+            estimates.push((
+                row.get("activity_id")?,
+                EstimatorEstimate {
+                    estimator: row.get("estimator")?,
+                    round: row.get("round")?,
+                    estimation: Estimation {
+                        optimistic: row.get("optimistic")?,
+                        probable: row.get("probable")?,
+                        pessimistic: row.get("pessimistic")?,
+                    },
+                    confidence: row.get("confidence")?,
+                },
+            ));
+        }
+        Ok(estimates)
+    }
+
+    fn delete_activity(&mut self, activity_id: ActivityId) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM activities WHERE id = ?1", params![activity_id])?;
+
+        Ok(())
+    }
+
+    fn delete_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM activity_dependencies WHERE activity_id_head = ?1 AND activity_id_tail = ?2",
+            params![head, tail],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO activity_dependencies (activity_id_head, activity_id_tail) VALUES (?1, ?2)",
+            params![head, tail],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_activities_with_related_dependencies(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Vec<ActivityWithRelatedDependencies>> {
+        let mut statement = self.conn.prepare(
+            "
+        select
+            id as activity_id, activities.name as head_name, activity_id_head
+            from activities
+            left join activity_dependencies on activities.id = activity_dependencies.activity_id_tail
+        where pert_id = ?1
+        order by id, activity_id_head",
+        )?;
+        let acts = statement
+            .query_map(params![pert_id], |row| {
+                Ok(ActivityWithRelatedDependencies {
+                    activity_id_head: row.get("activity_id_head")?,
+                    head_name: row.get("head_name")?,
+                    activity_id: row.get("activity_id")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(acts)
+    }
+
+    fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO boards (pert_id, github_board_id) VALUES (?1, ?2)",
+            params![pert_id, github_board_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn add_baseline(&mut self, pert_id: PertId, baseline: &Baseline) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO baselines (pert_id, name, created_at, snapshot) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                pert_id,
+                baseline.name,
+                baseline.created_at,
+                serde_json::to_string(&baseline.snapshot)?
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>> {
+        let mut statement = self.conn.prepare(
+            "SELECT name, created_at, snapshot FROM baselines WHERE pert_id = ?1 ORDER BY id",
+        )?;
+        let mut rows = statement.query(params![pert_id])?;
+        let mut baselines = vec![];
+        while let Some(row) = rows.next()? {
+            let name: String = row.get("name")?;
+            let snapshot: String = row.get("snapshot")?;
+            baselines.push(Baseline {
+                snapshot: serde_json::from_str(&snapshot)
+                    .with_context(|| format!("Invalid snapshot of baseline {}", name))?,
+                name,
+                created_at: row.get("created_at")?,
+            });
+        }
+        Ok(baselines)
+    }
+
+    fn schema_version(&mut self) -> Result<Option<i32>> {
+        let created: bool = self.conn.query_row(
+            "SELECT count(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !created {
+            return Ok(None);
+        }
+        let version =
+            self.conn
+                .query_row("SELECT max(version) FROM schema_migrations", [], |row| {
+                    row.get(0)
+                })?;
+        Ok(version)
+    }
+
+    fn migrate(&mut self) -> Result<Vec<String>> {
+        self.conn.execute_batch(CREATE_MIGRATIONS_TABLE)?;
+        let applied = self
+            .conn
+            .prepare("SELECT version FROM schema_migrations")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i32>>>()?;
+        let mut names = vec![];
+        for migration in pending(SQLITE_MIGRATIONS, &applied)? {
+            let transaction = self.conn.transaction()?;
+            transaction
+                .execute_batch(migration.sql)
+                .with_context(|| format!("Migration {} failed", migration))?;
+            transaction.execute(
+                "INSERT INTO schema_migrations (version, name) VALUES (?1, ?2)",
+                params![migration.version, migration.name],
+            )?;
+            transaction.commit()?;
+            names.push(migration.to_string());
+        }
+        Ok(names)
+    }
+
+    fn migrated_on_open(&self) -> Option<&[String]> {
+        Some(&self.migrated_on_open)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SqliteDb;
+    use crate::{
+        modules::{
+            activity::Estimation,
+            duration::DurationUnit,
+            estimation_model::EstimationModel,
+            storage::{migrations::SQLITE_MIGRATIONS, Storage},
+        },
+        perty::Perty,
+        storage_conformance_tests,
+    };
+
+    storage_conformance_tests!(Some(SqliteDb::open_in_memory().unwrap()));
+
+    #[test]
+    fn schema_migrated_when_opened() {
+        let mut db = SqliteDb::open_in_memory().unwrap();
+        let last = SQLITE_MIGRATIONS.last().unwrap().version;
+        assert_eq!(db.schema_version().unwrap(), Some(last));
+        assert_eq!(
+            db.migrated_on_open().unwrap().len(),
+            SQLITE_MIGRATIONS.len()
+        );
+    }
+
+    #[test]
+    fn invalid_estimations_are_read_and_reported() {
+        let mut db = SqliteDb::open_in_memory().unwrap();
+        let pert_id = db
+            .add_pert("example", EstimationModel::Classic, DurationUnit::Days)
+            .unwrap();
+        let estimation = Estimation::new(1.0, 2.0, 3.0).unwrap();
+        db.add_activity(pert_id, "design".to_string(), estimation)
+            .unwrap();
+        // as saved before the estimations were validated
+        db.conn
+            .execute_batch(
+                "PRAGMA ignore_check_constraints = ON;
+                UPDATE activities SET optimistic = 5;",
+            )
+            .unwrap();
+
+        let activities = db.get_activities(pert_id).unwrap();
+        assert_eq!(activities[0].estimation.optimistic, 5.0);
+        let mut perty = Perty::new(Box::new(db));
+        assert_eq!(
+            perty.get_invalid_activities(pert_id).unwrap(),
+            vec![
+                "activity 1 \"design\": Estimation values must be optimistic <= most probable <= pessimistic"
+            ]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Work of the activities sharing a tag. Their estimates are assumed independent, so
+/// their variances add up.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TagSubtotal {
+    pub tag: String,
+    pub activities: usize,
+    /// Sum of the expected durations.
+    pub total: f64,
+    pub standard_deviation: f64,
+}
+
+/// One subtotal per tag, sorted by tag, from the tags, expected duration and variance
+/// of every activity. An activity with several tags counts in each of them.
+pub fn tag_subtotals<'a>(
+    activities: impl IntoIterator<Item = (&'a [String], f64, f64)>,
+) -> Vec<TagSubtotal> {
+    let mut groups: BTreeMap<&str, (usize, f64, f64)> = BTreeMap::new();
+    for (tags, mean, variance) in activities {
+        for tag in tags {
+            let group = groups.entry(tag).or_default();
+            group.0 += 1;
+            group.1 += mean;
+            group.2 += variance;
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(tag, (activities, total, variance))| TagSubtotal {
+            tag: tag.to_string(),
+            activities,
+            total,
+            standard_deviation: variance.sqrt(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::tag_subtotals;
+
+    #[test]
+    fn subtotals_by_tag() {
+        let backend = vec!["backend".to_string()];
+        let both = vec!["backend".to_string(), "ui".to_string()];
+        let subtotals = tag_subtotals([
+            (backend.as_slice(), 2.0, 9.0),
+            (both.as_slice(), 3.0, 16.0),
+            (&[][..], 5.0, 1.0),
+        ]);
+        assert_eq!(subtotals.len(), 2);
+        assert_eq!(subtotals[0].tag, "backend");
+        assert_eq!(subtotals[0].activities, 2);
+        assert_eq!(subtotals[0].total, 5.0);
+        assert_eq!(subtotals[0].standard_deviation, 5.0);
+        assert_eq!(subtotals[1].tag, "ui");
+        assert_eq!(subtotals[1].total, 3.0);
+        assert_eq!(subtotals[1].standard_deviation, 4.0);
+    }
+}
@@ -0,0 +1,57 @@
+use cli_table::{format::Justify, Table, WithTitle};
+
+use super::{duration::DurationUnit, report::round, tags::TagSubtotal};
+
+#[derive(Table)]
+struct TagSubtotalRow {
+    #[table(title = "Tag")]
+    tag: String,
+    #[table(title = "Activities", justify = "Justify::Right")]
+    activities: usize,
+    #[table(title = "PERT estimation")]
+    total: f64,
+    #[table(title = "σ")]
+    standard_deviation: f64,
+}
+
+/// Tags of an activity in a table cell.
+pub fn display_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+pub fn subtotals_table(subtotals: &[TagSubtotal]) -> String {
+    let rows: Vec<TagSubtotalRow> = subtotals
+        .iter()
+        .map(|subtotal| TagSubtotalRow {
+            tag: subtotal.tag.to_owned(),
+            activities: subtotal.activities,
+            total: round(subtotal.total),
+            standard_deviation: round(subtotal.standard_deviation),
+        })
+        .collect();
+    rows.with_title().display().unwrap().to_string()
+}
+
+/// Header of the subtotals in the CSV and Markdown reports.
+pub fn subtotals_header(unit: DurationUnit) -> Vec<String> {
+    vec![
+        "Tag".to_string(),
+        "Activities".to_string(),
+        format!("PERT ({})", unit),
+        format!("Standard deviation ({})", unit),
+    ]
+}
+
+pub fn subtotals_body(subtotals: &[TagSubtotal]) -> Vec<Vec<String>> {
+    subtotals
+        .iter()
+        .map(|subtotal| {
+            vec![
+                subtotal.tag.to_owned(),
+                subtotal.activities.to_string(),
+                round(subtotal.total).to_string(),
+                round(subtotal.standard_deviation).to_string(),
+            ]
+        })
+        .collect()
+}
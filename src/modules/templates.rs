@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+pub const TEMPLATES_DIR_ENV: &str = "PERTY_TEMPLATES_DIR";
+
+const INCLUDES_DIR: &str = "_includes";
+
+const TEMPLATES: [(&str, &str); 3] = [
+    (
+        "report_baseline_diff.liquid",
+        include_str!("templates/report_baseline_diff.liquid"),
+    ),
+    (
+        "report_pert.liquid",
+        include_str!("templates/report_pert.liquid"),
+    ),
+    (
+        "report_roadmap.liquid",
+        include_str!("templates/report_roadmap.liquid"),
+    ),
+];
+
+const PARTIALS: [(&str, &str); 3] = [
+    (
+        "activities_table.liquid",
+        include_str!("templates/_includes/activities_table.liquid"),
+    ),
+    (
+        "style.liquid",
+        include_str!("templates/_includes/style.liquid"),
+    ),
+    (
+        "tags_table.liquid",
+        include_str!("templates/_includes/tags_table.liquid"),
+    ),
+];
+
+type Partials = liquid::partials::EagerCompiler<liquid::partials::InMemorySource>;
+
+/// Directory set in `PERTY_TEMPLATES_DIR`, its files override the embedded templates.
+pub fn templates_dir() -> Option<PathBuf> {
+    std::env::var_os(TEMPLATES_DIR_ENV).map(PathBuf::from)
+}
+
+/// Content of `path` when it exists, `None` otherwise.
+fn read_override(path: &Path) -> Result<Option<String>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read template {}", path.display()))?;
+    Ok(Some(content))
+}
+
+/// Embedded partials, replaced or extended by the `_includes` folder of `dir`.
+fn get_partials(dir: Option<&Path>) -> Result<Partials> {
+    let mut partials = Partials::empty();
+    for (name, content) in PARTIALS {
+        partials.add(name, content);
+    }
+    let includes = match dir.map(|dir| dir.join(INCLUDES_DIR)) {
+        Some(includes) if includes.is_dir() => includes,
+        _ => return Ok(partials),
+    };
+    let entries = std::fs::read_dir(&includes)
+        .with_context(|| format!("Unable to read templates folder {}", includes.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        if let Some(content) = read_override(&path)? {
+            partials.add(name, content);
+        }
+    }
+    Ok(partials)
+}
+
+fn get_template(dir: Option<&Path>, name: &str) -> Result<String> {
+    if let Some(dir) = dir {
+        if let Some(content) = read_override(&dir.join(name))? {
+            return Ok(content);
+        }
+    }
+    TEMPLATES
+        .iter()
+        .find(|(template_name, _)| *template_name == name)
+        .map(|(_, content)| content.to_string())
+        .with_context(|| format!("Unknown template {}", name))
+}
+
+/// Renders the template `name`, looking for overrides in `PERTY_TEMPLATES_DIR`.
+pub fn render(name: &str, globals: &liquid::Object) -> Result<String> {
+    render_from(templates_dir().as_deref(), name, globals)
+}
+
+pub fn render_from(dir: Option<&Path>, name: &str, globals: &liquid::Object) -> Result<String> {
+    let parser = liquid::ParserBuilder::with_stdlib()
+        .partials(get_partials(dir)?)
+        .build()
+        .context("Invalid partial template")?;
+    let template = parser
+        .parse(&get_template(dir, name)?)
+        .with_context(|| format!("Invalid template {}", name))?;
+    template
+        .render(globals)
+        .with_context(|| format!("Unable to render template {}", name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::render_from;
+    use tempfile::TempDir;
+
+    /// Removed when dropped.
+    fn templates_dir() -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("_includes")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn override_template_and_partial() {
+        let temp = templates_dir();
+        let dir = temp.path();
+        std::fs::write(
+            dir.join("report_roadmap.liquid"),
+            "{% include \"style.liquid\" %}{{ pert_name }}",
+        )
+        .unwrap();
+        std::fs::write(dir.join("_includes/style.liquid"), "<style></style>").unwrap();
+
+        let globals = liquid::object!({ "pert_name": "example" });
+        let html = render_from(Some(dir), "report_roadmap.liquid", &globals).unwrap();
+        assert_eq!(html, "<style></style>example");
+    }
+
+    #[test]
+    fn invalid_template_is_an_error() {
+        let temp = templates_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("report_pert.liquid"), "{% if %}").unwrap();
+
+        let globals = liquid::object!({});
+        assert!(render_from(Some(dir), "report_pert.liquid", &globals).is_err());
+    }
+}
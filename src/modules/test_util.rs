@@ -0,0 +1,16 @@
+//! Fixtures shared by the tests of the modules.
+
+use super::{activity::ActivityId, roadmap::ActivityWithRelatedDependencies};
+
+/// Dependency of `activity_id` on `activity_id_head`, or an activity without dependency
+/// when `None`, named `activity ID` as in the test fixtures.
+pub fn dep(
+    activity_id: ActivityId,
+    activity_id_head: Option<ActivityId>,
+) -> ActivityWithRelatedDependencies {
+    ActivityWithRelatedDependencies {
+        activity_id,
+        head_name: format!("activity {}", activity_id),
+        activity_id_head,
+    }
+}
@@ -1,12 +1,33 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate, Timelike, Weekday};
 
 use crate::modules::{
-    activity::{Activity, Estimation},
-    activity_report::ActivityReport,
+    accuracy::Accuracy,
+    accuracy_report::{AccuracyOverviewReport, AccuracyReport},
+    activity::{validate_tags, Activity, ActivityId, Actual, Estimation, Progress},
+    activity_report::{ActivityList, ActivityReport},
+    baseline::{Baseline, BaselineDiff, Snapshot, CURRENT},
+    baseline_report::BaselineDiffReport,
+    calendar::{load_holidays, Calendar},
+    critical_path::CriticalPath,
+    critical_path_report::CriticalPathReport,
+    delphi::{latest, Aggregation, Delphi, EstimatorEstimate},
+    delphi_report::DelphiReport,
+    duration::DurationUnit,
+    estimation_model::EstimationModel,
+    gantt_report::GanttReport,
     github::BoardId,
+    graph::{describe_path, DependencyGraph},
+    graph_report::GraphReport,
     pert::{Pert, PertId},
+    probability::CompletionProbability,
+    probability_report::ProbabilityReport,
+    progress::Forecast,
     roadmap::Roadmap,
     roadmap_report::RoadmapReport,
+    schedule::Schedule,
+    simulation::{MonteCarlo, SimulationResult},
+    simulation_report::SimulationReport,
     storage::Storage,
 };
 
@@ -19,8 +40,47 @@ impl Perty {
         Self { storage }
     }
 
-    pub fn add_pert(&mut self, name: String) -> Result<PertId> {
-        self.storage.add_pert(&name)
+    pub fn add_pert(
+        &mut self,
+        name: String,
+        estimation_model: EstimationModel,
+        unit: DurationUnit,
+    ) -> Result<PertId> {
+        self.storage.add_pert(&name, estimation_model, unit)
+    }
+
+    pub fn set_hours_per_day(&mut self, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+        // written as a negation to refuse NaN, every comparison with it is false
+        if !(hours_per_day > 0f64 && hours_per_day <= 24f64) {
+            bail!("Hours per day must be between 0 and 24");
+        }
+        self.existing_pert(pert_id)?;
+        self.storage.set_hours_per_day(pert_id, hours_per_day)
+    }
+
+    pub fn set_start_date(&mut self, pert_id: PertId, start_date: Option<NaiveDate>) -> Result<()> {
+        let pert = self.existing_pert(pert_id)?;
+        if start_date.is_some() && pert.unit == DurationUnit::Points {
+            bail!("Activities estimated in points cannot be scheduled on a calendar");
+        }
+        self.storage.set_start_date(pert_id, start_date)
+    }
+
+    pub fn set_working_days(&mut self, pert_id: PertId, working_days: &[Weekday]) -> Result<()> {
+        if working_days.is_empty() {
+            bail!("At least one working day is needed");
+        }
+        self.existing_pert(pert_id)?;
+        self.storage.set_working_days(pert_id, working_days)
+    }
+
+    pub fn set_estimation_model(
+        &mut self,
+        pert_id: PertId,
+        estimation_model: EstimationModel,
+    ) -> Result<()> {
+        self.existing_pert(pert_id)?;
+        self.storage.set_estimation_model(pert_id, estimation_model)
     }
 
     pub fn get_pert(&mut self, pert_id: PertId) -> Result<Option<Pert>> {
@@ -31,35 +91,429 @@ impl Perty {
         self.storage.get_perts()
     }
 
+    pub fn rename_pert(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("The name cannot be empty");
+        }
+        self.existing_pert(pert_id)?;
+        self.storage.rename_pert(pert_id, name)
+    }
+
+    pub fn delete_pert(&mut self, pert_id: PertId) -> Result<()> {
+        self.existing_pert(pert_id)?;
+        self.storage.delete_pert(pert_id)
+    }
+
+    fn existing_pert(&mut self, pert_id: PertId) -> Result<Pert> {
+        self.get_pert(pert_id)?
+            .with_context(|| format!("No PERT found with id {}", pert_id))
+    }
+
+    /// Fails when the activity does not belong to the PERT.
+    fn check_activity(&mut self, pert_id: PertId, activity_id: ActivityId) -> Result<()> {
+        if self.storage.get_activity_pert_id(activity_id)? != Some(pert_id) {
+            bail!("Activity {} not found in PERT {}", activity_id, pert_id);
+        }
+        Ok(())
+    }
+
     pub fn add_activity(
         &mut self,
         pert_id: PertId,
         name: String,
         estimation: Estimation,
     ) -> Result<()> {
+        estimation.validate()?;
         self.storage.add_activity(pert_id, name, estimation)?;
         Ok(())
     }
 
-    pub fn add_dependency(&mut self, head: PertId, tail: PertId) -> Result<()> {
+    /// The estimation of an activity with estimates is their aggregate, only its name
+    /// can be changed.
+    pub fn update_activity(
+        &mut self,
+        pert_id: PertId,
+        activity_id: ActivityId,
+        name: String,
+        estimation: Estimation,
+    ) -> Result<()> {
+        estimation.validate()?;
+        self.check_activity(pert_id, activity_id)?;
+        if self.has_estimates(pert_id, activity_id)? {
+            let current = self
+                .get_activities(pert_id)?
+                .into_iter()
+                .find(|act| act.id == activity_id)
+                .map(|act| act.estimation);
+            if current.as_ref() != Some(&estimation) {
+                bail!(
+                    "The estimation of activity {} is the aggregate of its estimates",
+                    activity_id
+                );
+            }
+        }
+        self.storage.update_activity(activity_id, name, estimation)
+    }
+
+    pub fn has_estimates(&mut self, pert_id: PertId, activity_id: ActivityId) -> Result<bool> {
+        Ok(self
+            .storage
+            .get_estimates(pert_id)?
+            .iter()
+            .any(|(id, _)| *id == activity_id))
+    }
+
+    pub fn set_actual(
+        &mut self,
+        pert_id: PertId,
+        activity_id: ActivityId,
+        actual: &Actual,
+    ) -> Result<()> {
+        actual.validate()?;
+        self.check_activity(pert_id, activity_id)?;
+        self.storage.set_actual(activity_id, actual)
+    }
+
+    pub fn set_progress(
+        &mut self,
+        pert_id: PertId,
+        activity_id: ActivityId,
+        progress: &Progress,
+    ) -> Result<()> {
+        progress.validate()?;
+        self.check_activity(pert_id, activity_id)?;
+        self.storage.set_progress(activity_id, progress)
+    }
+
+    /// Replaces the tags of the activity, they are sorted and duplicates are dropped.
+    pub fn set_tags(
+        &mut self,
+        pert_id: PertId,
+        activity_id: ActivityId,
+        mut tags: Vec<String>,
+    ) -> Result<()> {
+        validate_tags(&tags)?;
+        tags.sort();
+        tags.dedup();
+        self.check_activity(pert_id, activity_id)?;
+        self.storage.set_tags(activity_id, &tags)
+    }
+
+    /// Records the next round of `estimator` on the activity, whose estimation becomes
+    /// the aggregate of the last round of every estimator.
+    pub fn add_estimate(
+        &mut self,
+        pert_id: PertId,
+        activity_id: ActivityId,
+        estimator: &str,
+        estimation: Estimation,
+        confidence: f64,
+    ) -> Result<()> {
+        self.check_activity(pert_id, activity_id)?;
+        let estimator = estimator.trim();
+        let rounds = self
+            .storage
+            .get_estimates(pert_id)?
+            .iter()
+            .filter(|(id, estimate)| *id == activity_id && estimate.estimator == estimator)
+            .count() as u32;
+        let estimate = EstimatorEstimate {
+            estimator: estimator.to_string(),
+            round: rounds + 1,
+            estimation,
+            confidence,
+        };
+        estimate.validate()?;
+        self.storage.add_estimate(activity_id, &estimate)?;
+        self.aggregate_estimates(pert_id)
+    }
+
+    pub fn set_aggregation(&mut self, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+        self.existing_pert(pert_id)?;
+        self.storage.set_aggregation(pert_id, aggregation)?;
+        self.aggregate_estimates(pert_id)
+    }
+
+    /// Replaces the estimation of the activities having estimates by their aggregate.
+    fn aggregate_estimates(&mut self, pert_id: PertId) -> Result<()> {
+        let pert = self.existing_pert(pert_id)?;
+        let estimates = self.storage.get_estimates(pert_id)?;
+        for act in self.get_activities(pert_id)? {
+            let own: Vec<EstimatorEstimate> = estimates
+                .iter()
+                .filter(|(activity_id, _)| *activity_id == act.id)
+                .map(|(_, estimate)| estimate.clone())
+                .collect();
+            if let Some(estimation) = pert.aggregation.aggregate(&latest(&own)) {
+                if estimation != act.estimation {
+                    self.storage.update_activity(act.id, act.name, estimation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimates of every estimator, flagging the activities whose estimates are apart
+    /// by more than `threshold` of their aggregated expected duration.
+    pub fn get_delphi(&mut self, pert_id: PertId, threshold: f64) -> Result<Delphi> {
+        if !threshold.is_finite() || threshold < 0f64 {
+            bail!("The threshold must be zero or a positive number");
+        }
+        let pert = self.existing_pert(pert_id)?;
+        let activities = self.get_activities(pert_id)?;
+        let estimates = self.storage.get_estimates(pert_id)?;
+        Ok(Delphi::new(
+            &activities,
+            &estimates,
+            pert.aggregation,
+            threshold,
+        ))
+    }
+
+    pub fn get_delphi_reporter(&mut self, pert_id: PertId, threshold: f64) -> Result<DelphiReport> {
+        let pert = self.existing_pert(pert_id)?;
+        let delphi = self.get_delphi(pert_id, threshold)?;
+        Ok(DelphiReport::new(pert, delphi))
+    }
+
+    pub fn delete_activity(&mut self, pert_id: PertId, activity_id: ActivityId) -> Result<()> {
+        self.check_activity(pert_id, activity_id)?;
+        self.storage.delete_activity(activity_id)
+    }
+
+    pub fn delete_dependency(
+        &mut self,
+        pert_id: PertId,
+        head: ActivityId,
+        tail: ActivityId,
+    ) -> Result<()> {
+        let graph = self.get_dependency_graph(pert_id)?;
+        if !graph.predecessors(tail).contains(&head) {
+            bail!("Activity {} does not depend on {}", tail, head);
+        }
+        self.storage.delete_dependency(head, tail)
+    }
+
+    /// Adds the dependency "`tail` depends on `head`", refusing the ones that would
+    /// make the activities of the PERT impossible to schedule.
+    pub fn add_dependency(&mut self, head: ActivityId, tail: ActivityId) -> Result<()> {
+        if head == tail {
+            bail!("An activity cannot depend on itself");
+        }
+        let head_pert = self.storage.get_activity_pert_id(head)?;
+        let tail_pert = self.storage.get_activity_pert_id(tail)?;
+        let pert_id = match (head_pert, tail_pert) {
+            (None, _) => bail!("Activity {} not found", head),
+            (_, None) => bail!("Activity {} not found", tail),
+            (Some(head_pert), Some(tail_pert)) if head_pert != tail_pert => {
+                bail!("Activities {} and {} belong to different PERTs", head, tail)
+            }
+            (Some(pert_id), _) => pert_id,
+        };
+        let graph = self.get_dependency_graph(pert_id)?;
+        if let Some(mut path) = graph.path(tail, head) {
+            path.push(tail);
+            let activities = self.get_activities(pert_id)?;
+            bail!(
+                "The dependency would create the cycle {}",
+                describe_path(&path, &activities)
+            );
+        }
         self.storage.add_dependency(head, tail)?;
         Ok(())
     }
+
+    /// Cycles among the dependencies of a PERT, described by activity names.
+    pub fn get_cycles(&mut self, pert_id: PertId) -> Result<Vec<String>> {
+        let activities = self.get_activities(pert_id)?;
+        let graph = self.get_dependency_graph(pert_id)?;
+        Ok(graph
+            .cycles()
+            .iter()
+            .map(|cycle| describe_path(cycle, &activities))
+            .collect())
+    }
+
+    /// Activities whose estimation is not valid, described with the reason. Storages read
+    /// them as they are, they were saved before the estimations were validated.
+    pub fn get_invalid_activities(&mut self, pert_id: PertId) -> Result<Vec<String>> {
+        Ok(self
+            .get_activities(pert_id)?
+            .iter()
+            .filter_map(|act| {
+                act.estimation
+                    .validate()
+                    .err()
+                    .map(|error| format!("activity {} \"{}\": {}", act.id, act.name, error))
+            })
+            .collect())
+    }
+
     pub fn get_activities(&mut self, pert_id: PertId) -> Result<Vec<Activity>> {
         self.storage.get_activities(pert_id)
     }
 
+    /// Activities and dependencies of the PERT, `None` when the PERT does not exist. Unlike
+    /// the reporter, nothing is scheduled: it lists the PERTs with a cycle too.
+    pub fn get_activity_list(&mut self, pert_id: PertId) -> Result<Option<ActivityList>> {
+        if self.get_pert(pert_id)?.is_none() {
+            return Ok(None);
+        }
+        let activities = self.get_activities(pert_id)?;
+        let acts_with_deps = self
+            .storage
+            .get_activities_with_related_dependencies(pert_id)?;
+        Ok(Some(ActivityList::new(activities, &acts_with_deps)))
+    }
+
     pub fn get_activities_reporter(&mut self, pert_id: PertId) -> Result<Option<ActivityReport>> {
         let activities = self.get_activities(pert_id)?;
         let pert = self.get_pert(pert_id)?;
         match pert {
-            Some(pert) => Ok(Some(ActivityReport::new(pert, activities))),
+            Some(pert) => {
+                let graph = self.get_dependency_graph(pert_id)?;
+                let critical_path = CriticalPath::new(&activities, &graph)?;
+                let schedule = schedule(&pert, &critical_path)?;
+                let forecast = forecast(&pert, &activities, &graph)?;
+                let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+                Ok(Some(ActivityReport::new(
+                    pert,
+                    activities,
+                    critical_path,
+                    schedule,
+                    forecast,
+                    gantt,
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_dependency_graph(&mut self, pert_id: PertId) -> Result<DependencyGraph> {
+        let acts_with_deps = self
+            .storage
+            .get_activities_with_related_dependencies(pert_id)?;
+
+        Ok(DependencyGraph::new(&acts_with_deps))
+    }
+
+    pub fn get_critical_path(&mut self, pert_id: PertId) -> Result<CriticalPath> {
+        let activities = self.get_activities(pert_id)?;
+        let graph = self.get_dependency_graph(pert_id)?;
+        CriticalPath::new(&activities, &graph)
+    }
+
+    /// Planned dates of the activities, if the PERT has a start date.
+    pub fn get_schedule(&mut self, pert_id: PertId) -> Result<Option<Schedule>> {
+        match self.get_pert(pert_id)? {
+            Some(pert) => {
+                let critical_path = self.get_critical_path(pert_id)?;
+                schedule(&pert, &critical_path)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_critical_path_reporter(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Option<CriticalPathReport>> {
+        match self.get_pert(pert_id)? {
+            Some(pert) => {
+                let critical_path = self.get_critical_path(pert_id)?;
+                Ok(Some(CriticalPathReport::new(pert, critical_path)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_completion_probability(&mut self, pert_id: PertId) -> Result<CompletionProbability> {
+        let critical_path = self.get_critical_path(pert_id)?;
+        Ok(CompletionProbability::new(&critical_path))
+    }
+
+    pub fn get_probability_reporter(
+        &mut self,
+        pert_id: PertId,
+    ) -> Result<Option<ProbabilityReport>> {
+        match self.get_pert(pert_id)? {
+            Some(pert) => {
+                let probability = self.get_completion_probability(pert_id)?;
+                Ok(Some(ProbabilityReport::new(pert, probability)))
+            }
             None => Ok(None),
         }
     }
 
-    pub fn get_roadmap_reporter(&self, roadmap: Roadmap) -> RoadmapReport {
-        RoadmapReport::new(roadmap)
+    pub fn simulate(
+        &mut self,
+        pert_id: PertId,
+        simulation: &MonteCarlo,
+    ) -> Result<SimulationResult> {
+        let activities = self.get_activities(pert_id)?;
+        let graph = self.get_dependency_graph(pert_id)?;
+        simulation.run(&activities, &graph)
+    }
+
+    pub fn get_simulation_reporter(
+        &mut self,
+        pert_id: PertId,
+        simulation: &MonteCarlo,
+    ) -> Result<Option<SimulationReport>> {
+        match self.get_pert(pert_id)? {
+            Some(pert) => {
+                let result = self.simulate(pert_id, simulation)?;
+                Ok(Some(SimulationReport::new(pert, result)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_roadmap_reporter(
+        &mut self,
+        pert_id: PertId,
+        roadmap: Roadmap,
+    ) -> Result<RoadmapReport> {
+        let pert = self
+            .get_pert(pert_id)?
+            .with_context(|| format!("No PERT found with id {}", pert_id))?;
+        let activities = self.get_activities(pert_id)?;
+        let graph = self.get_dependency_graph(pert_id)?;
+        let critical_path = CriticalPath::new(&activities, &graph)?;
+        let schedule = schedule(&pert, &critical_path)?;
+        let forecast = forecast(&pert, &activities, &graph)?;
+        let gantt = GanttReport::new(&critical_path, &graph, pert.unit);
+        Ok(RoadmapReport::new(
+            pert, roadmap, activities, schedule, forecast, gantt,
+        ))
+    }
+
+    /// With `clustered` the activities are grouped by roadmap phase.
+    pub fn get_graph_reporter(
+        &mut self,
+        pert_id: PertId,
+        clustered: bool,
+    ) -> Result<Option<GraphReport>> {
+        match self.get_pert(pert_id)? {
+            Some(pert) => {
+                let activities = self.get_activities(pert_id)?;
+                let graph = self.get_dependency_graph(pert_id)?;
+                let critical_path = CriticalPath::new(&activities, &graph)?;
+                let roadmap = match clustered {
+                    true => Some(self.get_roadmap(pert_id)?),
+                    false => None,
+                };
+                Ok(Some(GraphReport::new(
+                    pert,
+                    &activities,
+                    &graph,
+                    &critical_path,
+                    roadmap,
+                )))
+            }
+            None => Ok(None),
+        }
     }
 
     pub fn get_roadmap(&mut self, pert_id: PertId) -> Result<Roadmap> {
@@ -67,10 +521,452 @@ impl Perty {
             .storage
             .get_activities_with_related_dependencies(pert_id)?;
 
-        Ok(Roadmap::new(acts_with_deps))
+        Roadmap::new(acts_with_deps)
+    }
+
+    pub fn get_accuracy_reporter(&mut self, pert_id: PertId) -> Result<AccuracyReport> {
+        let pert = self.existing_pert(pert_id)?;
+        let activities = self.get_activities(pert_id)?;
+        Ok(AccuracyReport::new(pert, Accuracy::new(&activities)))
+    }
+
+    /// Accuracy of every PERT, to learn from all the finished work.
+    pub fn get_accuracy_overview_reporter(&mut self) -> Result<AccuracyOverviewReport> {
+        let mut perts = vec![];
+        for pert in self.get_perts()? {
+            let activities = self.get_activities(pert.id)?;
+            perts.push((pert, Accuracy::new(&activities)));
+        }
+        Ok(AccuracyOverviewReport::new(perts))
+    }
+
+    /// Current state of the PERT, as it would be kept in a baseline.
+    pub fn get_snapshot(&mut self, pert_id: PertId) -> Result<Snapshot> {
+        let pert = self.existing_pert(pert_id)?;
+        let activities = self.get_activities(pert_id)?;
+        let acts_with_deps = self
+            .storage
+            .get_activities_with_related_dependencies(pert_id)?;
+        Snapshot::new(&pert, &activities, &acts_with_deps)
+    }
+
+    pub fn add_baseline(&mut self, pert_id: PertId, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("The name cannot be empty");
+        }
+        if name == CURRENT {
+            bail!(
+                "\"{}\" is reserved for the current state of the PERT",
+                CURRENT
+            );
+        }
+        let baseline = Baseline {
+            name: name.to_string(),
+            created_at: Local::now().naive_local().with_nanosecond(0).unwrap(),
+            snapshot: self.get_snapshot(pert_id)?,
+        };
+        self.storage.add_baseline(pert_id, &baseline)
+    }
+
+    pub fn get_baselines(&mut self, pert_id: PertId) -> Result<Vec<Baseline>> {
+        self.existing_pert(pert_id)?;
+        self.storage.get_baselines(pert_id)
+    }
+
+    /// Changes from the baseline `from` to the baseline `to`, or to the current state
+    /// of the PERT without `to`.
+    pub fn get_baseline_diff(
+        &mut self,
+        pert_id: PertId,
+        from: &str,
+        to: Option<&str>,
+    ) -> Result<BaselineDiff> {
+        let baselines = self.get_baselines(pert_id)?;
+        let find = |name: &str| -> Result<Snapshot> {
+            baselines
+                .iter()
+                .find(|baseline| baseline.name == name)
+                .map(|baseline| baseline.snapshot.clone())
+                .with_context(|| format!("No baseline {} found in PERT {}", name, pert_id))
+        };
+        let before = find(from)?;
+        let (to, after) = match to {
+            Some(to) => (to, find(to)?),
+            None => (CURRENT, self.get_snapshot(pert_id)?),
+        };
+        Ok(BaselineDiff::new(from, &before, to, &after))
+    }
+
+    pub fn get_baseline_diff_reporter(
+        &mut self,
+        pert_id: PertId,
+        from: &str,
+        to: Option<&str>,
+    ) -> Result<BaselineDiffReport> {
+        let pert = self.existing_pert(pert_id)?;
+        let diff = self.get_baseline_diff(pert_id, from, to)?;
+        Ok(BaselineDiffReport::new(pert, diff))
+    }
+
+    pub fn schema_version(&mut self) -> Result<Option<i32>> {
+        self.storage.schema_version()
+    }
+
+    pub fn migrate(&mut self) -> Result<Vec<String>> {
+        self.storage.migrate()
+    }
+
+    pub fn migrated_on_open(&self) -> Option<&[String]> {
+        self.storage.migrated_on_open()
     }
+
     pub fn create_board(&mut self, pert_id: PertId, github_board_id: BoardId) -> Result<()> {
         self.storage.create_board(pert_id, github_board_id)?;
         Ok(())
     }
 }
+
+fn schedule(pert: &Pert, critical_path: &CriticalPath) -> Result<Option<Schedule>> {
+    match pert.start_date {
+        Some(start_date) => {
+            let calendar = Calendar::new(pert, load_holidays()?)?;
+            Ok(Some(Schedule::new(start_date, critical_path, &calendar)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Completion of the remaining work. A scheduled PERT resumes today, or on its start
+/// date when it has not started yet.
+fn forecast(pert: &Pert, activities: &[Activity], graph: &DependencyGraph) -> Result<Forecast> {
+    match pert.start_date {
+        Some(start_date) => {
+            let calendar = Calendar::new(pert, load_holidays()?)?;
+            let from = start_date.max(Local::now().date_naive());
+            Forecast::new(activities, graph, Some((from, &calendar)))
+        }
+        None => Forecast::new(activities, graph, None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Perty;
+    use crate::modules::{
+        activity::{ActivityId, Actual, Estimation, Progress, Status},
+        delphi::Aggregation,
+        duration::DurationUnit,
+        estimation_model::EstimationModel,
+        pert::PertId,
+        storage::MemoryStorage,
+    };
+    use chrono::{NaiveDate, Weekday};
+
+    fn example_perty(names: &[&str]) -> (Perty, PertId, Vec<ActivityId>) {
+        let mut perty = Perty::new(Box::new(MemoryStorage::new()));
+        let pert_id = perty
+            .add_pert(
+                "example".to_string(),
+                EstimationModel::Classic,
+                DurationUnit::Days,
+            )
+            .unwrap();
+        for name in names {
+            let estimation = Estimation::new(1.0, 2.0, 3.0).unwrap();
+            perty
+                .add_activity(pert_id, name.to_string(), estimation)
+                .unwrap();
+        }
+        let ids = perty
+            .get_activities(pert_id)
+            .unwrap()
+            .iter()
+            .map(|act| act.id)
+            .collect();
+        (perty, pert_id, ids)
+    }
+
+    #[test]
+    fn add_dependency_refuses_cycles() {
+        let (mut perty, _, ids) = example_perty(&["first", "second", "third"]);
+        perty.add_dependency(ids[0], ids[1]).unwrap();
+        perty.add_dependency(ids[1], ids[2]).unwrap();
+
+        assert!(perty.add_dependency(ids[0], ids[0]).is_err());
+        let error = perty.add_dependency(ids[2], ids[0]).unwrap_err();
+        assert!(error.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn remove_dependency_of_a_cycle() {
+        let (mut perty, pert_id, ids) = example_perty(&["a", "b"]);
+        perty.add_dependency(ids[0], ids[1]).unwrap();
+        // left by a version that did not refuse cycles
+        perty.storage.add_dependency(ids[1], ids[0]).unwrap();
+        assert_eq!(perty.get_cycles(pert_id).unwrap().len(), 1);
+        assert!(perty.get_activities_reporter(pert_id).is_err());
+
+        let list = perty.get_activity_list(pert_id).unwrap().unwrap();
+        assert_eq!(list.activities.len(), 2);
+        assert!(list.ascii().contains("\"a\" depends on \"b\""));
+        let ui = list.with_tag(Some("ui".to_string()));
+        assert!(ui.activities.is_empty() && !ui.ascii().contains("depends on"));
+        perty.delete_dependency(pert_id, ids[1], ids[0]).unwrap();
+        assert!(perty.get_cycles(pert_id).unwrap().is_empty());
+        assert!(perty.get_activities_reporter(pert_id).is_ok());
+    }
+
+    #[test]
+    fn hours_per_day_are_validated() {
+        let (mut perty, pert_id, _) = example_perty(&[]);
+        for hours_per_day in [0.0, -1.0, 24.5, f64::NAN, f64::INFINITY] {
+            assert!(perty.set_hours_per_day(pert_id, hours_per_day).is_err());
+        }
+        perty.set_hours_per_day(pert_id, 7.5).unwrap();
+        assert_eq!(perty.get_pert(pert_id).unwrap().unwrap().hours_per_day, 7.5);
+    }
+
+    #[test]
+    fn settings_of_an_unknown_pert_are_refused() {
+        let (mut perty, pert_id, _) = example_perty(&[]);
+        let unknown = pert_id + 1;
+        assert!(perty
+            .set_estimation_model(unknown, EstimationModel::Triangular)
+            .is_err());
+        assert!(perty.set_hours_per_day(unknown, 7.5).is_err());
+        assert!(perty
+            .set_start_date(unknown, NaiveDate::from_ymd_opt(2022, 6, 1))
+            .is_err());
+        assert!(perty.set_working_days(unknown, &[Weekday::Mon]).is_err());
+    }
+
+    #[test]
+    fn add_dependency_refuses_other_perts() {
+        let (mut perty, _, ids) = example_perty(&["first"]);
+        let other_pert_id = perty
+            .add_pert(
+                "other".to_string(),
+                EstimationModel::Classic,
+                DurationUnit::Days,
+            )
+            .unwrap();
+        let estimation = Estimation::new(1.0, 1.0, 1.0).unwrap();
+        perty
+            .add_activity(other_pert_id, "other".to_string(), estimation)
+            .unwrap();
+        let other_id = perty.get_activities(other_pert_id).unwrap()[0].id;
+
+        assert!(perty.add_dependency(ids[0], other_id).is_err());
+    }
+
+    #[test]
+    fn roadmap_and_schedule() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        perty.add_dependency(ids[0], ids[1]).unwrap();
+        assert_eq!(perty.get_roadmap(pert_id).unwrap().phases.len(), 2);
+        assert!(perty.get_schedule(pert_id).unwrap().is_none());
+
+        perty
+            .set_start_date(pert_id, NaiveDate::from_ymd_opt(2022, 6, 1))
+            .unwrap();
+        let schedule = perty.get_schedule(pert_id).unwrap().unwrap();
+        assert_eq!(schedule.start, NaiveDate::from_ymd_opt(2022, 6, 1).unwrap());
+        assert_eq!(
+            schedule.finish,
+            NaiveDate::from_ymd_opt(2022, 6, 6).unwrap()
+        );
+    }
+
+    #[test]
+    fn rename_and_delete_pert() {
+        let (mut perty, pert_id, _) = example_perty(&["first"]);
+        assert!(perty.rename_pert(pert_id, " ").is_err());
+        perty.rename_pert(pert_id, " renamed ").unwrap();
+        assert_eq!(perty.get_pert(pert_id).unwrap().unwrap().name, "renamed");
+
+        perty.delete_pert(pert_id).unwrap();
+        assert!(perty.get_pert(pert_id).unwrap().is_none());
+        assert!(perty.delete_pert(pert_id).is_err());
+    }
+
+    #[test]
+    fn activities_are_changed_in_their_pert_only() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        let estimation = Estimation::new(1.0, 1.0, 1.0).unwrap();
+        assert!(perty
+            .update_activity(pert_id + 1, ids[0], "moved".to_string(), estimation)
+            .is_err());
+        assert!(perty.delete_activity(pert_id + 1, ids[0]).is_err());
+
+        let estimation = Estimation::new(1.0, 1.0, 1.0).unwrap();
+        perty
+            .update_activity(pert_id, ids[0], "updated".to_string(), estimation)
+            .unwrap();
+        perty.delete_activity(pert_id, ids[1]).unwrap();
+        let activities = perty.get_activities(pert_id).unwrap();
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].name, "updated");
+    }
+
+    #[test]
+    fn delete_dependency_must_exist() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        perty.add_dependency(ids[0], ids[1]).unwrap();
+        assert!(perty.delete_dependency(pert_id, ids[1], ids[0]).is_err());
+
+        perty.delete_dependency(pert_id, ids[0], ids[1]).unwrap();
+        let graph = perty.get_dependency_graph(pert_id).unwrap();
+        assert!(graph.predecessors(ids[1]).is_empty());
+    }
+
+    #[test]
+    fn baselines_are_compared() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        perty.add_dependency(ids[0], ids[1]).unwrap();
+        assert!(perty.add_baseline(pert_id, " ").is_err());
+        assert!(perty.add_baseline(pert_id, "current").is_err());
+        assert!(perty.add_baseline(pert_id + 1, "v1").is_err());
+        perty.add_baseline(pert_id, " v1 ").unwrap();
+        assert!(perty.add_baseline(pert_id, "v1").is_err());
+
+        let estimation = Estimation::new(2.0, 3.0, 4.0).unwrap();
+        perty
+            .update_activity(pert_id, ids[1], "second".to_string(), estimation)
+            .unwrap();
+        let diff = perty.get_baseline_diff(pert_id, "v1", None).unwrap();
+        assert_eq!(diff.to, "current");
+        assert_eq!(diff.activities.len(), 1);
+        assert_eq!(diff.total_delta(), 1.0);
+
+        perty.add_baseline(pert_id, "v2").unwrap();
+        let diff = perty.get_baseline_diff(pert_id, "v2", Some("v1")).unwrap();
+        assert_eq!(diff.total_delta(), -1.0);
+        assert!(perty.get_baseline_diff(pert_id, "v3", None).is_err());
+        assert_eq!(perty.get_baselines(pert_id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn actuals_are_recorded_in_their_pert_only() {
+        let (mut perty, pert_id, ids) = example_perty(&["first"]);
+        let actual = Actual {
+            effort: Some(3.0),
+            ..Actual::default()
+        };
+        assert!(perty.set_actual(pert_id + 1, ids[0], &actual).is_err());
+        perty.set_actual(pert_id, ids[0], &actual).unwrap();
+        assert_eq!(perty.get_activities(pert_id).unwrap()[0].actual, actual);
+    }
+
+    #[test]
+    fn progress_reduces_the_remaining_work() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        let progress = Progress::new(Status::InProgress, 50.0).unwrap();
+        assert!(perty.set_progress(pert_id + 1, ids[0], &progress).is_err());
+        perty.set_progress(pert_id, ids[0], &progress).unwrap();
+        assert_eq!(perty.get_activities(pert_id).unwrap()[0].progress, progress);
+
+        let json = perty
+            .get_activities_reporter(pert_id)
+            .unwrap()
+            .unwrap()
+            .pert_detail()
+            .unwrap()
+            .json()
+            .unwrap();
+        assert!(json.contains("\"status\": \"in-progress\""));
+    }
+
+    #[test]
+    fn estimates_are_aggregated() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        let estimation = |probable| Estimation::new(1.0, probable, 10.0).unwrap();
+        assert!(perty
+            .add_estimate(pert_id + 1, ids[0], "ann", estimation(2.0), 1.0)
+            .is_err());
+        assert!(perty
+            .add_estimate(pert_id, ids[0], "ann", estimation(2.0), 0.0)
+            .is_err());
+        perty
+            .add_estimate(pert_id, ids[0], "ann", estimation(4.0), 1.0)
+            .unwrap();
+        perty
+            .add_estimate(pert_id, ids[0], "bob", estimation(5.0), 1.0)
+            .unwrap();
+        perty
+            .add_estimate(pert_id, ids[0], "eve", estimation(9.0), 0.5)
+            .unwrap();
+        let probable = |perty: &mut Perty| {
+            perty.get_activities(pert_id).unwrap()[0]
+                .estimation
+                .probable
+        };
+        assert_eq!(probable(&mut perty), 6.0);
+
+        perty.set_aggregation(pert_id, Aggregation::Median).unwrap();
+        assert_eq!(probable(&mut perty), 5.0);
+        let delphi = perty.get_delphi(pert_id, 0.25).unwrap();
+        assert_eq!(delphi.needing_another_round().len(), 1);
+        for threshold in [-0.25, f64::NAN, f64::INFINITY] {
+            assert!(perty.get_delphi(pert_id, threshold).is_err());
+        }
+
+        // a second round of eve replaces the first one
+        perty
+            .add_estimate(pert_id, ids[0], " eve ", estimation(5.0), 1.0)
+            .unwrap();
+        assert_eq!(probable(&mut perty), 5.0);
+        let delphi = perty.get_delphi(pert_id, 0.25).unwrap();
+        assert_eq!(delphi.activities[0].estimates[2].round, 2);
+        assert!(delphi.needing_another_round().is_empty());
+        // the activities without estimates keep their estimation
+        assert_eq!(
+            perty.get_activities(pert_id).unwrap()[1]
+                .estimation
+                .probable,
+            2.0
+        );
+
+        // the aggregate is not overwritten by hand, the activity can still be renamed
+        assert!(perty
+            .update_activity(pert_id, ids[0], "first".to_string(), estimation(7.0))
+            .is_err());
+        perty
+            .update_activity(pert_id, ids[0], "renamed".to_string(), estimation(5.0))
+            .unwrap();
+        let activity = &perty.get_activities(pert_id).unwrap()[0];
+        assert_eq!(activity.name, "renamed");
+        assert_eq!(activity.estimation.probable, 5.0);
+    }
+
+    #[test]
+    fn tags_filter_the_activities() {
+        let (mut perty, pert_id, ids) = example_perty(&["first", "second"]);
+        let tags = vec!["ui".to_string(), "backend".to_string(), "ui".to_string()];
+        assert!(perty.set_tags(pert_id + 1, ids[0], tags.clone()).is_err());
+        assert!(perty
+            .set_tags(pert_id, ids[1], vec!["a,b".to_string()])
+            .is_err());
+        perty.set_tags(pert_id, ids[0], tags).unwrap();
+        assert_eq!(
+            perty.get_activities(pert_id).unwrap()[0].tags,
+            vec!["backend".to_string(), "ui".to_string()]
+        );
+
+        let list = perty
+            .get_activity_list(pert_id)
+            .unwrap()
+            .unwrap()
+            .with_tag(Some("ui".to_string()))
+            .table();
+        assert!(list.contains("first") && !list.contains("second"));
+        let reporter = perty
+            .get_activities_reporter(pert_id)
+            .unwrap()
+            .unwrap()
+            .with_tag(Some("ui".to_string()));
+        let json = reporter.pert_detail().unwrap().json().unwrap();
+        assert!(json.contains("\"tag\": \"ui\""));
+        assert!(!json.contains("second"));
+    }
+}
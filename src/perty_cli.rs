@@ -1,14 +1,25 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::{NaiveDate, Weekday};
 use std::fs::File;
 use std::io;
 use std::io::Write;
 
 use crate::{
     modules::{
-        activity::Estimation,
+        activity::{
+            parse_tags, validate_tags, Activity, ActivityId, Actual, Estimation, Progress, Status,
+        },
         activity_report::list_view,
+        baseline_report::baselines_view,
+        calendar::weekdays_name,
+        delphi::Aggregation,
+        duration::{Duration, DurationUnit},
+        estimation_model::EstimationModel,
         github::{get_owner_repo_from_url, Github},
+        graph_report::GraphFormat,
+        json_report::list_json,
         pert::PertId,
+        simulation::MonteCarlo,
     },
     perty::Perty,
 };
@@ -17,6 +28,10 @@ pub enum Output {
     Console,
     HTML,
     CSV,
+    SVG,
+    JSON,
+    /// Printed to stdout, or saved in the given file.
+    Markdown(Option<String>),
 }
 
 fn create_file(file_name: String, content: String) -> Result<String> {
@@ -26,8 +41,7 @@ fn create_file(file_name: String, content: String) -> Result<String> {
     Ok(file_path.to_str().unwrap().to_string())
 }
 
-fn file_in_browser(pert_id: PertId, content: String) -> Result<()> {
-    let file_name = format!("report-{}.html", pert_id);
+fn file_in_browser(file_name: String, content: String) -> Result<()> {
     let file_path = create_file(file_name, content)?;
     let file_path_url = format!("file://{}", file_path);
     webbrowser::open(&file_path_url).expect("Unable to open browser");
@@ -36,127 +50,818 @@ fn file_in_browser(pert_id: PertId, content: String) -> Result<()> {
     Ok(())
 }
 
+fn save_gantt(pert_id: PertId, content: String) -> Result<()> {
+    let file_name = format!("gantt-{}.svg", pert_id);
+    let file_path = create_file(file_name, content)?;
+    println!("Gantt chart saved in {}", file_path);
+    Ok(())
+}
+
+fn print_or_save(file_name: Option<String>, content: String) -> Result<()> {
+    match file_name {
+        Some(file_name) => {
+            let file_path = create_file(file_name, content)?;
+            eprintln!("Report saved in {}", file_path);
+        }
+        None => println!("{}", content),
+    }
+    Ok(())
+}
+
 pub fn read_input() -> Result<String> {
     let mut buffer = String::new();
-    io::stdin().read_line(&mut buffer)?;
+    if io::stdin().read_line(&mut buffer)? == 0 {
+        // no more input: asking again would loop forever
+        bail!("Unexpected end of input");
+    }
     Ok(buffer.trim().to_string())
 }
 
+/// Asks again until `parse` accepts the input.
+fn read_valid_input<T>(prompt: &str, parse: impl Fn(&str) -> Result<T>) -> Result<T> {
+    loop {
+        println!("{}", prompt);
+        match parse(&read_input()?) {
+            Ok(value) => return Ok(value),
+            Err(error) => println!("{}, please retry.", error),
+        }
+    }
+}
+
+/// Asks "Are you sure? Y/N" until the answer is one of them.
+fn confirm() -> Result<bool> {
+    read_valid_input("Are you sure? Y/N", |input| match input {
+        "Y" => Ok(true),
+        "N" => Ok(false),
+        _ => bail!("Unknown input {}", input),
+    })
+}
+
+/// Prints the activities of the PERT and asks the ID of one of them, `None` when the
+/// PERT does not exist.
+fn select_activity(perty: &mut Perty, pert_id: PertId, prompt: &str) -> Result<Option<Activity>> {
+    let list = match perty.get_activity_list(pert_id)? {
+        Some(list) => list,
+        None => {
+            println!("No PERT found with id {}", pert_id);
+            return Ok(None);
+        }
+    };
+    println!("{}", list.ascii());
+    let mut activities = list.activities;
+    let index = read_valid_input(prompt, |input| {
+        let id: ActivityId = input.parse()?;
+        match activities.iter().position(|act| act.id == id) {
+            Some(index) => Ok(index),
+            None => bail!("No activity found with id {}", id),
+        }
+    })?;
+    Ok(Some(activities.swap_remove(index)))
+}
+
+fn read_estimation_model() -> Result<EstimationModel> {
+    let model = read_valid_input(
+        "Estimation model (classic, triangular, modified-pert, pessimism-weighted): [classic]",
+        |input| match input {
+            "" => Ok(EstimationModel::default()),
+            name => EstimationModel::from_name(name, None),
+        },
+    )?;
+    if !matches!(model, EstimationModel::ModifiedPert { .. }) {
+        return Ok(model);
+    }
+    read_valid_input("Lambda: [4]", |input| match input {
+        "" => Ok(model),
+        lambda => EstimationModel::from_name(model.name(), Some(lambda.parse()?)),
+    })
+}
+
+fn read_unit() -> Result<DurationUnit> {
+    read_valid_input(
+        "Unit (hours, days, weeks, points): [days]",
+        |input| match input {
+            "" => Ok(DurationUnit::default()),
+            name => DurationUnit::from_name(name),
+        },
+    )
+}
+
 pub fn create_pert(mut perty: Perty) -> Result<()> {
     println!("Name: ");
     let pert_name = read_input()?;
+    let estimation_model = read_estimation_model()?;
+    let unit = read_unit()?;
     println!("Creating PERT {}...", pert_name);
-    let pert_id = perty.add_pert(pert_name).unwrap();
+    let pert_id = perty.add_pert(pert_name, estimation_model, unit)?;
     println!("Created {}", pert_id);
     Ok(())
 }
 
-pub fn list_perts(perty: &mut Perty) -> Result<()> {
-    println!("Getting list of PERTs...");
+pub fn set_estimation_model(
+    mut perty: Perty,
+    pert_id: PertId,
+    estimation_model: EstimationModel,
+) -> Result<()> {
+    if perty.get_pert(pert_id)?.is_none() {
+        println!("No PERT found with id {}", pert_id);
+        return Ok(());
+    }
+    perty.set_estimation_model(pert_id, estimation_model)?;
+    println!("Estimation model set to {}", estimation_model);
+    Ok(())
+}
+
+pub fn list_perts(perty: &mut Perty, output: Output) -> Result<()> {
+    eprintln!("Getting list of PERTs...");
     let perts = perty.get_perts()?;
-    println!("{}", list_view(perts));
+    match output {
+        Output::JSON => println!("{}", list_json(&perts)?),
+        _ => println!("{}", list_view(perts)),
+    }
     Ok(())
 }
 
-pub fn get_pert(mut perty: Perty, pert_id: PertId, output: Output) -> Result<()> {
-    println!("Getting list of PERTs...");
-    if let Some(report) = perty.get_activities_reporter(pert_id)? {
-        match output {
-            Output::Console => println!("{}", report.pert_detail().ascii()),
-            Output::HTML => {
-                file_in_browser(pert_id, report.pert_detail().html())?;
-            }
-            Output::CSV => {
-                let file_name = format!("report-{}.csv", pert_id);
-                create_file(file_name, report.pert_detail().csv())?;
-            }
-        };
-    } else {
+pub fn set_hours_per_day(mut perty: Perty, pert_id: PertId, hours_per_day: f64) -> Result<()> {
+    if perty.get_pert(pert_id)?.is_none() {
         println!("No PERT found with id {}", pert_id);
+        return Ok(());
     }
+    perty.set_hours_per_day(pert_id, hours_per_day)?;
+    println!("Hours per day set to {}", hours_per_day);
     Ok(())
 }
 
-pub fn add_dependency(mut perty: Perty, pert_id: PertId) -> Result<()> {
-    let reporter = perty.get_activities_reporter(pert_id)?;
-    if reporter.is_none() {
+pub fn set_start_date(
+    mut perty: Perty,
+    pert_id: PertId,
+    start_date: Option<NaiveDate>,
+) -> Result<()> {
+    if perty.get_pert(pert_id)?.is_none() {
         println!("No PERT found with id {}", pert_id);
         return Ok(());
     }
-    println!("{}", reporter.unwrap().list_activities());
-    println!("\"A\" depends on \"B\"");
-    println!("Insert the ID of activity \"A\":");
-    let activities = perty.get_activities(pert_id)?;
+    perty.set_start_date(pert_id, start_date)?;
+    match start_date {
+        Some(start_date) => println!("Start date set to {}", start_date),
+        None => println!("Start date removed"),
+    }
+    Ok(())
+}
+
+pub fn set_working_days(
+    mut perty: Perty,
+    pert_id: PertId,
+    working_days: Vec<Weekday>,
+) -> Result<()> {
+    if perty.get_pert(pert_id)?.is_none() {
+        println!("No PERT found with id {}", pert_id);
+        return Ok(());
+    }
+    perty.set_working_days(pert_id, &working_days)?;
+    println!("Working days set to {}", weekdays_name(&working_days));
+    Ok(())
+}
+
+pub fn check(perty: &mut Perty) -> Result<()> {
+    println!("Checking dependencies and estimations of every PERT...");
+    let mut found = false;
+    for pert in perty.get_perts()? {
+        for cycle in perty.get_cycles(pert.id)? {
+            found = true;
+            println!("PERT {} ({}): cycle {}", pert.id, pert.name, cycle);
+        }
+        for invalid in perty.get_invalid_activities(pert.id)? {
+            found = true;
+            println!("PERT {} ({}): {}", pert.id, pert.name, invalid);
+        }
+    }
+    if !found {
+        println!("No cycles or invalid estimations found");
+    }
+    Ok(())
+}
+
+/// For storages that create and migrate their schema when they are opened, with what
+/// the opening did.
+fn report_migrated_on_open(perty: &mut Perty) -> Result<bool> {
+    let applied = match perty.migrated_on_open() {
+        Some(applied) => applied.to_vec(),
+        None => return Ok(false),
+    };
+    println!("This database is created and migrated when it is opened");
+    for migration in applied {
+        println!("Applied migration {}", migration);
+    }
+    if let Some(version) = perty.schema_version()? {
+        println!("Schema at version {}", version);
+    }
+    Ok(true)
+}
 
-    let tail_id: PertId = read_input()?.parse()?;
-    let tail = activities
-        .iter()
-        .find(|act| (*act).id == tail_id)
-        .expect("Unable to find activity A");
-    let tail_name = &tail.name;
-    println!("Insert the ID of activity \"B\":");
-    let head_id: PertId = read_input()?.parse()?;
-    let head = activities
-        .iter()
-        .find(|act| act.id == head_id)
-        .expect("Unable to find activity B");
-    let head_name = &head.name;
+pub fn init_database(perty: &mut Perty) -> Result<()> {
+    if report_migrated_on_open(perty)? {
+        return Ok(());
+    }
+    if let Some(version) = perty.schema_version()? {
+        println!(
+            "Database already initialized at version {}, use `db migrate` to update it",
+            version
+        );
+        return Ok(());
+    }
+    let applied = perty.migrate()?;
+    if applied.is_empty() {
+        println!("This storage has no schema to create");
+    } else {
+        println!("Schema created ({})", applied.join(", "));
+    }
+    Ok(())
+}
+
+pub fn migrate_database(perty: &mut Perty) -> Result<()> {
+    if report_migrated_on_open(perty)? {
+        return Ok(());
+    }
+    let applied = perty.migrate()?;
+    if applied.is_empty() {
+        println!("Database schema is up to date");
+    }
+    for migration in applied {
+        println!("Applied migration {}", migration);
+    }
+    Ok(())
+}
+
+/// `tag` keeps the activities with this tag only.
+pub fn get_pert(
+    mut perty: Perty,
+    pert_id: PertId,
+    output: Output,
+    tag: Option<String>,
+) -> Result<()> {
+    eprintln!("Getting PERT {}...", pert_id);
+    let report = match perty.get_activities_reporter(pert_id)? {
+        Some(report) => report.with_tag(tag),
+        // on stderr, so that stdout stays valid JSON or Markdown
+        None => bail!("No PERT found with id {}", pert_id),
+    };
+    let mut report = report.pert_detail()?;
+    match output {
+        Output::Console => println!("{}", report.ascii()),
+        Output::HTML => {
+            let file_name = format!("report-{}.html", pert_id);
+            file_in_browser(file_name, report.html()?)?;
+        }
+        Output::CSV => {
+            let file_name = format!("report-{}.csv", pert_id);
+            create_file(file_name, report.csv())?;
+        }
+        Output::SVG => save_gantt(pert_id, report.svg())?,
+        Output::JSON => println!("{}", report.json()?),
+        Output::Markdown(file_name) => print_or_save(file_name, report.markdown())?,
+    };
+    Ok(())
+}
+
+pub fn list_activities(mut perty: Perty, pert_id: PertId, tag: Option<String>) -> Result<()> {
+    match perty.get_activity_list(pert_id)? {
+        Some(list) => println!("{}", list.with_tag(tag).table()),
+        None => println!("No PERT found with id {}", pert_id),
+    }
+    Ok(())
+}
+
+pub fn get_critical_path(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    println!("Calculating critical path for PERT {}", pert_id);
+    match perty.get_critical_path_reporter(pert_id)? {
+        Some(mut report) => println!("{}", report.ascii()),
+        None => println!("No PERT found with id {}", pert_id),
+    }
+    Ok(())
+}
+
+pub fn get_probability(
+    mut perty: Perty,
+    pert_id: PertId,
+    deadline: f64,
+    confidence: f64,
+) -> Result<()> {
+    println!("Calculating completion probability for PERT {}", pert_id);
+    match perty.get_probability_reporter(pert_id)? {
+        Some(mut report) => println!("{}", report.ascii(deadline, confidence)?),
+        None => println!("No PERT found with id {}", pert_id),
+    }
+    Ok(())
+}
+
+pub fn get_graph(
+    mut perty: Perty,
+    pert_id: PertId,
+    format: GraphFormat,
+    clustered: bool,
+) -> Result<()> {
+    match perty.get_graph_reporter(pert_id, clustered)? {
+        Some(report) => println!("{}", report.render(format)),
+        None => println!("No PERT found with id {}", pert_id),
+    }
+    Ok(())
+}
+
+pub fn simulate(mut perty: Perty, pert_id: PertId, simulation: MonteCarlo) -> Result<()> {
+    println!(
+        "Running {} simulations for PERT {}",
+        simulation.iterations, pert_id
+    );
+    match perty.get_simulation_reporter(pert_id, &simulation)? {
+        Some(mut report) => println!("{}", report.ascii()),
+        None => println!("No PERT found with id {}", pert_id),
+    }
+    Ok(())
+}
+
+pub fn add_dependency(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    println!("\"A\" depends on \"B\"");
+    let tail = match select_activity(&mut perty, pert_id, "Insert the ID of activity \"A\":")? {
+        Some(tail) => tail,
+        None => return Ok(()),
+    };
+    let head = match select_activity(&mut perty, pert_id, "Insert the ID of activity \"B\":")? {
+        Some(head) => head,
+        None => return Ok(()),
+    };
 
     println!(
         "You are adding the following dependencies: \"{}\" depends on \"{}\"",
-        tail_name, head_name
+        tail.name, head.name
     );
-    println!("Are you sure? Y/N");
-    match read_input()?.as_str() {
-        "Y" => {
-            perty.add_dependency(head_id, tail_id)?;
-            println!("Dependency added");
+    if !confirm()? {
+        println!("The dependency has NOT been added.");
+        return Ok(());
+    }
+    match perty.add_dependency(head.id, tail.id) {
+        Ok(()) => println!("Dependency added"),
+        Err(error) => println!("The dependency has NOT been added: {}", error),
+    }
+    Ok(())
+}
+
+pub fn remove_dependency(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    println!("\"A\" depends on \"B\"");
+    let tail = match select_activity(&mut perty, pert_id, "Insert the ID of activity \"A\":")? {
+        Some(tail) => tail,
+        None => return Ok(()),
+    };
+    let head = match select_activity(&mut perty, pert_id, "Insert the ID of activity \"B\":")? {
+        Some(head) => head,
+        None => return Ok(()),
+    };
+
+    println!(
+        "You are removing the following dependency: \"{}\" depends on \"{}\"",
+        tail.name, head.name
+    );
+    if !confirm()? {
+        println!("The dependency has NOT been removed.");
+        return Ok(());
+    }
+    match perty.delete_dependency(pert_id, head.id, tail.id) {
+        Ok(()) => println!("Dependency removed"),
+        Err(error) => println!("The dependency has NOT been removed: {}", error),
+    }
+    Ok(())
+}
+
+pub fn update_activity(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    let pert = perty.get_pert(pert_id)?.unwrap();
+    let read_duration = |prompt: &str, current: f64| -> Result<f64> {
+        read_valid_input(&format!("{} [{}]", prompt, current), |input| {
+            if input.is_empty() {
+                return Ok(current);
+            }
+            let duration = Duration::parse(input, pert.unit, pert.hours_per_day)?;
+            Ok(duration.value)
+        })
+    };
+    println!(
+        "Press enter to keep the current value, default unit is {}",
+        pert.unit
+    );
+    println!("Activity: [{}]", activity.name);
+    let name = match read_input()? {
+        name if name.is_empty() => activity.name.to_owned(),
+        name => name,
+    };
+    let current = &activity.estimation;
+    let estimation = if perty.has_estimates(pert_id, activity.id)? {
+        println!("The estimation is the aggregate of the estimates, add one to change it");
+        current.clone()
+    } else {
+        loop {
+            let optimistic = read_duration("Optimistic:", current.optimistic)?;
+            let probable = read_duration("Most probable:", current.probable)?;
+            let pessimistic = read_duration("Pessimistic:", current.pessimistic)?;
+            match Estimation::new(optimistic, probable, pessimistic) {
+                Ok(estimation) => break estimation,
+                Err(error) => println!("{}, please retry.", error),
+            }
         }
-        "N" => {
-            println!("The dependency has NOT been added.");
+    };
+
+    println!(
+        "You are updating \"{}\" to \"{}\" ({} / {} / {} {})",
+        activity.name,
+        name,
+        estimation.optimistic,
+        estimation.probable,
+        estimation.pessimistic,
+        pert.unit
+    );
+    if !confirm()? {
+        println!("The activity has NOT been updated.");
+        return Ok(());
+    }
+    perty.update_activity(pert_id, activity.id, name, estimation)?;
+    println!("Activity updated");
+    Ok(())
+}
+
+/// Enter keeps the current date, `none` clears it.
+fn read_date(prompt: &str, current: Option<NaiveDate>) -> Result<Option<NaiveDate>> {
+    let current_name = current.map_or("none".to_string(), |date| date.to_string());
+    read_valid_input(
+        &format!("{} (YYYY-MM-DD) [{}]", prompt, current_name),
+        |input| match input {
+            "" => Ok(current),
+            "none" => Ok(None),
+            date => Ok(Some(NaiveDate::parse_from_str(date, "%Y-%m-%d")?)),
+        },
+    )
+}
+
+pub fn update_actual(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    let pert = perty.get_pert(pert_id)?.unwrap();
+    println!(
+        "Press enter to keep the current value, none to clear it, default unit is {}",
+        pert.unit
+    );
+    let current = &activity.actual;
+    let actual = loop {
+        let start = read_date("Actual start:", current.start)?;
+        let finish = read_date("Actual finish:", current.finish)?;
+        let current_effort = current
+            .effort
+            .map_or("none".to_string(), |effort| effort.to_string());
+        let effort =
+            read_valid_input(
+                &format!("Actual effort: [{}]", current_effort),
+                |input| match input {
+                    "" => Ok(current.effort),
+                    "none" => Ok(None),
+                    input => Ok(Some(
+                        Duration::parse(input, pert.unit, pert.hours_per_day)?.value,
+                    )),
+                },
+            )?;
+        let actual = Actual {
+            start,
+            finish,
+            effort,
+        };
+        match actual.validate() {
+            Ok(()) => break actual,
+            Err(error) => println!("{}, please retry.", error),
         }
-        _ => {
-            panic!("Unknown input, let's do nothing... Retry");
+    };
+
+    perty.set_actual(pert_id, activity.id, &actual)?;
+    println!("Actuals of \"{}\" recorded", activity.name);
+    Ok(())
+}
+
+pub fn update_status(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    let current = activity.progress;
+    let names: Vec<&str> = Status::ALL.iter().map(|status| status.name()).collect();
+    let status = read_valid_input(
+        &format!(
+            "Status, one of {}: [{}]",
+            names.join(", "),
+            current.status.name()
+        ),
+        |input| match input {
+            "" => Ok(current.status),
+            input => Status::from_name(input),
+        },
+    )?;
+    let percent_complete = match status {
+        Status::NotStarted => 0f64,
+        Status::Done => 100f64,
+        _ => read_valid_input(
+            &format!("Percent complete: [{}]", current.percent_complete),
+            |input| {
+                let percent_complete = match input {
+                    "" => current.percent_complete,
+                    input => input.trim_end_matches('%').parse()?,
+                };
+                Ok(Progress::new(status, percent_complete)?.percent_complete)
+            },
+        )?,
+    };
+
+    perty.set_progress(
+        pert_id,
+        activity.id,
+        &Progress::new(status, percent_complete)?,
+    )?;
+    println!("\"{}\" is {}", activity.name, status);
+    Ok(())
+}
+
+pub fn update_tags(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    let current = activity.tags.join(", ");
+    let tags = read_valid_input(
+        &format!(
+            "Tags, separated by commas, none to clear them: [{}]",
+            current
+        ),
+        |input| match input {
+            "" => Ok(activity.tags.clone()),
+            "none" => Ok(vec![]),
+            input => {
+                let tags = parse_tags(input);
+                validate_tags(&tags)?;
+                Ok(tags)
+            }
+        },
+    )?;
+
+    perty.set_tags(pert_id, activity.id, tags)?;
+    println!("Tags of \"{}\" saved", activity.name);
+    Ok(())
+}
+
+pub fn add_estimate(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    let pert = perty.get_pert(pert_id)?.unwrap();
+    let read_duration = |prompt: &str| -> Result<f64> {
+        read_valid_input(prompt, |input| {
+            let duration = Duration::parse(input, pert.unit, pert.hours_per_day)?;
+            Ok(duration.value)
+        })
+    };
+    let estimator = read_valid_input("Estimator:", |input| match input {
+        "" => bail!("The estimator needs a name"),
+        input => Ok(input.to_string()),
+    })?;
+    println!(
+        "Add estimated cost: (e.g.: 2.5, 4h, 3d 4h, default unit is {})",
+        pert.unit
+    );
+    let estimation = loop {
+        let optimistic = read_duration("Optimistic: ")?;
+        let probable = read_duration("Most probable: ")?;
+        let pessimistic = read_duration("Pessimistic: ")?;
+        match Estimation::new(optimistic, probable, pessimistic) {
+            Ok(estimation) => break estimation,
+            Err(error) => println!("{}, please retry.", error),
+        }
+    };
+    let confidence = read_valid_input("Confidence, from 0 to 1: [1]", |input| {
+        let confidence: f64 = match input {
+            "" => 1f64,
+            input => input.parse()?,
+        };
+        if !(confidence > 0f64 && confidence <= 1f64) {
+            bail!("The confidence must be greater than 0 and at most 1");
         }
+        Ok(confidence)
+    })?;
+
+    perty.add_estimate(pert_id, activity.id, &estimator, estimation, confidence)?;
+    println!(
+        "Estimate of {} for \"{}\" recorded, aggregated with the {}",
+        estimator, activity.name, pert.aggregation
+    );
+    Ok(())
+}
+
+pub fn set_aggregation(mut perty: Perty, pert_id: PertId, aggregation: Aggregation) -> Result<()> {
+    if perty.get_pert(pert_id)?.is_none() {
+        println!("No PERT found with id {}", pert_id);
+        return Ok(());
     }
+    perty.set_aggregation(pert_id, aggregation)?;
+    println!("Estimates aggregated with the {}", aggregation);
+    Ok(())
+}
+
+pub fn get_delphi(mut perty: Perty, pert_id: PertId, threshold: f64, output: Output) -> Result<()> {
+    eprintln!("Comparing the estimators of PERT {}", pert_id);
+    let mut report = perty.get_delphi_reporter(pert_id, threshold)?;
+    match output {
+        Output::Console => println!("{}", report.ascii()),
+        Output::JSON => println!("{}", report.json()?),
+        _ => bail!("Unsupported format for a delphi report"),
+    };
+    Ok(())
+}
+
+pub fn remove_activity(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let activity = match select_activity(&mut perty, pert_id, "Insert the ID of the activity:")? {
+        Some(activity) => activity,
+        None => return Ok(()),
+    };
+    println!(
+        "You are removing \"{}\" and the dependencies from and to it",
+        activity.name
+    );
+    if !confirm()? {
+        println!("The activity has NOT been removed.");
+        return Ok(());
+    }
+    perty.delete_activity(pert_id, activity.id)?;
+    println!("Activity removed");
+    Ok(())
+}
+
+pub fn rename_pert(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let pert = match perty.get_pert(pert_id)? {
+        Some(pert) => pert,
+        None => {
+            println!("No PERT found with id {}", pert_id);
+            return Ok(());
+        }
+    };
+    let name = read_valid_input(&format!("New name: [{}]", pert.name), |input| {
+        if input.is_empty() {
+            bail!("The name cannot be empty");
+        }
+        Ok(input.to_string())
+    })?;
+    println!("You are renaming \"{}\" to \"{}\"", pert.name, name);
+    if !confirm()? {
+        println!("The PERT has NOT been renamed.");
+        return Ok(());
+    }
+    perty.rename_pert(pert_id, &name)?;
+    println!("PERT renamed");
+    Ok(())
+}
+
+pub fn delete_pert(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let pert = match perty.get_pert(pert_id)? {
+        Some(pert) => pert,
+        None => {
+            println!("No PERT found with id {}", pert_id);
+            return Ok(());
+        }
+    };
+    let activities = perty.get_activities(pert_id)?;
+    println!(
+        "You are deleting the PERT \"{}\" with its {} activities and their dependencies",
+        pert.name,
+        activities.len()
+    );
+    if !confirm()? {
+        println!("The PERT has NOT been deleted.");
+        return Ok(());
+    }
+    perty.delete_pert(pert_id)?;
+    println!("PERT deleted");
     Ok(())
 }
 
 pub fn add_activity(mut perty: Perty, pert_id: PertId) -> Result<()> {
-    println!("Add estimated cost:");
+    let pert = match perty.get_pert(pert_id)? {
+        Some(pert) => pert,
+        None => {
+            println!("No PERT found with id {}", pert_id);
+            return Ok(());
+        }
+    };
+    let read_duration = |prompt: &str| -> Result<f64> {
+        read_valid_input(prompt, |input| {
+            let duration = Duration::parse(input, pert.unit, pert.hours_per_day)?;
+            Ok(duration.value)
+        })
+    };
+    println!(
+        "Add estimated cost: (e.g.: 2.5, 4h, 3d 4h, default unit is {})",
+        pert.unit
+    );
     println!("Activity: ");
     let activity_name = read_input()?;
-    println!("Optimistic: ");
-    let optimistic = read_input()?.parse()?;
-    println!("Most probable: ");
-    let probable = read_input()?.parse()?;
-    println!("Pessimistic: ");
-    let pessimistic = read_input()?.parse()?;
-    perty.add_activity(
-        pert_id,
-        activity_name,
-        Estimation {
-            optimistic,
-            probable,
-            pessimistic,
-        },
-    )?;
+    let estimation = loop {
+        let optimistic = read_duration("Optimistic: ")?;
+        let probable = read_duration("Most probable: ")?;
+        let pessimistic = read_duration("Pessimistic: ")?;
+        match Estimation::new(optimistic, probable, pessimistic) {
+            Ok(estimation) => break estimation,
+            Err(error) => println!("{}, please retry.", error),
+        }
+    };
+    perty.add_activity(pert_id, activity_name, estimation)?;
+    Ok(())
+}
+
+pub fn get_accuracy(mut perty: Perty, pert_id: PertId, output: Output) -> Result<()> {
+    eprintln!("Comparing actuals with estimates for PERT {}", pert_id);
+    let mut report = perty.get_accuracy_reporter(pert_id)?;
+    match output {
+        Output::JSON => println!("{}", report.json()?),
+        _ => println!("{}", report.ascii()),
+    };
+    Ok(())
+}
+
+pub fn get_accuracy_overview(mut perty: Perty, output: Output) -> Result<()> {
+    eprintln!("Comparing actuals with estimates for every PERT");
+    let mut report = perty.get_accuracy_overview_reporter()?;
+    match output {
+        Output::JSON => println!("{}", report.json()?),
+        _ => println!("{}", report.ascii()),
+    };
+    Ok(())
+}
+
+pub fn add_baseline(mut perty: Perty, pert_id: PertId, name: &str) -> Result<()> {
+    perty.add_baseline(pert_id, name)?;
+    println!("Baseline {} saved", name.trim());
+    Ok(())
+}
+
+pub fn list_baselines(mut perty: Perty, pert_id: PertId) -> Result<()> {
+    let baselines = perty.get_baselines(pert_id)?;
+    if baselines.is_empty() {
+        println!("No baseline saved for PERT {}", pert_id);
+    } else {
+        println!("{}", baselines_view(&baselines));
+    }
+    Ok(())
+}
+
+pub fn get_baseline_diff(
+    mut perty: Perty,
+    pert_id: PertId,
+    from: &str,
+    to: Option<&str>,
+    output: Output,
+) -> Result<()> {
+    eprintln!("Comparing PERT {} with baseline {}", pert_id, from);
+    let mut report = perty.get_baseline_diff_reporter(pert_id, from, to)?;
+    match output {
+        Output::Console => println!("{}", report.ascii()),
+        Output::HTML => {
+            let file_name = format!("diff-{}.html", pert_id);
+            file_in_browser(file_name, report.html()?)?;
+        }
+        Output::JSON => println!("{}", report.json()?),
+        _ => bail!("Unsupported format for a baseline diff"),
+    };
     Ok(())
 }
 
 pub fn get_roadmap(mut perty: Perty, pert_id: PertId, output: Output) -> Result<()> {
-    println!("Calculating roadmap for PERT {}", pert_id);
+    eprintln!("Calculating roadmap for PERT {}", pert_id);
     let roadmap = perty.get_roadmap(pert_id)?;
-    let mut report = perty.get_roadmap_reporter(roadmap);
+    let mut report = perty.get_roadmap_reporter(pert_id, roadmap)?;
     match output {
         Output::Console => println!("{}", report.ascii()),
-        _ => todo!(),
+        Output::HTML => {
+            let file_name = format!("roadmap-{}.html", pert_id);
+            file_in_browser(file_name, report.html()?)?;
+        }
+        Output::CSV => {
+            let file_name = format!("roadmap-{}.csv", pert_id);
+            create_file(file_name, report.csv())?;
+        }
+        Output::SVG => save_gantt(pert_id, report.svg())?,
+        Output::JSON => println!("{}", report.json()?),
+        Output::Markdown(file_name) => print_or_save(file_name, report.markdown())?,
     };
     Ok(())
 }
 
 pub fn create_board_github(mut perty: Perty) -> Result<()> {
-    list_perts(&mut perty)?;
+    list_perts(&mut perty, Output::Console)?;
     println!("Select a PERT");
     let pert_id: PertId = read_input()?.parse().unwrap();
     println!("Github repository url: (e.g.: https://github.com/dibericky/perty)");